@@ -0,0 +1,18 @@
+use std::process::ExitCode;
+
+use backend::cli::{self, Cli};
+use backend::error::AppError;
+use clap::Parser;
+
+fn main() -> ExitCode {
+    match cli::run(Cli::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            match &e {
+                AppError::Internal(inner) => eprintln!("error: {:#}", inner),
+                other => eprintln!("error: {}", other),
+            }
+            ExitCode::from(e.exit_code())
+        }
+    }
+}
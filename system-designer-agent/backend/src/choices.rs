@@ -0,0 +1,296 @@
+use std::borrow::Cow;
+use std::fmt;
+
+use schemars::{json_schema, JsonSchema, Schema, SchemaGenerator};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use validator::ValidateLength;
+
+/// Lowercase `text` and drop everything but letters and digits, so
+/// "Plan-and-Execute", "plan and execute" and "PlanAndExecute" compare equal
+fn normalize(text: &str) -> String {
+    text.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Lowercase words of `text`, split on anything but letters and digits
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Declares a design field with a known vocabulary. Each known value has a
+/// canonical label, the spellings accepted for it and a description for the
+/// rendered document; anything else is kept verbatim in `Other`. On the wire
+/// the value is always a plain string.
+macro_rules! choices {
+    (
+        $(#[$meta:meta])*
+        pub enum $name:ident {
+            $(
+                $variant:ident => $label:literal,
+                aliases [$($alias:literal),* $(,)?],
+                $description:literal;
+            )*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+        pub enum $name {
+            $($variant,)*
+            /// Free-form text; empty when the field was left blank
+            Other(String),
+        }
+
+        impl $name {
+            /// Canonical labels of the known values
+            pub const KNOWN: &'static [&'static str] = &[$($label),*];
+
+            /// Canonical label of a known value, or the text as given
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $($name::$variant => $label,)*
+                    $name::Other(text) => text,
+                }
+            }
+
+            /// What the known value means for the design; `None` for `Other`
+            pub fn description(&self) -> Option<&'static str> {
+                match self {
+                    $($name::$variant => Some($description),)*
+                    $name::Other(_) => None,
+                }
+            }
+
+            /// True when the field was left blank
+            pub fn is_blank(&self) -> bool {
+                self.as_str().trim().is_empty()
+            }
+
+            /// True for values outside the known vocabulary, blank excluded
+            pub fn is_custom(&self) -> bool {
+                matches!(self, $name::Other(text) if !text.trim().is_empty())
+            }
+        }
+
+        impl From<&str> for $name {
+            fn from(text: &str) -> Self {
+                let key = normalize(text);
+                if !key.is_empty() {
+                    $(
+                        if key == normalize($label) $(|| key == normalize($alias))* {
+                            return $name::$variant;
+                        }
+                    )*
+                }
+                $name::Other(text.to_string())
+            }
+        }
+
+        impl From<String> for $name {
+            fn from(text: String) -> Self {
+                match $name::from(text.as_str()) {
+                    $name::Other(_) => $name::Other(text),
+                    known => known,
+                }
+            }
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                $name::Other(String::new())
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(self.as_str())
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                String::deserialize(deserializer).map($name::from)
+            }
+        }
+
+        impl ValidateLength<u64> for $name {
+            fn length(&self) -> Option<u64> {
+                Some(self.as_str().chars().count() as u64)
+            }
+        }
+
+        impl JsonSchema for $name {
+            fn inline_schema() -> bool {
+                true
+            }
+
+            fn schema_name() -> Cow<'static, str> {
+                stringify!($name).into()
+            }
+
+            fn json_schema(_: &mut SchemaGenerator) -> Schema {
+                json_schema!({
+                    "type": "string",
+                    "description": "One of the known values in `examples`, matched ignoring case and punctuation, or free-form text",
+                    "examples": $name::KNOWN,
+                })
+            }
+        }
+    };
+}
+
+choices! {
+    /// The LLM the agent runs on
+    pub enum BaseModel {
+        ClaudeSonnet => "Claude 3.5 Sonnet",
+            aliases ["claude-3-5-sonnet", "claude-3-5-sonnet-latest", "Claude 3.5", "Sonnet"],
+            "Anthropic's balanced model: strong reasoning and tool use at moderate cost, 200k token context.";
+        ClaudeHaiku => "Claude 3.5 Haiku",
+            aliases ["claude-3-5-haiku", "claude-3-5-haiku-latest", "Haiku"],
+            "Anthropic's fastest model, suited to high-volume routing, classification and extraction, 200k token context.";
+        ClaudeOpus => "Claude 3 Opus",
+            aliases ["claude-3-opus", "claude-3-opus-latest", "Opus"],
+            "Anthropic's most capable model of its generation for long, open-ended tasks; the slowest and most expensive option, 200k token context.";
+        Gpt4o => "GPT-4o",
+            aliases ["gpt4o", "gpt-4o-latest", "chatgpt-4o-latest"],
+            "OpenAI's multimodal flagship with native function calling and a 128k token context.";
+        Gpt4oMini => "GPT-4o mini",
+            aliases ["gpt-4o-mini", "gpt4o-mini"],
+            "OpenAI's low-cost model for simple tool calls and high-throughput workloads, 128k token context.";
+        Gpt4 => "GPT-4",
+            aliases ["gpt4", "gpt-4-0613"],
+            "OpenAI's original GPT-4: capable but slower and pricier than GPT-4o, with an 8k token context.";
+        GeminiPro => "Gemini 1.5 Pro",
+            aliases ["gemini-1.5-pro", "gemini-1.5-pro-latest", "Gemini Pro"],
+            "Google's long-context model, up to 2M tokens, for whole-repository or large-document reasoning.";
+        GeminiFlash => "Gemini 1.5 Flash",
+            aliases ["gemini-1.5-flash", "gemini-1.5-flash-latest", "Gemini Flash"],
+            "Google's fast, inexpensive model with a 1M token context.";
+        Llama => "Llama 3",
+            aliases ["llama-3", "llama3", "meta-llama-3"],
+            "Meta's open-weight family, self-hostable for data residency or cost control; tool use varies with model size.";
+        MistralLarge => "Mistral Large",
+            aliases ["mistral-large", "mistral-large-latest"],
+            "Mistral AI's flagship with function calling, available as open weights for self-hosting, 128k token context.";
+    }
+}
+
+choices! {
+    /// How the orchestrator sequences model calls and tools
+    pub enum WorkflowPattern {
+        ReAct => "ReAct",
+            aliases ["Re-Act", "reason and act", "reason-act"],
+            "A single loop that interleaves reasoning with tool calls and feeds each observation back into the next step.";
+        PlanAndExecute => "Plan-and-Execute",
+            aliases ["plan and execute", "plan & execute", "plan-execute", "planner-executor"],
+            "A planner drafts the steps up front and an executor works through them, replanning when a step fails.";
+        Router => "Router",
+            aliases ["routing", "intent router", "classifier"],
+            "The model classifies each request and hands it to the one tool or sub-agent best suited to it.";
+        SupervisorWorker => "Supervisor-Worker",
+            aliases ["supervisor worker", "supervisor", "orchestrator-worker", "hierarchical", "multi-agent"],
+            "A supervisor splits the task, delegates the parts to specialised workers and combines their reports.";
+        Sequential => "Sequential",
+            aliases ["chain", "prompt chaining", "pipeline", "sequential chain"],
+            "A fixed chain of steps where each model or tool call consumes the previous output.";
+    }
+}
+
+impl WorkflowPattern {
+    /// The known pattern the workflow follows. Free-form text such as
+    /// "Hierarchical multi-agent" is matched on whole keywords, so "reactive"
+    /// is not ReAct; `None` when nothing fits.
+    pub fn pattern(&self) -> Option<WorkflowPattern> {
+        let WorkflowPattern::Other(text) = self else {
+            return Some(self.clone());
+        };
+
+        let words = words(text);
+        // A keyword of several words must appear as a run, e.g. "plan and execute"
+        let has = |keywords: &[&str]| {
+            keywords.iter().any(|keyword| {
+                let keyword = self::words(keyword);
+                words.windows(keyword.len()).any(|run| run == keyword)
+            })
+        };
+
+        if has(&["plan and execute", "planner", "planning"]) {
+            Some(WorkflowPattern::PlanAndExecute)
+        } else if has(&[
+            "supervisor",
+            "worker",
+            "workers",
+            "hierarchical",
+            "hierarchy",
+            "multi agent",
+        ]) {
+            Some(WorkflowPattern::SupervisorWorker)
+        } else if has(&["router", "routing"]) {
+            Some(WorkflowPattern::Router)
+        } else if has(&["sequential", "chain", "chained", "chaining", "pipeline"]) {
+            Some(WorkflowPattern::Sequential)
+        } else if has(&["react", "re act"]) {
+            Some(WorkflowPattern::ReAct)
+        } else {
+            None
+        }
+    }
+}
+
+choices! {
+    /// Where users reach the agent
+    pub enum Platform {
+        Web => "Web",
+            aliases ["web app", "webapp", "website", "browser", "web ui"],
+            "A browser front end; plan for session handling, streaming responses and accessibility.";
+        Slack => "Slack",
+            aliases ["slack bot", "slackbot", "slack app"],
+            "A Slack app answering in channels and DMs; replies must arrive within Slack's 3 second acknowledgement window.";
+        Teams => "Microsoft Teams",
+            aliases ["teams", "ms teams", "teams bot"],
+            "A Teams bot registered through Azure Bot Service, rendering Adaptive Cards.";
+        Discord => "Discord",
+            aliases ["discord bot"],
+            "A Discord bot reacting to slash commands and messages within gateway rate limits.";
+        Cli => "CLI",
+            aliases ["command line", "command-line", "terminal", "shell"],
+            "A terminal tool for developers; output should be scriptable and work without a TTY.";
+        Api => "API",
+            aliases ["rest api", "http api", "headless"],
+            "A headless service other systems call directly; needs authentication, versioning and quotas.";
+        Mobile => "Mobile",
+            aliases ["mobile app", "ios", "android"],
+            "A native or hybrid mobile app; budget for flaky networks and keep responses short.";
+    }
+}
+
+choices! {
+    /// How the conversation between user and agent unfolds
+    pub enum InteractionMode {
+        Chat => "Chat",
+            aliases ["conversational", "chatbot", "chat bot", "messaging"],
+            "Turn-based conversation where the user drives and the agent keeps context across turns.";
+        Voice => "Voice",
+            aliases ["speech", "voice assistant", "phone", "ivr"],
+            "Spoken input and output; latency budgets are tight and replies must read well aloud.";
+        Proactive => "Proactive",
+            aliases ["notifications", "push", "agent-initiated"],
+            "The agent starts the exchange when an event or schedule fires and notifies the user.";
+        Batch => "Batch",
+            aliases ["offline", "bulk", "scheduled jobs"],
+            "Inputs are processed in bulk without a user waiting; throughput matters more than latency.";
+        Autonomous => "Autonomous",
+            aliases ["background agent", "unattended", "autopilot"],
+            "The agent works through long tasks without supervision and reports when done or blocked.";
+    }
+}
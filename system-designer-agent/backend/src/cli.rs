@@ -0,0 +1,269 @@
+use std::fs;
+use std::io::{self, Read, Write};
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+
+use crate::config::{Config, ConfigSources};
+use crate::error::AppError;
+use crate::listener::Listen;
+use crate::middleware::auth::hash_api_key;
+use crate::migrate;
+use crate::spec::{DesignSpec, OutputFormat};
+use crate::{core_generate_design, lint, server, tui, validate_design_request, DesignRequest};
+
+/// Headless design generation for CI pipelines and scripts
+#[derive(Parser, Debug)]
+#[command(name = "system-designer", version, about)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Generate a design document from a request
+    Generate {
+        /// Request file (JSON or YAML); reads stdin when omitted or `-`
+        input: Option<PathBuf>,
+        /// Request format; guessed from the file extension or content by default
+        #[arg(long, value_enum)]
+        input_format: Option<InputFormat>,
+        /// Output format: `markdown`, `json` or `yaml`
+        #[arg(short, long, default_value = "markdown")]
+        format: String,
+        /// Output file; writes stdout when omitted
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Validate a request and report lint findings
+    Validate {
+        /// Request file (JSON or YAML); reads stdin when omitted or `-`
+        input: Option<PathBuf>,
+        /// Request format; guessed from the file extension or content by default
+        #[arg(long, value_enum)]
+        input_format: Option<InputFormat>,
+    },
+    /// Fill in a design interactively in the terminal
+    Wizard {
+        /// Request file (JSON or YAML) to start from
+        #[arg(long)]
+        input: Option<PathBuf>,
+        /// Request format; guessed from the file extension or content by default
+        #[arg(long, value_enum)]
+        input_format: Option<InputFormat>,
+        /// Where the rendered spec is saved
+        #[arg(short, long, default_value = "DESIGN_SPEC.md")]
+        output: PathBuf,
+    },
+    /// Print the hash of an API key for the auth configuration
+    HashKey {
+        /// The API key; read from stdin when omitted
+        key: Option<String>,
+    },
+    /// Run the HTTP API
+    Serve {
+        /// Overrides `APP_HOST`
+        #[arg(long)]
+        host: Option<IpAddr>,
+        /// Overrides `APP_PORT`
+        #[arg(long)]
+        port: Option<u16>,
+        /// Overrides `APP_LISTEN`: `tcp`, `unix:<path>` or `systemd`
+        #[arg(long)]
+        listen: Option<Listen>,
+        #[command(flatten)]
+        config: ConfigArgs,
+    },
+}
+
+/// Configuration layers shared by `serve` and the `backend` binary
+#[derive(Args, Debug, Clone, Default)]
+pub struct ConfigArgs {
+    /// TOML configuration file; overrides `APP_CONFIG`
+    #[arg(long)]
+    pub config: Option<PathBuf>,
+    /// Override one setting, e.g. `--set rate_limit_burst=50`; repeatable
+    #[arg(long = "set", value_name = "KEY=VALUE", value_parser = parse_setting)]
+    pub set: Vec<(String, String)>,
+    /// Print the effective configuration with credentials masked, then exit
+    #[arg(long)]
+    pub print_config: bool,
+}
+
+impl ConfigArgs {
+    /// Layers for [`Config::load`]: the file, the environment, then `--set`
+    /// and `extra` flags
+    pub fn sources(&self, extra: Vec<(String, String)>) -> ConfigSources {
+        let mut flags = self.set.clone();
+        flags.extend(extra);
+        ConfigSources::from_process(self.config.clone(), flags)
+    }
+}
+
+fn parse_setting(arg: &str) -> Result<(String, String), String> {
+    arg.split_once('=')
+        .map(|(key, value)| (key.trim().to_string(), value.to_string()))
+        .ok_or_else(|| format!("expected KEY=VALUE, got {:?}", arg))
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputFormat {
+    Json,
+    Yaml,
+}
+
+impl InputFormat {
+    fn detect(path: Option<&Path>, source: &str) -> Self {
+        let extension = path
+            .and_then(|p| p.extension())
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_ascii_lowercase());
+
+        match extension.as_deref() {
+            Some("json") => InputFormat::Json,
+            Some("yaml") | Some("yml") => InputFormat::Yaml,
+            _ if source.trim_start().starts_with('{') => InputFormat::Json,
+            _ => InputFormat::Yaml,
+        }
+    }
+}
+
+/// Parse a `DesignRequest` from JSON or YAML source, upgrading older
+/// schema versions
+pub fn parse_request(source: &str, format: InputFormat) -> Result<DesignRequest, AppError> {
+    let value: serde_json::Value = match format {
+        InputFormat::Json => serde_json::from_str(source)
+            .map_err(|e| AppError::BadRequest(format!("Invalid JSON request: {}", e)))?,
+        InputFormat::Yaml => serde_yaml::from_str(source)
+            .map_err(|e| AppError::BadRequest(format!("Invalid YAML request: {}", e)))?,
+    };
+    migrate::design_request(value)
+}
+
+fn read_request(
+    input: Option<&Path>,
+    format: Option<InputFormat>,
+) -> Result<DesignRequest, AppError> {
+    let input = input.filter(|p| p.as_os_str() != "-");
+    let source = match input {
+        Some(path) => fs::read_to_string(path)
+            .map_err(|e| AppError::NotFound(format!("Cannot read {}: {}", path.display(), e)))?,
+        None => {
+            let mut source = String::new();
+            io::stdin()
+                .read_to_string(&mut source)
+                .map_err(|e| AppError::BadRequest(format!("Cannot read stdin: {}", e)))?;
+            source
+        }
+    };
+
+    let format = format.unwrap_or_else(|| InputFormat::detect(input, &source));
+    let request = parse_request(&source, format)?;
+    validate_design_request(&request).map_err(AppError::Validation)?;
+    Ok(request)
+}
+
+fn write_output(output: Option<&Path>, body: &str) -> Result<(), AppError> {
+    match output {
+        Some(path) => fs::write(path, body).map_err(|e| {
+            AppError::Internal(anyhow::anyhow!("Cannot write {}: {}", path.display(), e))
+        }),
+        None => io::stdout()
+            .write_all(body.as_bytes())
+            .map_err(|e| AppError::Internal(e.into())),
+    }
+}
+
+/// Execute a parsed command line
+pub fn run(cli: Cli) -> Result<(), AppError> {
+    match cli.command {
+        Command::Generate {
+            input,
+            input_format,
+            format,
+            output,
+        } => {
+            let format: OutputFormat = format.parse()?;
+            let request = read_request(input.as_deref(), input_format)?;
+            let body = match format {
+                OutputFormat::Markdown => core_generate_design(&request),
+                OutputFormat::Json => DesignSpec::from_request(&request).to_json()?,
+                OutputFormat::Yaml => DesignSpec::from_request(&request).to_yaml()?,
+            };
+            write_output(output.as_deref(), &body)
+        }
+        Command::Validate {
+            input,
+            input_format,
+        } => {
+            let request = read_request(input.as_deref(), input_format)?;
+            for finding in lint::builtin().lint(&request) {
+                eprintln!(
+                    "{}: {} [{}] {}",
+                    finding.severity, finding.path, finding.rule_id, finding.message
+                );
+            }
+            println!("{} is valid", request.title());
+            Ok(())
+        }
+        Command::Wizard {
+            input,
+            input_format,
+            output,
+        } => {
+            let request = match input {
+                Some(path) => read_request(Some(&path), input_format)?,
+                None => DesignRequest::default(),
+            };
+            tui::run(request, output)
+        }
+        Command::HashKey { key } => {
+            let key = match key {
+                Some(key) => key,
+                None => {
+                    let mut key = String::new();
+                    io::stdin()
+                        .read_line(&mut key)
+                        .map_err(|e| AppError::BadRequest(format!("Cannot read stdin: {}", e)))?;
+                    key.trim_end_matches(['\r', '\n']).to_string()
+                }
+            };
+            if key.is_empty() {
+                return Err(AppError::BadRequest("API key is empty".to_string()));
+            }
+            println!("{}", hash_api_key(&key));
+            Ok(())
+        }
+        Command::Serve {
+            host,
+            port,
+            listen,
+            config,
+        } => {
+            let print_config = config.print_config;
+            let mut extra = Vec::new();
+            if let Some(host) = host {
+                extra.push(("host".to_string(), host.to_string()));
+            }
+            if let Some(port) = port {
+                extra.push(("port".to_string(), port.to_string()));
+            }
+            if let Some(listen) = listen {
+                extra.push(("listen".to_string(), listen.to_string()));
+            }
+            let sources = config.sources(extra);
+            let config = Config::load(&sources)?;
+            if print_config {
+                print!("{}", config.to_masked_toml()?);
+                return Ok(());
+            }
+
+            tokio::runtime::Runtime::new()
+                .map_err(|e| AppError::Internal(e.into()))?
+                .block_on(server::run(config, sources))
+                .map_err(AppError::Internal)
+        }
+    }
+}
@@ -1,83 +1,509 @@
-use std::net::IpAddr;
 use std::env;
+use std::fmt;
+use std::fs;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use axum::http::HeaderValue;
+use regex::Regex;
+use serde::Serialize;
+use toml::Value;
+use tracing_subscriber::EnvFilter;
+
+use crate::error::AppError;
+use crate::listener::{Listen, SocketMode};
+use crate::logging::LogFormat;
+use crate::redact::MASK;
+
+/// Environment variable naming the TOML configuration file
+pub const CONFIG_FILE_ENV: &str = "APP_CONFIG";
+
+/// Every setting, by its key in the TOML file. The environment variable is
+/// the key upper-cased with an `APP_` prefix, e.g. `APP_RATE_LIMIT_BURST`.
+pub const KEYS: &[&str] = &[
+    "listen",
+    "host",
+    "port",
+    "unix_socket_mode",
+    "cors_origins",
+    "log_level",
+    "log_format",
+    "log_redact_fields",
+    "log_redact_patterns",
+    "otlp_endpoint",
+    "trace_file",
+    "max_body_size",
+    "rate_limit_per_second",
+    "rate_limit_burst",
+    "health_rate_limit_per_second",
+    "health_rate_limit_burst",
+    "trusted_proxies",
+    "metrics_enabled",
+    "metrics_port",
+    "template_dir",
+    "database_path",
+    "lint_config",
+    "workspace_dir",
+    "auth_config",
+    "tls_cert",
+    "tls_key",
+    "tls_client_ca",
+    "pre_stop_delay_secs",
+    "drain_timeout_secs",
+];
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Config {
+    /// `tcp` for `host`:`port`, `unix:<path>`, or `systemd` for a socket
+    /// passed by systemd socket activation
+    pub listen: Listen,
     pub host: IpAddr,
     pub port: u16,
+    /// Permissions of the `unix:` socket file, in octal
+    pub unix_socket_mode: SocketMode,
     pub cors_origins: Vec<String>,
     pub log_level: String,
+    pub log_format: LogFormat,
+    /// Log fields masked in addition to `redact::DEFAULT_FIELDS`
+    pub log_redact_fields: Vec<String>,
+    /// Regexes masked in every log line, in addition to the built-in email,
+    /// API key and token patterns
+    pub log_redact_patterns: Vec<String>,
+    /// OTLP/HTTP traces endpoint, e.g. `http://localhost:4318/v1/traces`
+    pub otlp_endpoint: Option<String>,
+    /// Append finished spans to this file as JSON lines
+    pub trace_file: Option<PathBuf>,
     pub max_body_size: usize,
     pub rate_limit_per_second: u32,
     pub rate_limit_burst: u32,
+    /// Separate quota for `/health` and `/ready`
+    pub health_rate_limit_per_second: u32,
+    pub health_rate_limit_burst: u32,
+    /// Proxies whose `X-Forwarded-For` header is trusted for client addresses
+    pub trusted_proxies: Vec<IpAddr>,
+    /// Serve Prometheus metrics at `/metrics`
+    pub metrics_enabled: bool,
+    /// Serve `/metrics` on this port instead of the API port
+    pub metrics_port: Option<u16>,
+    pub template_dir: Option<PathBuf>,
+    pub database_path: Option<PathBuf>,
+    pub lint_config: Option<PathBuf>,
+    /// Per-workspace overrides: `<dir>/<workspace>/templates/` and
+    /// `<dir>/<workspace>/lint.toml`
+    pub workspace_dir: Option<PathBuf>,
+    /// API keys and JWT settings; the API is unauthenticated when unset
+    pub auth_config: Option<PathBuf>,
+    /// PEM certificate chain; the API is served over HTTPS when this and
+    /// `tls_key` are set
+    pub tls_cert: Option<PathBuf>,
+    /// PEM private key for `tls_cert`
+    pub tls_key: Option<PathBuf>,
+    /// PEM CA bundle; when set, clients must present a certificate it signed
+    pub tls_client_ca: Option<PathBuf>,
+    /// On shutdown, report not ready on `/ready` and keep serving this long
+    /// so load balancers stop routing here before draining starts
+    pub pre_stop_delay_secs: u64,
+    /// Longest wait for in-flight requests once draining starts
+    pub drain_timeout_secs: u64,
 }
 
-impl Config {
-    pub fn from_env() -> Result<Self, String> {
-        // Load .env file if present
+/// Layers applied over the built-in defaults, lowest precedence first: the
+/// TOML file, then `APP_*` variables, then command line overrides
+#[derive(Debug, Clone, Default)]
+pub struct ConfigSources {
+    /// TOML file; `APP_CONFIG` from `env` is used when unset
+    pub file: Option<PathBuf>,
+    /// Environment variables
+    pub env: Vec<(String, String)>,
+    /// `key = value` overrides, e.g. from `--set port=8080`
+    pub flags: Vec<(String, String)>,
+}
+
+impl ConfigSources {
+    /// The process environment, after loading `.env` if present
+    pub fn from_process(file: Option<PathBuf>, flags: Vec<(String, String)>) -> Self {
         let _ = dotenvy::dotenv();
+        ConfigSources {
+            file,
+            env: env::vars().collect(),
+            flags,
+        }
+    }
 
-        let host: IpAddr = env::var("APP_HOST")
-            .unwrap_or_else(|_| "127.0.0.1".to_string())
-            .parse()
-            .map_err(|_| "Invalid APP_HOST")?;
-
-        let port: u16 = env::var("APP_PORT")
-            .unwrap_or_else(|_| "3000".to_string())
-            .parse()
-            .map_err(|_| "Invalid APP_PORT")?;
-
-        let cors_origins: Vec<String> = env::var("APP_CORS_ORIGINS")
-            .unwrap_or_else(|_| "http://localhost:5173,http://localhost:1420".to_string())
-            .split(',')
-            .map(|s| s.trim().to_string())
-            .filter(|s| !s.is_empty())
-            .collect();
-
-        let log_level = env::var("APP_LOG_LEVEL").unwrap_or_else(|_| "info".to_string());
-
-        let max_body_size: usize = env::var("APP_MAX_BODY_SIZE")
-            .unwrap_or_else(|_| "1048576".to_string()) // 1MB default
-            .parse()
-            .map_err(|_| "Invalid APP_MAX_BODY_SIZE")?;
-
-        let rate_limit_per_second: u32 = env::var("APP_RATE_LIMIT_PER_SECOND")
-            .unwrap_or_else(|_| "10".to_string())
-            .parse()
-            .map_err(|_| "Invalid APP_RATE_LIMIT_PER_SECOND")?;
-
-        let rate_limit_burst: u32 = env::var("APP_RATE_LIMIT_BURST")
-            .unwrap_or_else(|_| "20".to_string())
-            .parse()
-            .map_err(|_| "Invalid APP_RATE_LIMIT_BURST")?;
-
-        Ok(Config {
-            host,
-            port,
-            cors_origins,
-            log_level,
-            max_body_size,
-            rate_limit_per_second,
-            rate_limit_burst,
+    /// The TOML file to read: `file`, else `APP_CONFIG`
+    pub fn config_file(&self) -> Option<PathBuf> {
+        self.file.clone().or_else(|| {
+            self.env_var(CONFIG_FILE_ENV)
+                .filter(|s| !s.trim().is_empty())
+                .map(PathBuf::from)
         })
     }
 
+    fn env_var(&self, name: &str) -> Option<&str> {
+        self.env
+            .iter()
+            .rev()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Every problem found while loading the configuration
+#[derive(Debug)]
+pub struct ConfigError(pub Vec<String>);
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid configuration")?;
+        for error in &self.0 {
+            write!(f, "\n  - {}", error)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl From<ConfigError> for AppError {
+    fn from(e: ConfigError) -> Self {
+        AppError::Config(e.to_string())
+    }
+}
+
+/// Where a value came from, so errors point at the right place
+enum Origin<'a> {
+    File(&'a Path),
+    Env(&'a str),
+    Flag,
+}
+
+impl fmt::Display for Origin<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Origin::File(path) => write!(f, "{}", path.display()),
+            Origin::Env(name) => write!(f, "{}", name),
+            Origin::Flag => write!(f, "--set"),
+        }
+    }
+}
+
+impl Config {
+    /// Built-in defaults overlaid with the process environment
+    pub fn from_env() -> Result<Self, ConfigError> {
+        Config::load(&ConfigSources::from_process(None, Vec::new()))
+    }
+
+    /// Apply every layer in `sources` over the defaults, then validate.
+    /// Nothing is reported until all layers are read, so one run shows
+    /// every bad setting.
+    pub fn load(sources: &ConfigSources) -> Result<Self, ConfigError> {
+        let mut config = Config::default();
+        let mut errors = Vec::new();
+
+        if let Some(path) = &sources.config_file() {
+            match fs::read_to_string(path)
+                .map_err(|e| e.to_string())
+                .and_then(|source| source.parse::<toml::Table>().map_err(|e| e.to_string()))
+            {
+                Ok(table) => {
+                    for (key, value) in &table {
+                        config.apply(key, value, Origin::File(path), &mut errors);
+                    }
+                }
+                Err(e) => errors.push(format!("cannot load {}: {}", path.display(), e)),
+            }
+        }
+
+        for key in KEYS {
+            let name = format!("APP_{}", key.to_ascii_uppercase());
+            if let Some(value) = sources.env_var(&name) {
+                let value = Value::String(value.to_string());
+                config.apply(key, &value, Origin::Env(&name), &mut errors);
+            }
+        }
+
+        for (key, value) in &sources.flags {
+            let value = Value::String(value.clone());
+            config.apply(key, &value, Origin::Flag, &mut errors);
+        }
+
+        errors.extend(config.validate());
+        if errors.is_empty() {
+            Ok(config)
+        } else {
+            Err(ConfigError(errors))
+        }
+    }
+
+    fn apply(&mut self, key: &str, value: &Value, origin: Origin<'_>, errors: &mut Vec<String>) {
+        if let Err(e) = self.set(key, value) {
+            errors.push(format!("{} (from {}): {}", key, origin, e));
+        }
+    }
+
+    /// Set one setting from a TOML value or a string
+    pub fn set(&mut self, key: &str, value: &Value) -> Result<(), String> {
+        match key {
+            "listen" => self.listen = scalar(value)?,
+            "host" => self.host = scalar(value)?,
+            "port" => self.port = scalar(value)?,
+            "unix_socket_mode" => self.unix_socket_mode = scalar(value)?,
+            "cors_origins" => self.cors_origins = list(value, ",")?,
+            "log_level" => self.log_level = scalar(value)?,
+            "log_format" => self.log_format = scalar(value)?,
+            "log_redact_fields" => self.log_redact_fields = list(value, ",")?,
+            // Whitespace separated, since patterns may contain commas
+            "log_redact_patterns" => self.log_redact_patterns = list(value, " ")?,
+            "otlp_endpoint" => self.otlp_endpoint = optional(value)?,
+            "trace_file" => self.trace_file = optional(value)?,
+            "max_body_size" => self.max_body_size = scalar(value)?,
+            "rate_limit_per_second" => self.rate_limit_per_second = scalar(value)?,
+            "rate_limit_burst" => self.rate_limit_burst = scalar(value)?,
+            "health_rate_limit_per_second" => self.health_rate_limit_per_second = scalar(value)?,
+            "health_rate_limit_burst" => self.health_rate_limit_burst = scalar(value)?,
+            "trusted_proxies" => self.trusted_proxies = list(value, ",")?,
+            "metrics_enabled" => self.metrics_enabled = scalar(value)?,
+            "metrics_port" => self.metrics_port = optional(value)?,
+            "template_dir" => self.template_dir = optional(value)?,
+            "database_path" => self.database_path = optional(value)?,
+            "lint_config" => self.lint_config = optional(value)?,
+            "workspace_dir" => self.workspace_dir = optional(value)?,
+            "auth_config" => self.auth_config = optional(value)?,
+            "tls_cert" => self.tls_cert = optional(value)?,
+            "tls_key" => self.tls_key = optional(value)?,
+            "tls_client_ca" => self.tls_client_ca = optional(value)?,
+            "pre_stop_delay_secs" => self.pre_stop_delay_secs = scalar(value)?,
+            "drain_timeout_secs" => self.drain_timeout_secs = scalar(value)?,
+            _ => return Err("unknown setting".to_string()),
+        }
+        Ok(())
+    }
+
+    /// Checks that need the whole configuration, or more than parsing
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+
+        for origin in &self.cors_origins {
+            let scheme = origin.starts_with("http://") || origin.starts_with("https://");
+            if !scheme || HeaderValue::from_str(origin).is_err() {
+                errors.push(format!("cors_origins: invalid origin {:?}", origin));
+            }
+        }
+        if let Err(e) = EnvFilter::try_new(&self.log_level) {
+            errors.push(format!("log_level: {}", e));
+        }
+        for pattern in &self.log_redact_patterns {
+            if let Err(e) = Regex::new(pattern) {
+                errors.push(format!("log_redact_patterns: {}", e));
+            }
+        }
+        if let Some(endpoint) = &self.otlp_endpoint {
+            if !endpoint.starts_with("http://") && !endpoint.starts_with("https://") {
+                errors.push(format!(
+                    "otlp_endpoint: not an http(s) URL: {:?}",
+                    mask_url(endpoint)
+                ));
+            }
+        }
+        if let Listen::Unix(path) = &self.listen {
+            let parent = path.parent().filter(|p| !p.as_os_str().is_empty());
+            if parent.is_some_and(|dir| !dir.is_dir()) {
+                errors.push(format!(
+                    "listen: directory of {} does not exist",
+                    path.display()
+                ));
+            }
+        }
+        if self.max_body_size == 0 {
+            errors.push("max_body_size: must be greater than zero".to_string());
+        }
+        if self.metrics_enabled && self.metrics_port == Some(self.port) {
+            errors.push(format!(
+                "metrics_port: {} is already the API port",
+                self.port
+            ));
+        }
+
+        for (key, dir) in [
+            ("template_dir", &self.template_dir),
+            ("workspace_dir", &self.workspace_dir),
+        ] {
+            if let Some(dir) = dir.as_ref().filter(|dir| !dir.is_dir()) {
+                errors.push(format!("{}: {} is not a directory", key, dir.display()));
+            }
+        }
+        for (key, file) in [
+            ("lint_config", &self.lint_config),
+            ("auth_config", &self.auth_config),
+            ("tls_cert", &self.tls_cert),
+            ("tls_key", &self.tls_key),
+            ("tls_client_ca", &self.tls_client_ca),
+        ] {
+            if let Some(file) = file.as_ref().filter(|file| !file.is_file()) {
+                errors.push(format!("{}: {} is not a file", key, file.display()));
+            }
+        }
+        if self.tls_cert.is_some() != self.tls_key.is_some() {
+            errors.push("tls_cert and tls_key must be set together".to_string());
+        }
+        if self.tls_client_ca.is_some() && self.tls_cert.is_none() {
+            errors.push("tls_client_ca: requires tls_cert and tls_key".to_string());
+        }
+
+        errors
+    }
+
+    /// The effective configuration as TOML, with credentials masked
+    pub fn to_masked_toml(&self) -> Result<String, AppError> {
+        let mut shown = self.clone();
+        shown.otlp_endpoint = shown.otlp_endpoint.as_deref().map(mask_url);
+        toml::to_string(&shown)
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Cannot print configuration: {}", e)))
+    }
+
     /// Default configuration for testing
     pub fn default_test() -> Self {
         Config {
+            listen: Listen::Tcp,
             host: "127.0.0.1".parse().unwrap(),
             port: 3000,
+            unix_socket_mode: SocketMode::default(),
             cors_origins: vec!["http://localhost:5173".to_string()],
             log_level: "debug".to_string(),
+            log_format: LogFormat::Text,
+            log_redact_fields: Vec::new(),
+            log_redact_patterns: Vec::new(),
+            otlp_endpoint: None,
+            trace_file: None,
             max_body_size: 1024 * 1024,
             rate_limit_per_second: 100,
             rate_limit_burst: 200,
+            health_rate_limit_per_second: 100,
+            health_rate_limit_burst: 200,
+            trusted_proxies: Vec::new(),
+            metrics_enabled: true,
+            metrics_port: None,
+            template_dir: None,
+            database_path: None,
+            lint_config: None,
+            workspace_dir: None,
+            auth_config: None,
+            tls_cert: None,
+            tls_key: None,
+            tls_client_ca: None,
+            pre_stop_delay_secs: 0,
+            drain_timeout_secs: 30,
         }
     }
 }
 
+/// Built-in defaults; the environment is not consulted
 impl Default for Config {
     fn default() -> Self {
-        Config::from_env().unwrap_or_else(|_| Config::default_test())
+        Config {
+            listen: Listen::Tcp,
+            host: "127.0.0.1".parse().unwrap(),
+            port: 3000,
+            unix_socket_mode: SocketMode::default(),
+            cors_origins: vec![
+                "http://localhost:5173".to_string(),
+                "http://localhost:1420".to_string(),
+            ],
+            log_level: "info".to_string(),
+            log_format: LogFormat::Text,
+            log_redact_fields: Vec::new(),
+            log_redact_patterns: Vec::new(),
+            otlp_endpoint: None,
+            trace_file: None,
+            max_body_size: 1024 * 1024, // 1MB
+            rate_limit_per_second: 10,
+            rate_limit_burst: 20,
+            health_rate_limit_per_second: 50,
+            health_rate_limit_burst: 100,
+            trusted_proxies: Vec::new(),
+            metrics_enabled: true,
+            metrics_port: None,
+            template_dir: None,
+            database_path: None,
+            lint_config: None,
+            workspace_dir: None,
+            auth_config: None,
+            tls_cert: None,
+            tls_key: None,
+            tls_client_ca: None,
+            pre_stop_delay_secs: 0,
+            drain_timeout_secs: 30,
+        }
+    }
+}
+
+/// A single value: a TOML scalar, or a string from the environment or flags
+fn scalar<T: FromStr>(value: &Value) -> Result<T, String> {
+    let text = match value {
+        Value::String(s) => s.trim().to_string(),
+        Value::Integer(i) => i.to_string(),
+        Value::Boolean(b) => b.to_string(),
+        Value::Float(f) => f.to_string(),
+        other => {
+            return Err(format!(
+                "expected a single value, found {}",
+                other.type_str()
+            ))
+        }
+    };
+    text.parse()
+        .map_err(|_| format!("invalid value {:?}", text))
+}
+
+/// A TOML array, or a string split on `separator` (`" "` for whitespace)
+fn list<T: FromStr>(value: &Value, separator: &str) -> Result<Vec<T>, String> {
+    match value {
+        Value::Array(items) => items.iter().map(scalar).collect(),
+        Value::String(s) => {
+            let items: Vec<&str> = if separator == " " {
+                s.split_whitespace().collect()
+            } else {
+                s.split(separator)
+                    .map(str::trim)
+                    .filter(|s| !s.is_empty())
+                    .collect()
+            };
+            items
+                .into_iter()
+                .map(|item| {
+                    item.parse()
+                        .map_err(|_| format!("invalid value {:?}", item))
+                })
+                .collect()
+        }
+        other => Err(format!("expected a list, found {}", other.type_str())),
+    }
+}
+
+/// Like [`scalar`], with an empty string meaning unset
+fn optional<T: FromStr>(value: &Value) -> Result<Option<T>, String> {
+    match value {
+        Value::String(s) if s.trim().is_empty() => Ok(None),
+        value => scalar(value).map(Some),
     }
 }
+
+/// Hide the user info and query of a URL, which may carry credentials
+pub fn mask_url(url: &str) -> String {
+    let (url, query) = match url.split_once('?') {
+        Some((url, _)) => (url, format!("?{}", MASK)),
+        None => (url, String::new()),
+    };
+    let masked = match url.split_once("://") {
+        Some((scheme, rest)) => {
+            let authority_end = rest.find('/').unwrap_or(rest.len());
+            match rest[..authority_end].rfind('@') {
+                Some(at) => format!("{}://{}{}", scheme, MASK, &rest[at..]),
+                None => url.to_string(),
+            }
+        }
+        None => url.to_string(),
+    };
+    masked + &query
+}
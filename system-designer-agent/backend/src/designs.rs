@@ -0,0 +1,246 @@
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::Json;
+use serde::Deserialize;
+use tracing::info;
+use utoipa::IntoParams;
+
+use crate::diff::DesignDiff;
+use crate::error::{AppError, AppResult, ErrorResponse};
+use crate::extractors::ValidatedJson;
+use crate::renderer::DEFAULT_TEMPLATE;
+use crate::state::AppState;
+use crate::store::{DesignRevision, DesignSummary, RevisionSummary, StoredDesign};
+use crate::tenant::Tenant;
+use crate::DesignRequest;
+
+#[derive(Deserialize, Debug, Default, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SaveParams {
+    /// Template used to render the stored output; defaults to the built-in one
+    pub template: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Default, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DiffParams {
+    /// Older revision; defaults to the one before `to`
+    pub from: Option<u32>,
+    /// Newer revision; defaults to the latest
+    pub to: Option<u32>,
+}
+
+fn not_found(id: &str) -> AppError {
+    AppError::NotFound(format!("Design {} not found", id))
+}
+
+#[utoipa::path(
+    post,
+    path = "/designs",
+    tag = "designs",
+    params(SaveParams),
+    request_body = DesignRequest,
+    responses(
+        (status = 201, description = "Saved as revision 1", body = StoredDesign),
+        (status = 400, description = "Malformed payload or unknown template", body = ErrorResponse),
+        (status = 403, description = "Missing scope or archived workspace", body = ErrorResponse),
+        (status = 422, description = "A field exceeds its limits", body = ErrorResponse),
+    )
+)]
+pub async fn create_design(
+    State(state): State<AppState>,
+    tenant: Tenant,
+    Query(params): Query<SaveParams>,
+    ValidatedJson(payload): ValidatedJson<DesignRequest>,
+) -> AppResult<(StatusCode, Json<StoredDesign>)> {
+    tenant.ensure_writable()?;
+    let template = params.template.as_deref().unwrap_or(DEFAULT_TEMPLATE);
+    let findings = tenant.linter.lint(&payload);
+    let rendered = tenant.renderer.render(template, &payload, &findings)?;
+
+    let design = state
+        .store
+        .create(&tenant.workspace, payload, rendered)
+        .await?;
+
+    info!(id = %design.id, workspace = %tenant.workspace, "Design saved");
+    Ok((StatusCode::CREATED, Json(design)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/designs",
+    tag = "designs",
+    responses((status = 200, description = "Designs in the workspace", body = Vec<DesignSummary>))
+)]
+pub async fn list_designs(
+    State(state): State<AppState>,
+    tenant: Tenant,
+) -> AppResult<Json<Vec<DesignSummary>>> {
+    Ok(Json(state.store.list(&tenant.workspace).await?))
+}
+
+#[utoipa::path(
+    get,
+    path = "/designs/{id}",
+    tag = "designs",
+    params(("id" = String, Path, description = "Design ID")),
+    responses(
+        (status = 200, description = "Latest revision of the design", body = StoredDesign),
+        (status = 404, description = "No such design", body = ErrorResponse),
+    )
+)]
+pub async fn get_design(
+    State(state): State<AppState>,
+    tenant: Tenant,
+    Path(id): Path<String>,
+) -> AppResult<Json<StoredDesign>> {
+    let design = state
+        .store
+        .get(&tenant.workspace, &id)
+        .await?
+        .ok_or_else(|| not_found(&id))?;
+    Ok(Json(design))
+}
+
+#[utoipa::path(
+    put,
+    path = "/designs/{id}",
+    tag = "designs",
+    params(("id" = String, Path, description = "Design ID"), SaveParams),
+    request_body = DesignRequest,
+    responses(
+        (status = 200, description = "Saved as a new revision", body = StoredDesign),
+        (status = 400, description = "Malformed payload or unknown template", body = ErrorResponse),
+        (status = 403, description = "Missing scope or archived workspace", body = ErrorResponse),
+        (status = 404, description = "No such design", body = ErrorResponse),
+        (status = 422, description = "A field exceeds its limits", body = ErrorResponse),
+    )
+)]
+pub async fn update_design(
+    State(state): State<AppState>,
+    tenant: Tenant,
+    Path(id): Path<String>,
+    Query(params): Query<SaveParams>,
+    ValidatedJson(payload): ValidatedJson<DesignRequest>,
+) -> AppResult<Json<StoredDesign>> {
+    tenant.ensure_writable()?;
+    let template = params.template.as_deref().unwrap_or(DEFAULT_TEMPLATE);
+    let findings = tenant.linter.lint(&payload);
+    let rendered = tenant.renderer.render(template, &payload, &findings)?;
+
+    let design = state
+        .store
+        .update(&tenant.workspace, &id, payload, rendered)
+        .await?
+        .ok_or_else(|| not_found(&id))?;
+
+    info!(id = %design.id, workspace = %tenant.workspace, "Design updated");
+    Ok(Json(design))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/designs/{id}",
+    tag = "designs",
+    params(("id" = String, Path, description = "Design ID")),
+    responses(
+        (status = 204, description = "Deleted with all revisions"),
+        (status = 403, description = "Missing scope or archived workspace", body = ErrorResponse),
+        (status = 404, description = "No such design", body = ErrorResponse),
+    )
+)]
+pub async fn delete_design(
+    State(state): State<AppState>,
+    tenant: Tenant,
+    Path(id): Path<String>,
+) -> AppResult<StatusCode> {
+    tenant.ensure_writable()?;
+    if !state.store.delete(&tenant.workspace, &id).await? {
+        return Err(not_found(&id));
+    }
+
+    info!(id = %id, workspace = %tenant.workspace, "Design deleted");
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    get,
+    path = "/designs/{id}/revisions",
+    tag = "designs",
+    params(("id" = String, Path, description = "Design ID")),
+    responses(
+        (status = 200, description = "Revisions, oldest first", body = Vec<RevisionSummary>),
+        (status = 404, description = "No such design", body = ErrorResponse),
+    )
+)]
+pub async fn list_revisions(
+    State(state): State<AppState>,
+    tenant: Tenant,
+    Path(id): Path<String>,
+) -> AppResult<Json<Vec<RevisionSummary>>> {
+    let revisions = state
+        .store
+        .revisions(&tenant.workspace, &id)
+        .await?
+        .ok_or_else(|| not_found(&id))?;
+    Ok(Json(revisions))
+}
+
+#[utoipa::path(
+    get,
+    path = "/designs/{id}/diff",
+    tag = "designs",
+    params(("id" = String, Path, description = "Design ID"), DiffParams),
+    responses(
+        (status = 200, description = "Structural and unified diff between two revisions", body = DesignDiff),
+        (status = 400, description = "The design has a single revision", body = ErrorResponse),
+        (status = 404, description = "No such design or revision", body = ErrorResponse),
+    )
+)]
+pub async fn diff_design(
+    State(state): State<AppState>,
+    tenant: Tenant,
+    Path(id): Path<String>,
+    Query(params): Query<DiffParams>,
+) -> AppResult<Json<DesignDiff>> {
+    let to = match params.to {
+        Some(to) => to,
+        None => {
+            state
+                .store
+                .get(&tenant.workspace, &id)
+                .await?
+                .ok_or_else(|| not_found(&id))?
+                .revision
+        }
+    };
+    let from = match params.from {
+        Some(from) => from,
+        None if to > 1 => to - 1,
+        None => {
+            return Err(AppError::BadRequest(
+                "Design has a single revision, nothing to compare".to_string(),
+            ))
+        }
+    };
+
+    let from = load_revision(&state, &tenant, &id, from).await?;
+    let to = load_revision(&state, &tenant, &id, to).await?;
+    Ok(Json(DesignDiff::between(&from, &to)))
+}
+
+async fn load_revision(
+    state: &AppState,
+    tenant: &Tenant,
+    id: &str,
+    revision: u32,
+) -> AppResult<DesignRevision> {
+    state
+        .store
+        .revision(&tenant.workspace, id, revision)
+        .await?
+        .ok_or_else(|| {
+            AppError::NotFound(format!("Revision {} of design {} not found", revision, id))
+        })
+}
@@ -0,0 +1,217 @@
+use std::fmt::Write;
+
+use crate::choices::WorkflowPattern;
+use crate::DesignRequest;
+
+struct Flowchart {
+    out: String,
+}
+
+impl Flowchart {
+    fn new() -> Self {
+        Flowchart {
+            out: "flowchart LR\n".to_string(),
+        }
+    }
+
+    /// `shape` is the pair of Mermaid brackets, e.g. `("[", "]")`
+    fn node(&mut self, id: &str, shape: (&str, &str), label: &str) {
+        let _ = writeln!(
+            self.out,
+            "    {}{}\"{}\"{}",
+            id,
+            shape.0,
+            escape(label),
+            shape.1
+        );
+    }
+
+    fn edge(&mut self, from: &str, arrow: &str, to: &str, label: Option<&str>) {
+        let _ = match label {
+            Some(label) => writeln!(
+                self.out,
+                "    {} {}|\"{}\"| {}",
+                from,
+                arrow,
+                escape(label),
+                to
+            ),
+            None => writeln!(self.out, "    {} {} {}", from, arrow, to),
+        };
+    }
+}
+
+const BOX: (&str, &str) = ("[", "]");
+const ROUNDED: (&str, &str) = ("([", "])");
+const SUBROUTINE: (&str, &str) = ("[[", "]]");
+const HEXAGON: (&str, &str) = ("{{", "}}");
+const DATABASE: (&str, &str) = ("[(", ")]");
+
+/// Mermaid-safe label text
+fn escape(label: &str) -> String {
+    label
+        .replace('"', "#quot;")
+        .replace('\n', " ")
+        .trim()
+        .to_string()
+}
+
+fn labelled(kind: &str, value: &str) -> String {
+    if value.trim().is_empty() {
+        kind.to_string()
+    } else {
+        format!("{}: {}", kind, value.trim())
+    }
+}
+
+/// Mermaid flowchart of the agent architecture: user interface,
+/// orchestrator, model, tools and memory stores, wired according to the
+/// workflow pattern
+pub fn mermaid_flowchart(request: &DesignRequest) -> String {
+    let workflow = &request.orchestration.workflow;
+    let mut chart = Flowchart::new();
+
+    let interface = &request.interface;
+    let ui_label = if interface.interaction_mode.is_blank() {
+        labelled("Interface", interface.platform.as_str())
+    } else {
+        format!(
+            "{} ({})",
+            labelled("Interface", interface.platform.as_str()),
+            interface.interaction_mode.as_str().trim()
+        )
+    };
+
+    chart.node("user", ROUNDED, "User");
+    chart.node("ui", BOX, &ui_label);
+    chart.node(
+        "orchestrator",
+        HEXAGON,
+        &labelled("Orchestrator", workflow.as_str()),
+    );
+    chart.node(
+        "model",
+        SUBROUTINE,
+        &labelled("Model", request.model.base_model.as_str()),
+    );
+
+    let mut tools: Vec<String> = Vec::new();
+    for (i, api) in request.tools.apis.iter().enumerate() {
+        let id = format!("api_{}", i);
+        chart.node(&id, BOX, &labelled("API", api));
+        tools.push(id);
+    }
+    for (i, tool) in request.tools.definitions.iter().enumerate() {
+        let id = format!("tool_{}", i);
+        chart.node(&id, BOX, &labelled("Tool", &tool.name));
+        tools.push(id);
+    }
+    for (i, server) in request.tools.mcp_servers.iter().enumerate() {
+        let id = format!("mcp_{}", i);
+        chart.node(&id, BOX, &labelled("MCP", server));
+        tools.push(id);
+    }
+
+    let memory = &request.memory;
+    let mut stores: Vec<&str> = Vec::new();
+    if !memory.vector_db.trim().is_empty() {
+        chart.node(
+            "vector_db",
+            DATABASE,
+            &labelled("Vector DB", &memory.vector_db),
+        );
+        stores.push("vector_db");
+    }
+    if !memory.sql_db.trim().is_empty() {
+        chart.node("sql_db", DATABASE, &labelled("SQL DB", &memory.sql_db));
+        stores.push("sql_db");
+    }
+    if memory.working_memory {
+        chart.node("working_memory", DATABASE, "Working Memory");
+    }
+    let file_storage = !memory.file_storage.trim().is_empty();
+    if file_storage {
+        chart.node(
+            "file_storage",
+            DATABASE,
+            &labelled("Files", &memory.file_storage),
+        );
+    }
+    let queues = &request.orchestration.message_queues;
+    if !queues.trim().is_empty() {
+        chart.node("queue", BOX, &labelled("Queue", queues));
+        chart.edge("queue", "-->", "orchestrator", Some("events"));
+    }
+
+    chart.edge("user", "-->", "ui", None);
+    chart.edge("ui", "-->", "orchestrator", None);
+
+    match workflow.pattern() {
+        Some(WorkflowPattern::ReAct) => {
+            chart.edge("orchestrator", "<-->", "model", Some("reason / act"));
+            for tool in &tools {
+                chart.edge("orchestrator", "-->", tool, Some("action"));
+                chart.edge(tool, "-.->", "orchestrator", Some("observation"));
+            }
+        }
+        Some(WorkflowPattern::PlanAndExecute) => {
+            chart.node("executor", HEXAGON, "Executor");
+            chart.edge("orchestrator", "-->", "model", Some("plan"));
+            chart.edge("model", "-->", "executor", Some("steps"));
+            for tool in &tools {
+                chart.edge("executor", "-->", tool, None);
+            }
+            chart.edge("executor", "-.->", "orchestrator", Some("results / replan"));
+        }
+        Some(WorkflowPattern::Router) => {
+            chart.edge("orchestrator", "-->", "model", Some("classify"));
+            for tool in &tools {
+                chart.edge("orchestrator", "-.->", tool, Some("route"));
+            }
+        }
+        Some(WorkflowPattern::SupervisorWorker) => {
+            chart.node("workers", ROUNDED, "Workers");
+            chart.edge("orchestrator", "-->", "model", Some("supervise"));
+            chart.edge("orchestrator", "-->", "workers", Some("delegate"));
+            chart.edge("workers", "-->", "model", None);
+            for tool in &tools {
+                chart.edge("workers", "-->", tool, None);
+            }
+            chart.edge("workers", "-.->", "orchestrator", Some("report"));
+        }
+        Some(WorkflowPattern::Sequential) => {
+            let mut previous = "model";
+            chart.edge("orchestrator", "-->", "model", None);
+            for tool in &tools {
+                chart.edge(previous, "-->", tool, None);
+                previous = tool;
+            }
+            chart.edge(previous, "-->", "orchestrator", Some("output"));
+        }
+        Some(WorkflowPattern::Other(_)) | None => {
+            chart.edge("orchestrator", "-->", "model", None);
+            for tool in &tools {
+                chart.edge("orchestrator", "-->", tool, None);
+            }
+        }
+    }
+
+    let store_label = if memory.episodic {
+        "episodic memory"
+    } else {
+        "read / write"
+    };
+    for store in &stores {
+        chart.edge("orchestrator", "<-->", store, Some(store_label));
+    }
+    if memory.working_memory {
+        chart.edge("orchestrator", "<-->", "working_memory", None);
+    }
+    if file_storage {
+        chart.edge("orchestrator", "<-->", "file_storage", Some("files"));
+    }
+
+    chart.edge("orchestrator", "-->", "ui", Some("response"));
+
+    chart.out.trim_end().to_string()
+}
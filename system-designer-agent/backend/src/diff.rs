@@ -0,0 +1,131 @@
+use serde::Serialize;
+use serde_json::Value;
+use similar::TextDiff;
+use utoipa::ToSchema;
+
+use crate::store::DesignRevision;
+use crate::DesignRequest;
+
+/// Lines of unchanged context around each hunk of the unified diff
+const CONTEXT_LINES: usize = 3;
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Modified,
+}
+
+/// A single field that differs between two revisions
+#[derive(Serialize, Debug, Clone, PartialEq, ToSchema)]
+pub struct FieldChange {
+    /// Field path within its section, e.g. `use_case` or `apis`
+    pub field: String,
+    pub kind: ChangeKind,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+}
+
+/// Changed fields of one `DesignRequest` section
+#[derive(Serialize, Debug, Clone, PartialEq, ToSchema)]
+pub struct SectionDiff {
+    pub section: String,
+    pub changes: Vec<FieldChange>,
+}
+
+/// Difference between two revisions of a stored design
+#[derive(Serialize, Debug, Clone, PartialEq, ToSchema)]
+pub struct DesignDiff {
+    pub from: u32,
+    pub to: u32,
+    /// Only sections with at least one change, in `DesignRequest` order
+    pub sections: Vec<SectionDiff>,
+    /// Unified diff of the rendered output
+    pub unified: String,
+}
+
+impl DesignDiff {
+    pub fn between(from: &DesignRevision, to: &DesignRevision) -> Self {
+        DesignDiff {
+            from: from.revision,
+            to: to.revision,
+            sections: diff_requests(&from.request, &to.request),
+            unified: unified_diff(
+                &from.rendered,
+                &to.rendered,
+                &format!("revision {}", from.revision),
+                &format!("revision {}", to.revision),
+            ),
+        }
+    }
+}
+
+/// Field-level structural diff, section by section
+pub fn diff_requests(before: &DesignRequest, after: &DesignRequest) -> Vec<SectionDiff> {
+    let before = serde_json::to_value(before).expect("DesignRequest serializes to JSON");
+    let after = serde_json::to_value(after).expect("DesignRequest serializes to JSON");
+    let (Value::Object(before), Value::Object(after)) = (before, after) else {
+        unreachable!("DesignRequest serializes to an object");
+    };
+
+    after
+        .iter()
+        .filter_map(|(section, after_section)| {
+            let before_section = before.get(section).unwrap_or(&Value::Null);
+            let mut changes = Vec::new();
+            diff_values("", before_section, after_section, &mut changes);
+            (!changes.is_empty()).then(|| SectionDiff {
+                section: section.clone(),
+                changes,
+            })
+        })
+        .collect()
+}
+
+fn diff_values(path: &str, before: &Value, after: &Value, changes: &mut Vec<FieldChange>) {
+    if before == after {
+        return;
+    }
+
+    match (before, after) {
+        (Value::Object(b), Value::Object(a)) => {
+            let added = a.keys().filter(|key| !b.contains_key(*key));
+            for key in b.keys().chain(added) {
+                let child = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", path, key)
+                };
+                diff_values(
+                    &child,
+                    b.get(key).unwrap_or(&Value::Null),
+                    a.get(key).unwrap_or(&Value::Null),
+                    changes,
+                );
+            }
+        }
+        _ => {
+            let kind = match (before, after) {
+                (Value::Null, _) => ChangeKind::Added,
+                (_, Value::Null) => ChangeKind::Removed,
+                _ => ChangeKind::Modified,
+            };
+            changes.push(FieldChange {
+                field: path.to_string(),
+                kind,
+                before: (!before.is_null()).then(|| before.clone()),
+                after: (!after.is_null()).then(|| after.clone()),
+            });
+        }
+    }
+}
+
+/// Unified diff between two rendered documents
+pub fn unified_diff(before: &str, after: &str, before_label: &str, after_label: &str) -> String {
+    TextDiff::from_lines(before, after)
+        .unified_diff()
+        .context_radius(CONTEXT_LINES)
+        .header(before_label, after_label)
+        .to_string()
+}
@@ -1,11 +1,14 @@
 use axum::{
-    http::StatusCode,
+    http::{header, HeaderValue, StatusCode},
     response::{IntoResponse, Response},
     Json,
 };
 use serde::Serialize;
 use thiserror::Error;
 use tracing::error;
+use utoipa::ToSchema;
+
+use crate::middleware::request_id;
 
 #[derive(Error, Debug)]
 pub enum AppError {
@@ -15,6 +18,21 @@ pub enum AppError {
     #[error("Bad request: {0}")]
     BadRequest(String),
 
+    #[error("Not found: {0}")]
+    NotFound(String),
+
+    #[error("Unauthorized: {0}")]
+    Unauthorized(String),
+
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
+
+    #[error("Conflict: {0}")]
+    Conflict(String),
+
+    #[error("Rate limit exceeded, retry in {0}s")]
+    RateLimited(u64),
+
     #[error("Internal server error")]
     Internal(#[from] anyhow::Error),
 
@@ -22,10 +40,15 @@ pub enum AppError {
     Config(String),
 }
 
-#[derive(Serialize)]
-struct ErrorResponse {
+/// Body of every error response
+#[derive(Serialize, ToSchema)]
+pub struct ErrorResponse {
     error: String,
+    /// Machine-readable kind, e.g. `VALIDATION_ERROR` or `RATE_LIMITED`
     code: String,
+    /// Correlation ID from `X-Request-Id`, absent outside a request
+    #[serde(skip_serializing_if = "Option::is_none")]
+    request_id: Option<String>,
 }
 
 impl IntoResponse for AppError {
@@ -37,6 +60,21 @@ impl IntoResponse for AppError {
             AppError::BadRequest(msg) => {
                 (StatusCode::BAD_REQUEST, "BAD_REQUEST", msg.clone())
             }
+            AppError::NotFound(msg) => {
+                (StatusCode::NOT_FOUND, "NOT_FOUND", msg.clone())
+            }
+            AppError::Unauthorized(msg) => {
+                (StatusCode::UNAUTHORIZED, "UNAUTHORIZED", msg.clone())
+            }
+            AppError::Forbidden(msg) => {
+                (StatusCode::FORBIDDEN, "FORBIDDEN", msg.clone())
+            }
+            AppError::Conflict(msg) => {
+                (StatusCode::CONFLICT, "CONFLICT", msg.clone())
+            }
+            AppError::RateLimited(_) => {
+                (StatusCode::TOO_MANY_REQUESTS, "RATE_LIMITED", self.to_string())
+            }
             AppError::Internal(e) => {
                 error!("Internal error: {:?}", e);
                 (
@@ -53,9 +91,41 @@ impl IntoResponse for AppError {
         let body = Json(ErrorResponse {
             error: message,
             code: code.to_string(),
+            request_id: request_id::current(),
         });
 
-        (status, body).into_response()
+        let mut response = (status, body).into_response();
+        match self {
+            AppError::Unauthorized(_) => {
+                response
+                    .headers_mut()
+                    .insert(header::WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"));
+            }
+            AppError::RateLimited(retry_after) => {
+                response
+                    .headers_mut()
+                    .insert(header::RETRY_AFTER, HeaderValue::from(retry_after));
+            }
+            _ => {}
+        }
+        response
+    }
+}
+
+impl AppError {
+    /// Process exit status for command line use, following `sysexits.h`
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            AppError::Validation(_) => 65,   // EX_DATAERR
+            AppError::BadRequest(_) => 64,   // EX_USAGE
+            AppError::NotFound(_) => 66,     // EX_NOINPUT
+            AppError::Unauthorized(_) => 77, // EX_NOPERM
+            AppError::Forbidden(_) => 77,    // EX_NOPERM
+            AppError::Conflict(_) => 73,     // EX_CANTCREAT
+            AppError::RateLimited(_) => 75,  // EX_TEMPFAIL
+            AppError::Internal(_) => 70,     // EX_SOFTWARE
+            AppError::Config(_) => 78,       // EX_CONFIG
+        }
     }
 }
 
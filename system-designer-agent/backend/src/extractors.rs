@@ -4,43 +4,102 @@ use axum::{
     Json,
 };
 use serde::de::DeserializeOwned;
-use validator::Validate;
+use serde_json::Value;
+use std::sync::Arc;
+use validator::{Validate, ValidationError, ValidationErrors, ValidationErrorsKind};
 
 use crate::error::AppError;
+use crate::metrics::Metrics;
+use crate::migrate::Upgrade;
 
-/// A JSON extractor that validates the payload using the `validator` crate
+/// A JSON extractor that upgrades older payload shapes and validates the
+/// result using the `validator` crate
 pub struct ValidatedJson<T>(pub T);
 
 #[async_trait]
 impl<S, T> FromRequest<S> for ValidatedJson<T>
 where
     S: Send + Sync,
-    T: DeserializeOwned + Validate,
+    T: DeserializeOwned + Validate + Upgrade,
 {
     type Rejection = AppError;
 
     async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
-        let result: Result<Json<T>, JsonRejection> = Json::from_request(req, state).await;
+        let metrics = req.extensions().get::<Arc<Metrics>>().cloned();
+        let result: Result<Json<Value>, JsonRejection> = Json::from_request(req, state).await;
 
         let Json(value) = result.map_err(|e| AppError::BadRequest(e.body_text()))?;
+        let value: T = serde_path_to_error::deserialize(T::upgrade(value)?).map_err(|e| {
+            AppError::BadRequest(format!(
+                "Failed to deserialize the JSON body into the target type: {}",
+                e
+            ))
+        })?;
 
         value.validate().map_err(|e| {
-            let errors: Vec<String> = e
-                .field_errors()
-                .into_iter()
-                .flat_map(|(field, errors)| {
-                    errors.iter().map(move |err| {
-                        format!(
-                            "{}: {}",
-                            field,
-                            err.message.as_ref().map(|c| c.to_string()).unwrap_or_else(|| "invalid".to_string())
-                        )
-                    })
-                })
-                .collect();
-            AppError::Validation(errors.join("; "))
+            if let Some(metrics) = &metrics {
+                for (path, _) in field_errors(&e) {
+                    metrics.validation_failed(&path);
+                }
+            }
+            AppError::Validation(validation_message(&e))
         })?;
 
         Ok(ValidatedJson(value))
     }
 }
+
+/// The `field: message; ...` text of a `422 VALIDATION_ERROR` response
+pub fn validation_message(errors: &ValidationErrors) -> String {
+    let errors: Vec<String> = errors
+        .field_errors()
+        .into_iter()
+        .flat_map(|(field, errors)| {
+            errors.iter().map(move |err| {
+                format!(
+                    "{}: {}",
+                    field,
+                    err.message
+                        .as_ref()
+                        .map(|c| c.to_string())
+                        .unwrap_or_else(|| "invalid".to_string())
+                )
+            })
+        })
+        .collect();
+    errors.join("; ")
+}
+
+/// Flatten nested validation errors into `(path, error)` pairs, with dotted
+/// paths such as `purpose.use_case` or `tools.apis[2]`, sorted by path
+pub fn field_errors(errors: &ValidationErrors) -> Vec<(String, &ValidationError)> {
+    let mut out = Vec::new();
+    collect_field_errors("", errors, &mut out);
+    out.sort_by(|a, b| a.0.cmp(&b.0));
+    out
+}
+
+fn collect_field_errors<'a>(
+    prefix: &str,
+    errors: &'a ValidationErrors,
+    out: &mut Vec<(String, &'a ValidationError)>,
+) {
+    for (field, kind) in errors.errors() {
+        let path = if prefix.is_empty() {
+            field.to_string()
+        } else {
+            format!("{}.{}", prefix, field)
+        };
+        match kind {
+            ValidationErrorsKind::Struct(nested) => collect_field_errors(&path, nested, out),
+            ValidationErrorsKind::List(items) => {
+                for (index, nested) in items {
+                    collect_field_errors(&format!("{}[{}]", path, index), nested, out);
+                }
+            }
+            ValidationErrorsKind::Field(errors) => {
+                out.extend(errors.iter().map(|err| (path.clone(), err)));
+            }
+        }
+    }
+}
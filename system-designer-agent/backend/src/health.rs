@@ -1,31 +1,39 @@
+use axum::http::StatusCode;
 use axum::Json;
 use serde::Serialize;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::{SystemTime, UNIX_EPOCH};
+use utoipa::ToSchema;
 
 /// Global readiness flag - can be set to false during graceful shutdown
 pub static READY: AtomicBool = AtomicBool::new(true);
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct HealthResponse {
     status: &'static str,
     timestamp: u64,
     version: &'static str,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct ReadinessResponse {
     ready: bool,
     checks: ReadinessChecks,
     timestamp: u64,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct ReadinessChecks {
     accepting_requests: bool,
     memory_ok: bool,
 }
 
+#[utoipa::path(
+    get,
+    path = "/health",
+    tag = "health",
+    responses((status = 200, description = "The process is up", body = HealthResponse))
+)]
 pub async fn health_check() -> Json<HealthResponse> {
     Json(HealthResponse {
         status: "healthy",
@@ -34,18 +42,33 @@ pub async fn health_check() -> Json<HealthResponse> {
     })
 }
 
-pub async fn readiness_check() -> Json<ReadinessResponse> {
+#[utoipa::path(
+    get,
+    path = "/ready",
+    tag = "health",
+    responses(
+        (status = 200, description = "Ready to take traffic", body = ReadinessResponse),
+        (status = 503, description = "Shutting down or low on memory", body = ReadinessResponse)
+    )
+)]
+pub async fn readiness_check() -> (StatusCode, Json<ReadinessResponse>) {
     let ready = READY.load(Ordering::SeqCst);
     let memory_ok = check_memory();
+    let status = if ready && memory_ok {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
 
-    Json(ReadinessResponse {
+    let body = Json(ReadinessResponse {
         ready: ready && memory_ok,
         checks: ReadinessChecks {
             accepting_requests: ready,
             memory_ok,
         },
         timestamp: current_timestamp(),
-    })
+    });
+    (status, body)
 }
 
 fn current_timestamp() -> u64 {
@@ -55,7 +78,8 @@ fn current_timestamp() -> u64 {
         .as_secs()
 }
 
-fn check_memory() -> bool {
+/// More than 5% of system memory is available
+pub fn check_memory() -> bool {
     use sysinfo::System;
 
     let mut sys = System::new();
@@ -0,0 +1,467 @@
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use utoipa::ToSchema;
+
+use crate::error::AppError;
+use crate::migrate;
+use crate::DesignRequest;
+
+/// How a bullet value is converted back into a `DesignRequest` field
+#[derive(Clone, Copy)]
+enum FieldKind {
+    Text,
+    Flag,
+    List,
+    /// Rendered from another field, e.g. the description of a known model;
+    /// skipped on import
+    Derived,
+}
+
+struct SectionLayout {
+    title: &'static str,
+    key: &'static str,
+    fields: &'static [(&'static str, &'static str, FieldKind)],
+}
+
+/// Section and bullet layout emitted by the default template
+const LAYOUT: &[SectionLayout] = &[
+    SectionLayout {
+        title: "Purpose & Scope",
+        key: "purpose",
+        fields: &[
+            ("Use Case", "use_case", FieldKind::Text),
+            ("User Needs", "user_needs", FieldKind::Text),
+            ("Success Criteria", "success_criteria", FieldKind::Text),
+            ("Constraints", "constraints", FieldKind::Text),
+        ],
+    },
+    SectionLayout {
+        title: "System Prompt Design",
+        key: "prompt",
+        fields: &[
+            ("Role & Persona", "role", FieldKind::Text),
+            ("Primary Goals", "goals", FieldKind::Text),
+            ("Instructions", "instructions", FieldKind::Text),
+            ("Guardrails", "guardrails", FieldKind::Text),
+        ],
+    },
+    SectionLayout {
+        title: "Model Selection",
+        key: "model",
+        fields: &[
+            ("Base Model", "base_model", FieldKind::Text),
+            ("Model Overview", "", FieldKind::Derived),
+            ("Parameters", "parameters", FieldKind::Text),
+            ("Context Window", "context_window", FieldKind::Text),
+            ("Tradeoffs", "cost_latency_tradeoff", FieldKind::Text),
+        ],
+    },
+    SectionLayout {
+        title: "Tools & Integrations",
+        key: "tools",
+        fields: &[
+            ("External APIs", "apis", FieldKind::List),
+            ("MCP Servers", "mcp_servers", FieldKind::List),
+            ("Custom Functions", "custom_functions", FieldKind::Text),
+        ],
+    },
+    SectionLayout {
+        title: "Memory Systems",
+        key: "memory",
+        fields: &[
+            ("Episodic Memory", "episodic", FieldKind::Flag),
+            ("Working Memory", "working_memory", FieldKind::Flag),
+            ("Vector Database", "vector_db", FieldKind::Text),
+            ("SQL/Structured DB", "sql_db", FieldKind::Text),
+            ("File Storage", "file_storage", FieldKind::Text),
+        ],
+    },
+    SectionLayout {
+        title: "Orchestration",
+        key: "orchestration",
+        fields: &[
+            ("Workflow Pattern", "workflow", FieldKind::Text),
+            ("Pattern Overview", "", FieldKind::Derived),
+            ("System Triggers", "triggers", FieldKind::Text),
+            ("Error Handling", "error_handling", FieldKind::Text),
+            ("Message Queues", "message_queues", FieldKind::Text),
+        ],
+    },
+    SectionLayout {
+        title: "User Interface",
+        key: "interface",
+        fields: &[
+            ("Platform", "platform", FieldKind::Text),
+            ("Platform Overview", "", FieldKind::Derived),
+            ("Interaction Mode", "interaction_mode", FieldKind::Text),
+            ("Interaction Overview", "", FieldKind::Derived),
+            ("API Strategy", "api_endpoint", FieldKind::Text),
+        ],
+    },
+    SectionLayout {
+        title: "Testing & Evals",
+        key: "testing",
+        fields: &[
+            ("Unit Tests", "unit_tests", FieldKind::List),
+            ("Latency Testing", "latency_testing", FieldKind::Text),
+            ("Quality Metrics", "quality_metrics", FieldKind::Text),
+            ("Evaluation Strategy", "evals", FieldKind::Text),
+        ],
+    },
+];
+
+/// Sections derived from the request at render time, skipped on import
+const GENERATED_SECTIONS: &[&str] = &["Design Review Findings", "Architecture Diagram"];
+
+/// Sub-heading the default template puts above the tool catalog table and
+/// the function-calling block in "Tools & Integrations"
+const TOOL_CATALOG: &str = "### Tool Catalog";
+
+/// Something the importer could not map back onto a `DesignRequest`
+#[derive(Serialize, Debug, Clone, PartialEq, ToSchema)]
+pub struct ImportIssue {
+    pub section: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+    pub message: String,
+}
+
+/// Result of parsing a design document back into a request
+#[derive(Serialize, Debug, Clone, ToSchema)]
+pub struct ImportReport {
+    pub request: DesignRequest,
+    pub issues: Vec<ImportIssue>,
+}
+
+/// A `## ` section of the document with its raw body lines
+struct RawSection<'a> {
+    heading: &'a str,
+    lines: Vec<&'a str>,
+}
+
+/// Parse a Markdown document produced by the default template.
+///
+/// Fields missing from the document are left empty. Sections and bullets
+/// that do not match the expected layout are reported as issues rather than
+/// failing the import; only a document with no recognizable section at all
+/// is rejected.
+pub fn parse_design_markdown(markdown: &str) -> Result<ImportReport, AppError> {
+    let mut root =
+        serde_json::to_value(DesignRequest::default()).expect("DesignRequest serializes to JSON");
+    let mut issues = Vec::new();
+    let mut recognized = 0;
+
+    for section in split_sections(markdown) {
+        let title = strip_numbering(section.heading);
+        if GENERATED_SECTIONS
+            .iter()
+            .any(|generated| generated.eq_ignore_ascii_case(title))
+        {
+            continue;
+        }
+        let Some(layout) = LAYOUT.iter().find(|l| l.title.eq_ignore_ascii_case(title)) else {
+            issues.push(ImportIssue {
+                section: title.to_string(),
+                field: None,
+                message: "Unrecognized section".to_string(),
+            });
+            continue;
+        };
+        recognized += 1;
+
+        let target = root
+            .get_mut(layout.key)
+            .and_then(Value::as_object_mut)
+            .expect("layout keys match DesignRequest sections");
+        let (lines, catalog) = match section.lines.iter().position(|l| l.trim() == TOOL_CATALOG) {
+            Some(index) if layout.key == "tools" => {
+                (&section.lines[..index], Some(&section.lines[index + 1..]))
+            }
+            _ => (&section.lines[..], None),
+        };
+        parse_section(layout, lines, target, &mut issues);
+
+        if let Some(catalog) = catalog {
+            match parse_tool_catalog(catalog) {
+                Ok(definitions) => {
+                    target.insert("definitions".to_string(), Value::Array(definitions));
+                }
+                Err(message) => issues.push(ImportIssue {
+                    section: layout.title.to_string(),
+                    field: Some("Tool Catalog".to_string()),
+                    message,
+                }),
+            }
+        }
+    }
+
+    if recognized == 0 {
+        return Err(AppError::BadRequest(
+            "No design sections found in document".to_string(),
+        ));
+    }
+
+    let request = migrate::design_request(root)?;
+    Ok(ImportReport { request, issues })
+}
+
+fn split_sections(markdown: &str) -> Vec<RawSection<'_>> {
+    let mut lines: Vec<&str> = markdown.lines().collect();
+
+    // Only the document title and the footer rule bound the sections; the
+    // same text inside a field is part of its value
+    if let Some(first) = lines.iter().position(|line| !line.trim().is_empty()) {
+        if lines[first].starts_with("# ") {
+            lines.drain(..=first);
+        }
+    }
+    if let Some(rule) = lines.iter().rposition(|line| line.trim() == "---") {
+        let trailing = lines[rule + 1..]
+            .iter()
+            .filter(|line| !line.trim().is_empty())
+            .count();
+        if trailing <= 1 {
+            lines.truncate(rule);
+        }
+    }
+
+    let mut sections: Vec<RawSection> = Vec::new();
+    for line in lines {
+        if let Some(heading) = line.strip_prefix("## ") {
+            sections.push(RawSection {
+                heading: heading.trim(),
+                lines: Vec::new(),
+            });
+        } else if let Some(current) = sections.last_mut() {
+            current.lines.push(line);
+        }
+    }
+    sections
+}
+
+/// `"4. Tools & Integrations"` -> `"Tools & Integrations"`
+fn strip_numbering(heading: &str) -> &str {
+    match heading.split_once(". ") {
+        Some((number, rest)) if number.chars().all(|c| c.is_ascii_digit()) => rest.trim(),
+        _ => heading,
+    }
+}
+
+fn parse_section(
+    layout: &SectionLayout,
+    lines: &[&str],
+    target: &mut Map<String, Value>,
+    issues: &mut Vec<ImportIssue>,
+) {
+    for (label, raw) in collect_bullets(lines) {
+        let Some(&(_, key, kind)) = layout
+            .fields
+            .iter()
+            .find(|(l, _, _)| l.eq_ignore_ascii_case(&label))
+        else {
+            issues.push(ImportIssue {
+                section: layout.title.to_string(),
+                field: Some(label),
+                message: "Unrecognized field".to_string(),
+            });
+            continue;
+        };
+
+        let value = match kind {
+            FieldKind::Derived => continue,
+            FieldKind::Text => Ok(Value::String(raw)),
+            FieldKind::Flag => parse_flag(&raw).map(Value::Bool),
+            FieldKind::List => {
+                parse_list(&raw).map(|items| items.into_iter().map(Value::String).collect())
+            }
+        };
+
+        match value {
+            Ok(value) => {
+                target.insert(key.to_string(), value);
+            }
+            Err(message) => issues.push(ImportIssue {
+                section: layout.title.to_string(),
+                field: Some(label),
+                message,
+            }),
+        }
+    }
+}
+
+/// Group `- **Label:** value` bullets, folding continuation lines into the
+/// previous value so multi-line text survives the round trip
+fn collect_bullets(lines: &[&str]) -> Vec<(String, String)> {
+    let mut bullets: Vec<(String, String)> = Vec::new();
+
+    for line in lines {
+        if let Some((label, value)) = line
+            .strip_prefix("- **")
+            .and_then(|rest| rest.split_once(":**"))
+        {
+            bullets.push((label.trim().to_string(), value.trim_start().to_string()));
+        } else if let Some((_, value)) = bullets.last_mut() {
+            value.push('\n');
+            value.push_str(line);
+        }
+    }
+
+    for (_, value) in bullets.iter_mut() {
+        let trimmed = value.trim_end().len();
+        value.truncate(trimmed);
+    }
+
+    bullets
+}
+
+/// Rebuild tool definitions from the catalog table and the function-calling
+/// block. The block holds the name, description and parameters verbatim;
+/// the table adds side effect, auth and rate limit, row by row.
+fn parse_tool_catalog(lines: &[&str]) -> Result<Vec<Value>, String> {
+    let rows: Vec<Vec<String>> = lines
+        .iter()
+        .filter(|line| line.starts_with('|'))
+        .skip(2)
+        .map(|line| split_cells(line))
+        .collect();
+
+    let block: String = lines
+        .iter()
+        .skip_while(|line| line.trim() != "```json")
+        .skip(1)
+        .take_while(|line| line.trim() != "```")
+        .map(|line| format!("{}\n", line))
+        .collect();
+    let functions: Vec<Value> = serde_json::from_str(&block)
+        .map_err(|e| format!("Invalid function-calling block: {}", e))?;
+
+    if rows.len() != functions.len() {
+        return Err(format!(
+            "Catalog lists {} tool(s) but the function-calling block has {}",
+            rows.len(),
+            functions.len()
+        ));
+    }
+
+    rows.into_iter()
+        .zip(functions)
+        .map(|(row, function)| {
+            let function = &function["function"];
+            let name = function["name"].as_str().unwrap_or_default();
+            let [listed, _, side_effect, auth, rate_limit] = &row[..] else {
+                return Err(format!("Expected 5 columns for tool {:?}", name));
+            };
+            if listed.trim_matches('`') != name {
+                return Err(format!(
+                    "Catalog row {:?} does not match function {:?}",
+                    listed, name
+                ));
+            }
+            Ok(json!({
+                "name": name,
+                "description": function.get("description").cloned().unwrap_or_else(|| json!("")),
+                "parameters": function["parameters"],
+                "auth": auth,
+                "side_effect": side_effect,
+                "rate_limit": rate_limit.replace("<br>", "\n"),
+            }))
+        })
+        .collect()
+}
+
+/// Cells of a Markdown table row, with `\|` read as a literal pipe
+fn split_cells(row: &str) -> Vec<String> {
+    let inner = row.trim().trim_start_matches('|');
+    let inner = inner.strip_suffix('|').unwrap_or(inner);
+    let mut cells = vec![String::new()];
+    let mut chars = inner.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.peek() == Some(&'|') => {
+                cells.last_mut().unwrap().push('|');
+                chars.next();
+            }
+            '|' => cells.push(String::new()),
+            c => cells.last_mut().unwrap().push(c),
+        }
+    }
+    cells.iter().map(|cell| cell.trim().to_string()).collect()
+}
+
+fn parse_flag(raw: &str) -> Result<bool, String> {
+    match raw.trim().to_ascii_lowercase().as_str() {
+        "true" | "yes" | "enabled" => Ok(true),
+        "false" | "no" | "disabled" | "" => Ok(false),
+        other => Err(format!("Expected true or false, found {:?}", other)),
+    }
+}
+
+/// Parse a list rendered with `{:?}` (`["a", "b"]`). Hand-edited files may
+/// also use a plain comma-separated list.
+fn parse_list(raw: &str) -> Result<Vec<String>, String> {
+    let raw = raw.trim();
+    match raw.strip_prefix('[').and_then(|r| r.strip_suffix(']')) {
+        Some(inner) => parse_debug_strings(inner),
+        None => Ok(raw
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+            .collect()),
+    }
+}
+
+/// Parse the comma-separated, quoted and escaped strings inside `[...]`
+fn parse_debug_strings(inner: &str) -> Result<Vec<String>, String> {
+    let mut items = Vec::new();
+    let mut chars = inner.chars().peekable();
+
+    loop {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        match chars.next() {
+            None => return Ok(items),
+            Some('"') => {}
+            Some(c) => return Err(format!("Expected a quoted string, found {:?}", c)),
+        }
+
+        let mut item = String::new();
+        loop {
+            match chars.next() {
+                None => return Err("Unterminated string in list".to_string()),
+                Some('"') => break,
+                Some('\\') => item.push(parse_escape(&mut chars)?),
+                Some(c) => item.push(c),
+            }
+        }
+        items.push(item);
+
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        match chars.next() {
+            None => return Ok(items),
+            Some(',') => {}
+            Some(c) => return Err(format!("Expected ',' between list items, found {:?}", c)),
+        }
+    }
+}
+
+fn parse_escape(chars: &mut impl Iterator<Item = char>) -> Result<char, String> {
+    match chars.next() {
+        Some('n') => Ok('\n'),
+        Some('r') => Ok('\r'),
+        Some('t') => Ok('\t'),
+        Some('0') => Ok('\0'),
+        Some(c @ ('\\' | '"' | '\'')) => Ok(c),
+        Some('u') => {
+            if chars.next() != Some('{') {
+                return Err("Malformed unicode escape".to_string());
+            }
+            let hex: String = chars.by_ref().take_while(|&c| c != '}').collect();
+            u32::from_str_radix(&hex, 16)
+                .ok()
+                .and_then(char::from_u32)
+                .ok_or_else(|| format!("Invalid unicode escape \\u{{{}}}", hex))
+        }
+        Some(c) => Err(format!("Unknown escape \\{}", c)),
+        None => Err("Unterminated escape".to_string()),
+    }
+}
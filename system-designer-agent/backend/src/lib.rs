@@ -1,38 +1,183 @@
-use axum::routing::{get, post};
+use axum::extract::{Extension, Query};
+use axum::http::{header, HeaderMap};
+use axum::middleware::from_fn_with_state;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post, put};
 use axum::{Json, Router};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 use tower_http::limit::RequestBodyLimitLayer;
 use tower_http::trace::TraceLayer;
 use tracing::info;
+use utoipa::{IntoParams, ToSchema};
 use validator::Validate;
 
+pub mod choices;
+pub mod cli;
 pub mod config;
+pub mod designs;
+pub mod diagram;
+pub mod diff;
 pub mod error;
 pub mod extractors;
 pub mod health;
+pub mod import;
+pub mod lint;
+pub mod listener;
 pub mod logging;
+pub mod metrics;
 pub mod middleware;
-
+pub mod migrate;
+pub mod openapi;
+pub mod redact;
+pub mod reload;
+pub mod renderer;
+pub mod server;
+pub mod spec;
+pub mod state;
+pub mod store;
+pub mod tenant;
+pub mod tls;
+pub mod tools;
+pub mod tui;
+pub mod workspaces;
+
+use crate::choices::{BaseModel, InteractionMode, Platform, WorkflowPattern};
 use crate::config::Config;
-use crate::error::{AppError, AppResult};
+use crate::designs::{
+    create_design, delete_design, diff_design, get_design, list_designs, list_revisions,
+    update_design,
+};
+use crate::error::{AppError, AppResult, ErrorResponse};
 use crate::extractors::ValidatedJson;
 use crate::health::{health_check, readiness_check};
-use crate::middleware::{cors_layer, security_headers};
+use crate::import::{parse_design_markdown, ImportReport};
+use crate::lint::Finding;
+use crate::metrics::Metrics;
+use crate::middleware::{
+    authorize, cors_layer, rate_limit, request_id, security_headers, track_metrics, Authenticator,
+    CorsOrigins, RateLimiter, RequireScope, Scope,
+};
+use crate::migrate::SCHEMA_VERSION;
+use crate::openapi::{design_request_schema_json, openapi_json};
+use crate::reload::Reloader;
+use crate::renderer::DEFAULT_TEMPLATE;
+use crate::spec::{DesignSpec, OutputFormat};
+use crate::state::AppState;
+use crate::tenant::Tenant;
+use crate::tools::ToolDefinition;
+use crate::workspaces::{archive_workspace, create_workspace, list_workspaces};
 
 /// Build the application router with configuration
 pub fn app_with_config(config: &Config) -> Result<Router, AppError> {
-    let cors = cors_layer(config)?;
+    app_with_metrics(config, Arc::new(Metrics::new()))
+}
+
+/// Build the application router, recording into `metrics`. `/metrics` is
+/// served alongside `/health` unless metrics are disabled or moved to
+/// `metrics_port`.
+pub fn app_with_metrics(config: &Config, metrics: Arc<Metrics>) -> Result<Router, AppError> {
+    app_with_reloader(config, metrics).map(|(router, _)| router)
+}
 
-    let router = Router::new()
+/// Build the application router together with the [`Reloader`] that
+/// changes its CORS origins, rate limits and templates while it serves
+pub fn app_with_reloader(
+    config: &Config,
+    metrics: Arc<Metrics>,
+) -> Result<(Router, Reloader), AppError> {
+    let cors_origins = CorsOrigins::from_config(config)?;
+    let state = AppState::from_config(config)?;
+
+    let health_limiter = RateLimiter::new(
+        "health",
+        config.health_rate_limit_per_second,
+        config.health_rate_limit_burst,
+        config.trusted_proxies.clone(),
+    );
+    let auth = Arc::new(Authenticator::from_config(config)?);
+    let api_limiter = RateLimiter::new(
+        "api",
+        config.rate_limit_per_second,
+        config.rate_limit_burst,
+        config.trusted_proxies.clone(),
+    )
+    .with_authenticator(auth.clone());
+
+    let reloader = Reloader::new(
+        config,
+        cors_origins.clone(),
+        api_limiter.clone(),
+        health_limiter.clone(),
+        state.resources.clone(),
+        metrics.clone(),
+    );
+
+    let mut health = Router::new()
         .route("/health", get(health_check))
-        .route("/ready", get(readiness_check))
+        .route("/ready", get(readiness_check));
+    if config.metrics_enabled && config.metrics_port.is_none() {
+        health = health.merge(metrics::router(metrics.clone()));
+    }
+    let health = health.route_layer(from_fn_with_state(health_limiter, rate_limit));
+
+    let require = |scope| from_fn_with_state(RequireScope::new(auth.clone(), scope), authorize);
+
+    let reads = Router::new()
         .route("/generate", post(generate_design))
+        .route("/import", post(import_design))
+        .route("/lint", post(lint_design))
+        .route("/diagram", post(diagram_design))
+        .route("/templates", get(list_templates))
+        .route("/designs", get(list_designs))
+        .route("/designs/:id", get(get_design))
+        .route("/designs/:id/revisions", get(list_revisions))
+        .route("/designs/:id/diff", get(diff_design))
+        .route_layer(require(Scope::DesignsRead));
+
+    let writes = Router::new()
+        .route("/designs", post(create_design))
+        .route("/designs/:id", put(update_design).delete(delete_design))
+        .route_layer(require(Scope::DesignsWrite));
+
+    let admin = Router::new()
+        .route(
+            "/admin/workspaces",
+            get(list_workspaces).post(create_workspace),
+        )
+        .route("/admin/workspaces/:id/archive", post(archive_workspace))
+        .route_layer(require(Scope::Admin));
+
+    let docs = Router::new()
+        .route("/openapi.json", get(openapi_json))
+        .route(
+            "/schema/design-request.json",
+            get(design_request_schema_json),
+        );
+
+    // Rate limiting runs before authorization, per principal for valid
+    // credentials and per client address otherwise
+    let api = reads
+        .merge(writes)
+        .merge(admin)
+        .merge(docs)
+        .route_layer(from_fn_with_state(api_limiter, rate_limit))
+        .with_state(state);
+
+    let mut router = health.merge(api);
+    if config.metrics_enabled {
+        router = router.layer(from_fn_with_state(metrics, track_metrics));
+    }
+
+    let router = router
         .layer(axum::middleware::from_fn(security_headers))
-        .layer(TraceLayer::new_for_http())
+        .layer(TraceLayer::new_for_http().make_span_with(middleware::request_id::make_span))
+        .layer(axum::middleware::from_fn(request_id))
         .layer(RequestBodyLimitLayer::new(config.max_body_size))
-        .layer(cors);
+        .layer(cors_layer(cors_origins));
 
-    Ok(router)
+    Ok((router, reloader))
 }
 
 /// Build a default application router (for tests and backward compatibility)
@@ -45,8 +190,12 @@ pub fn app() -> Router {
 // Request/Response Types with Validation
 // ============================================================================
 
-#[derive(Deserialize, Serialize, Debug, Validate)]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Validate, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct DesignRequest {
+    /// Payload shape version; older payloads are upgraded before validation
+    #[serde(default = "current_schema_version")]
+    pub schema_version: u32,
     #[validate(nested)]
     pub purpose: Purpose,
     #[validate(nested)]
@@ -55,6 +204,7 @@ pub struct DesignRequest {
     pub model: ModelConfig,
     #[validate(nested)]
     pub tools: Tools,
+    #[validate(nested)]
     pub memory: Memory,
     #[validate(nested)]
     pub orchestration: Orchestration,
@@ -64,7 +214,40 @@ pub struct DesignRequest {
     pub testing: Testing,
 }
 
-#[derive(Deserialize, Serialize, Debug, Validate)]
+impl Default for DesignRequest {
+    fn default() -> Self {
+        DesignRequest {
+            schema_version: SCHEMA_VERSION,
+            purpose: Purpose::default(),
+            prompt: Prompt::default(),
+            model: ModelConfig::default(),
+            tools: Tools::default(),
+            memory: Memory::default(),
+            orchestration: Orchestration::default(),
+            interface: Interface::default(),
+            testing: Testing::default(),
+        }
+    }
+}
+
+fn current_schema_version() -> u32 {
+    SCHEMA_VERSION
+}
+
+impl DesignRequest {
+    /// Display title for the design, taken from its use case
+    pub fn title(&self) -> String {
+        let title = self.purpose.use_case.trim();
+        if title.is_empty() {
+            "Untitled Design".to_string()
+        } else {
+            title.to_string()
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Default, Validate, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct Purpose {
     #[validate(length(max = 500))]
     pub use_case: String,
@@ -76,7 +259,8 @@ pub struct Purpose {
     pub constraints: String,
 }
 
-#[derive(Deserialize, Serialize, Debug, Validate)]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Default, Validate, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct Prompt {
     #[validate(length(max = 2000))]
     pub goals: String,
@@ -88,10 +272,11 @@ pub struct Prompt {
     pub guardrails: String,
 }
 
-#[derive(Deserialize, Serialize, Debug, Validate)]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Default, Validate, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct ModelConfig {
     #[validate(length(max = 100))]
-    pub base_model: String,
+    pub base_model: BaseModel,
     #[validate(length(max = 500))]
     pub parameters: String,
     #[validate(length(max = 100))]
@@ -100,7 +285,8 @@ pub struct ModelConfig {
     pub cost_latency_tradeoff: String,
 }
 
-#[derive(Deserialize, Serialize, Debug, Validate)]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Default, Validate, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct Tools {
     #[validate(length(max = 50))]
     pub apis: Vec<String>,
@@ -108,49 +294,97 @@ pub struct Tools {
     pub mcp_servers: Vec<String>,
     #[validate(length(max = 5000))]
     pub custom_functions: String,
+    /// Structured tools, rendered as a catalog and function-calling JSON
+    #[serde(default)]
+    #[validate(
+        length(max = 50),
+        nested,
+        custom(function = "tools::validate_unique_names")
+    )]
+    pub definitions: Vec<ToolDefinition>,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Default, Validate, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct Memory {
     pub episodic: bool,
     pub working_memory: bool,
+    #[validate(length(max = 200))]
     pub vector_db: String,
+    #[validate(length(max = 200))]
     pub sql_db: String,
+    #[serde(default)]
+    #[validate(length(max = 200))]
+    pub file_storage: String,
 }
 
-#[derive(Deserialize, Serialize, Debug, Validate)]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Default, Validate, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct Orchestration {
     #[validate(length(max = 200))]
-    pub workflow: String,
+    pub workflow: WorkflowPattern,
     #[validate(length(max = 1000))]
     pub triggers: String,
     #[validate(length(max = 1000))]
     pub error_handling: String,
+    #[serde(default)]
+    #[validate(length(max = 500))]
+    pub message_queues: String,
 }
 
-#[derive(Deserialize, Serialize, Debug, Validate)]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Default, Validate, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct Interface {
     #[validate(length(max = 100))]
-    pub platform: String,
+    pub platform: Platform,
     #[validate(length(max = 200))]
-    pub interaction_mode: String,
+    pub interaction_mode: InteractionMode,
     #[validate(length(max = 500))]
     pub api_endpoint: String,
 }
 
-#[derive(Deserialize, Serialize, Debug, Validate)]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Default, Validate, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct Testing {
     #[validate(length(max = 100))]
     pub unit_tests: Vec<String>,
+    #[serde(default)]
+    #[validate(length(max = 1000))]
+    pub latency_testing: String,
     #[validate(length(max = 1000))]
     pub quality_metrics: String,
     #[validate(length(max = 2000))]
     pub evals: String,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 struct DesignResponse {
     markdown: String,
+    findings: Vec<Finding>,
+}
+
+#[derive(Serialize, ToSchema)]
+struct LintResponse {
+    findings: Vec<Finding>,
+}
+
+#[derive(Serialize, ToSchema)]
+struct DiagramResponse {
+    mermaid: String,
+}
+
+#[derive(Deserialize, Debug, Default, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct GenerateParams {
+    /// Template name; the built-in default is used when omitted
+    pub template: Option<String>,
+    /// Output format (`markdown`, `json` or `yaml`); overrides the `Accept` header
+    pub format: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+struct TemplatesResponse {
+    templates: Vec<String>,
 }
 
 // ============================================================================
@@ -159,120 +393,166 @@ struct DesignResponse {
 
 /// Validate a design request (for Tauri integration)
 pub fn validate_design_request(request: &DesignRequest) -> Result<(), String> {
-    request.validate().map_err(|e| {
-        e.field_errors()
-            .into_iter()
-            .flat_map(|(field, errors)| {
-                errors.iter().map(move |err| {
-                    format!(
-                        "{}: {}",
-                        field,
-                        err.message
-                            .as_ref()
-                            .map(|c| c.to_string())
-                            .unwrap_or_else(|| "invalid".to_string())
-                    )
-                })
-            })
-            .collect::<Vec<_>>()
-            .join("; ")
-    })
+    request
+        .validate()
+        .map_err(|e| extractors::validation_message(&e))
 }
 
-/// Generate design document from request (pure logic)
+/// Generate design document from request using the built-in template and
+/// lint rules (pure logic)
 pub fn core_generate_design(payload: &DesignRequest) -> String {
-    format!(
-        r#"# System Design Specification: {}
-
-## 1. Purpose & Scope
-- **Use Case:** {}
-- **User Needs:** {}
-- **Success Criteria:** {}
-- **Constraints:** {}
-
-## 2. System Prompt Design
-- **Role & Persona:** {}
-- **Primary Goals:** {}
-- **Instructions:** {}
-- **Guardrails:** {}
-
-## 3. Model Selection
-- **Base Model:** {}
-- **Parameters:** {}
-- **Context Window:** {}
-- **Tradeoffs:** {}
-
-## 4. Tools & Integrations
-- **External APIs:** {:?}
-- **MCP Servers:** {:?}
-- **Custom Functions:** {}
-
-## 5. Memory Systems
-- **Episodic Memory:** {}
-- **Working Memory:** {}
-- **Vector Database:** {}
-- **SQL/Structured DB:** {}
-
-## 6. Orchestration
-- **Workflow Pattern:** {}
-- **System Triggers:** {}
-- **Error Handling:** {}
-
-## 7. User Interface
-- **Platform:** {}
-- **Interaction Mode:** {}
-- **API Strategy:** {}
-
-## 8. Testing & Evals
-- **Unit Tests:** {:?}
-- **Quality Metrics:** {}
-- **Evaluation Strategy:** {}
-
----
-*Generated by System Designer Agent*"#,
-        payload.purpose.use_case,
-        payload.purpose.use_case,
-        payload.purpose.user_needs,
-        payload.purpose.success_criteria,
-        payload.purpose.constraints,
-        payload.prompt.role,
-        payload.prompt.goals,
-        payload.prompt.instructions,
-        payload.prompt.guardrails,
-        payload.model.base_model,
-        payload.model.parameters,
-        payload.model.context_window,
-        payload.model.cost_latency_tradeoff,
-        payload.tools.apis,
-        payload.tools.mcp_servers,
-        payload.tools.custom_functions,
-        payload.memory.episodic,
-        payload.memory.working_memory,
-        payload.memory.vector_db,
-        payload.memory.sql_db,
-        payload.orchestration.workflow,
-        payload.orchestration.triggers,
-        payload.orchestration.error_handling,
-        payload.interface.platform,
-        payload.interface.interaction_mode,
-        payload.interface.api_endpoint,
-        payload.testing.unit_tests,
-        payload.testing.quality_metrics,
-        payload.testing.evals
-    )
+    let findings = lint::builtin().lint(payload);
+    renderer::builtin()
+        .render(DEFAULT_TEMPLATE, payload, &findings)
+        .expect("built-in template renders every DesignRequest")
 }
 
 // ============================================================================
 // HTTP Handler
 // ============================================================================
 
+#[utoipa::path(
+    post,
+    path = "/generate",
+    tag = "generate",
+    params(GenerateParams),
+    request_body = DesignRequest,
+    responses(
+        (status = 200, description = "Rendered Markdown with lint findings, or the design spec when `format` or `Accept` asks for it", content(
+            (DesignResponse = "application/json"),
+            (DesignSpec = "application/vnd.system-designer.spec+json"),
+            (String = "application/yaml"),
+        )),
+        (status = 400, description = "Malformed payload, unknown template or unsupported format", body = ErrorResponse),
+        (status = 422, description = "A field exceeds its limits", body = ErrorResponse),
+    )
+)]
 async fn generate_design(
+    tenant: Tenant,
+    metrics: Option<Extension<Arc<Metrics>>>,
+    Query(params): Query<GenerateParams>,
+    headers: HeaderMap,
     ValidatedJson(payload): ValidatedJson<DesignRequest>,
-) -> AppResult<Json<DesignResponse>> {
-    info!(use_case = %payload.purpose.use_case, "Processing design generation request");
+) -> AppResult<Response> {
+    let format = OutputFormat::negotiate(params.format.as_deref(), &headers)?;
+    info!(
+        use_case_len = payload.purpose.use_case.len(),
+        ?format,
+        "Processing design generation request"
+    );
+    let observe_output = |format: &str, bytes: usize| {
+        if let Some(Extension(metrics)) = &metrics {
+            metrics.observe_output(format, bytes);
+        }
+    };
+
+    let response = match format {
+        OutputFormat::Markdown => {
+            let template = params.template.as_deref().unwrap_or(DEFAULT_TEMPLATE);
+            let findings = tenant.linter.lint(&payload);
+            let md = tenant.renderer.render(template, &payload, &findings)?;
+            observe_output("markdown", md.len());
+            info!(
+                output_length = md.len(),
+                template,
+                findings = findings.len(),
+                "Design generation complete"
+            );
+            Json(DesignResponse {
+                markdown: md,
+                findings,
+            })
+            .into_response()
+        }
+        OutputFormat::Json => {
+            let body = DesignSpec::from_request(&payload).to_json()?;
+            observe_output("json", body.len());
+            info!(
+                output_length = body.len(),
+                "Design spec generation complete"
+            );
+            ([(header::CONTENT_TYPE, "application/json")], body).into_response()
+        }
+        OutputFormat::Yaml => {
+            let body = DesignSpec::from_request(&payload).to_yaml()?;
+            observe_output("yaml", body.len());
+            info!(
+                output_length = body.len(),
+                "Design spec generation complete"
+            );
+            ([(header::CONTENT_TYPE, "application/yaml")], body).into_response()
+        }
+    };
+
+    Ok(response)
+}
 
-    let md = core_generate_design(&payload);
+#[utoipa::path(
+    get,
+    path = "/templates",
+    tag = "generate",
+    responses((status = 200, description = "Templates available in the workspace", body = TemplatesResponse))
+)]
+async fn list_templates(tenant: Tenant) -> Json<TemplatesResponse> {
+    Json(TemplatesResponse {
+        templates: tenant.renderer.template_names(),
+    })
+}
 
-    info!(output_length = md.len(), "Design generation complete");
-    Ok(Json(DesignResponse { markdown: md }))
+#[utoipa::path(
+    post,
+    path = "/import",
+    tag = "generate",
+    request_body(content = String, description = "A generated `DESIGN_SPEC.md`", content_type = "text/markdown"),
+    responses(
+        (status = 200, description = "The recovered request and anything that could not be mapped", body = ImportReport),
+        (status = 400, description = "No design sections found", body = ErrorResponse),
+        (status = 422, description = "The recovered request exceeds field limits", body = ErrorResponse),
+    )
+)]
+async fn import_design(markdown: String) -> AppResult<Json<ImportReport>> {
+    let report = parse_design_markdown(&markdown)?;
+    validate_design_request(&report.request).map_err(AppError::Validation)?;
+
+    info!(issues = report.issues.len(), "Design import complete");
+    Ok(Json(report))
+}
+
+#[utoipa::path(
+    post,
+    path = "/lint",
+    tag = "generate",
+    request_body = DesignRequest,
+    responses(
+        (status = 200, description = "Lint findings", body = LintResponse),
+        (status = 400, description = "Malformed payload", body = ErrorResponse),
+        (status = 422, description = "A field exceeds its limits", body = ErrorResponse),
+    )
+)]
+async fn lint_design(
+    tenant: Tenant,
+    ValidatedJson(payload): ValidatedJson<DesignRequest>,
+) -> Json<LintResponse> {
+    Json(LintResponse {
+        findings: tenant.linter.lint(&payload),
+    })
+}
+
+#[utoipa::path(
+    post,
+    path = "/diagram",
+    tag = "generate",
+    request_body = DesignRequest,
+    responses(
+        (status = 200, description = "Mermaid flowchart source", body = DiagramResponse),
+        (status = 400, description = "Malformed payload", body = ErrorResponse),
+        (status = 422, description = "A field exceeds its limits", body = ErrorResponse),
+    )
+)]
+async fn diagram_design(
+    ValidatedJson(payload): ValidatedJson<DesignRequest>,
+) -> Json<DiagramResponse> {
+    Json(DiagramResponse {
+        mermaid: diagram::mermaid_flowchart(&payload),
+    })
 }
@@ -0,0 +1,164 @@
+pub mod rules;
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::config::Config;
+use crate::error::AppError;
+use crate::DesignRequest;
+
+use self::rules::{Rule, RULES};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+    Info,
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Info => "info",
+        };
+        f.write_str(label)
+    }
+}
+
+/// A problem reported by a lint rule
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, ToSchema)]
+pub struct Finding {
+    pub rule_id: String,
+    pub severity: Severity,
+    /// Dotted path of the offending field, e.g. `memory.episodic`
+    pub path: String,
+    pub message: String,
+}
+
+/// Per-rule overrides from the lint configuration file
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct RuleConfig {
+    pub enabled: Option<bool>,
+    pub severity: Option<Severity>,
+}
+
+/// Lint configuration file layout:
+///
+/// ```toml
+/// [rules.mcp-without-guardrails]
+/// severity = "error"
+///
+/// [rules.no-evals]
+/// enabled = false
+/// ```
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct LintConfig {
+    #[serde(default)]
+    pub rules: HashMap<String, RuleConfig>,
+}
+
+impl LintConfig {
+    pub fn from_toml(source: &str) -> Result<Self, AppError> {
+        let config: LintConfig = toml::from_str(source)
+            .map_err(|e| AppError::Config(format!("Invalid lint configuration: {}", e)))?;
+
+        if let Some(unknown) = config
+            .rules
+            .keys()
+            .find(|id| !RULES.iter().any(|rule| rule.id == id.as_str()))
+        {
+            return Err(AppError::Config(format!("Unknown lint rule: {}", unknown)));
+        }
+
+        Ok(config)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, AppError> {
+        let source = fs::read_to_string(path).map_err(|e| {
+            AppError::Config(format!(
+                "Cannot read lint configuration {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        Self::from_toml(&source)
+    }
+}
+
+/// Rule-based checks for consistency across `DesignRequest` sections
+pub struct Linter {
+    rules: Vec<(&'static Rule, Severity)>,
+}
+
+impl Linter {
+    /// Create a linter with every built-in rule at its default severity
+    pub fn new() -> Self {
+        Self::with_config(&LintConfig::default())
+    }
+
+    pub fn with_config(config: &LintConfig) -> Self {
+        let rules = RULES
+            .iter()
+            .filter_map(|rule| {
+                let overrides = config.rules.get(rule.id).cloned().unwrap_or_default();
+                if !overrides.enabled.unwrap_or(true) {
+                    return None;
+                }
+                Some((rule, overrides.severity.unwrap_or(rule.severity)))
+            })
+            .collect();
+
+        Linter { rules }
+    }
+
+    /// Create a linter from the configured rules file, if any
+    pub fn from_config(config: &Config) -> Result<Self, AppError> {
+        match &config.lint_config {
+            Some(path) => Ok(Self::with_config(&LintConfig::load(path)?)),
+            None => Ok(Self::new()),
+        }
+    }
+
+    /// Run every enabled rule, most severe findings first
+    pub fn lint(&self, request: &DesignRequest) -> Vec<Finding> {
+        let mut findings: Vec<Finding> = self
+            .rules
+            .iter()
+            .flat_map(|(rule, severity)| {
+                (rule.check)(request)
+                    .into_iter()
+                    .map(move |(path, message)| Finding {
+                        rule_id: rule.id.to_string(),
+                        severity: *severity,
+                        path: path.to_string(),
+                        message,
+                    })
+            })
+            .collect();
+
+        findings.sort_by_key(|finding| finding.severity);
+        findings
+    }
+}
+
+impl Default for Linter {
+    fn default() -> Self {
+        Linter::new()
+    }
+}
+
+/// Shared linter with the built-in rule set
+pub fn builtin() -> &'static Linter {
+    static BUILTIN: OnceLock<Linter> = OnceLock::new();
+    BUILTIN.get_or_init(Linter::new)
+}
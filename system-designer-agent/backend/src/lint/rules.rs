@@ -0,0 +1,196 @@
+use super::Severity;
+use crate::choices::WorkflowPattern;
+use crate::tools::SideEffect;
+use crate::DesignRequest;
+
+/// Field path and message for each problem a rule finds
+pub type Check = fn(&DesignRequest) -> Vec<(&'static str, String)>;
+
+/// A built-in lint rule
+pub struct Rule {
+    pub id: &'static str,
+    pub severity: Severity,
+    pub description: &'static str,
+    pub check: Check,
+}
+
+/// Every built-in rule, in evaluation order
+pub const RULES: &[Rule] = &[
+    Rule {
+        id: "missing-use-case",
+        severity: Severity::Error,
+        description: "The design has no use case",
+        check: missing_use_case,
+    },
+    Rule {
+        id: "episodic-memory-without-store",
+        severity: Severity::Warning,
+        description: "Episodic memory is enabled but no vector or SQL store is configured",
+        check: episodic_memory_without_store,
+    },
+    Rule {
+        id: "mcp-without-guardrails",
+        severity: Severity::Warning,
+        description: "MCP servers are listed but the prompt has no guardrails",
+        check: mcp_without_guardrails,
+    },
+    Rule {
+        id: "destructive-tool-without-guardrails",
+        severity: Severity::Warning,
+        description: "A destructive tool is defined but the prompt has no guardrails",
+        check: destructive_tool_without_guardrails,
+    },
+    Rule {
+        id: "tools-without-error-handling",
+        severity: Severity::Warning,
+        description: "External tools are used but no error handling strategy is set",
+        check: tools_without_error_handling,
+    },
+    Rule {
+        id: "missing-base-model",
+        severity: Severity::Warning,
+        description: "No base model has been selected",
+        check: missing_base_model,
+    },
+    Rule {
+        id: "unrecognized-workflow",
+        severity: Severity::Info,
+        description: "The workflow does not follow a known orchestration pattern",
+        check: unrecognized_workflow,
+    },
+    Rule {
+        id: "missing-success-criteria",
+        severity: Severity::Info,
+        description: "The design does not state how success is measured",
+        check: missing_success_criteria,
+    },
+    Rule {
+        id: "no-evals",
+        severity: Severity::Info,
+        description: "Neither unit tests nor an evaluation strategy are defined",
+        check: no_evals,
+    },
+];
+
+fn blank(value: &str) -> bool {
+    value.trim().is_empty()
+}
+
+fn missing_use_case(request: &DesignRequest) -> Vec<(&'static str, String)> {
+    if blank(&request.purpose.use_case) {
+        vec![(
+            "purpose.use_case",
+            "Describe the use case the agent serves".to_string(),
+        )]
+    } else {
+        vec![]
+    }
+}
+
+fn episodic_memory_without_store(request: &DesignRequest) -> Vec<(&'static str, String)> {
+    let memory = &request.memory;
+    if memory.episodic && blank(&memory.vector_db) && blank(&memory.sql_db) {
+        vec![(
+            "memory.episodic",
+            "Episodic memory needs a vector database or SQL store to persist episodes".to_string(),
+        )]
+    } else {
+        vec![]
+    }
+}
+
+fn mcp_without_guardrails(request: &DesignRequest) -> Vec<(&'static str, String)> {
+    if !request.tools.mcp_servers.is_empty() && blank(&request.prompt.guardrails) {
+        vec![(
+            "prompt.guardrails",
+            format!(
+                "{} MCP server(s) are connected but no guardrails limit what the agent may do with them",
+                request.tools.mcp_servers.len()
+            ),
+        )]
+    } else {
+        vec![]
+    }
+}
+
+fn destructive_tool_without_guardrails(request: &DesignRequest) -> Vec<(&'static str, String)> {
+    let destructive: Vec<&str> = request
+        .tools
+        .definitions
+        .iter()
+        .filter(|tool| tool.side_effect == SideEffect::Destructive)
+        .map(|tool| tool.name.as_str())
+        .collect();
+    if !destructive.is_empty() && blank(&request.prompt.guardrails) {
+        vec![(
+            "prompt.guardrails",
+            format!(
+                "Destructive tool(s) {} need guardrails saying when the agent may call them and who confirms",
+                destructive.join(", ")
+            ),
+        )]
+    } else {
+        vec![]
+    }
+}
+
+fn tools_without_error_handling(request: &DesignRequest) -> Vec<(&'static str, String)> {
+    let tools = &request.tools;
+    let uses_tools =
+        !tools.apis.is_empty() || !tools.mcp_servers.is_empty() || !tools.definitions.is_empty();
+    if uses_tools && blank(&request.orchestration.error_handling) {
+        vec![(
+            "orchestration.error_handling",
+            "Describe how tool failures, timeouts and retries are handled".to_string(),
+        )]
+    } else {
+        vec![]
+    }
+}
+
+fn missing_base_model(request: &DesignRequest) -> Vec<(&'static str, String)> {
+    if request.model.base_model.is_blank() {
+        vec![("model.base_model", "Select a base model".to_string())]
+    } else {
+        vec![]
+    }
+}
+
+fn unrecognized_workflow(request: &DesignRequest) -> Vec<(&'static str, String)> {
+    let workflow = &request.orchestration.workflow;
+    if workflow.is_custom() && workflow.pattern().is_none() {
+        vec![(
+            "orchestration.workflow",
+            format!(
+                "\"{}\" is not a known pattern ({}); the architecture diagram falls back to a generic layout",
+                workflow.as_str().trim(),
+                WorkflowPattern::KNOWN.join(", ")
+            ),
+        )]
+    } else {
+        vec![]
+    }
+}
+
+fn missing_success_criteria(request: &DesignRequest) -> Vec<(&'static str, String)> {
+    if blank(&request.purpose.success_criteria) {
+        vec![(
+            "purpose.success_criteria",
+            "Add measurable success criteria".to_string(),
+        )]
+    } else {
+        vec![]
+    }
+}
+
+fn no_evals(request: &DesignRequest) -> Vec<(&'static str, String)> {
+    let testing = &request.testing;
+    if testing.unit_tests.is_empty() && blank(&testing.evals) {
+        vec![(
+            "testing.evals",
+            "Add unit tests or an evaluation strategy".to_string(),
+        )]
+    } else {
+        vec![]
+    }
+}
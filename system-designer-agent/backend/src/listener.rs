@@ -0,0 +1,402 @@
+use std::fmt;
+use std::future::Future;
+use std::io;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+
+use axum::extract::ConnectInfo;
+use axum::middleware::from_fn_with_state;
+use axum::Router;
+use hyper::body::Incoming;
+use hyper::Request;
+use hyper_util::rt::{TokioExecutor, TokioIo};
+use hyper_util::server::conn::auto;
+use hyper_util::server::graceful::{GracefulShutdown, Watcher};
+use hyper_util::service::TowerToHyperService;
+use serde::{Serialize, Serializer};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::{TcpListener, TcpStream};
+use tokio_rustls::TlsAcceptor;
+use tower::ServiceExt;
+use tracing::{debug, info, warn};
+
+use crate::config::Config;
+use crate::middleware::{track_in_flight, InFlight};
+use crate::tls::TlsConfig;
+
+/// Connections that have not finished the TLS handshake by then are dropped
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// First descriptor passed by systemd socket activation
+#[cfg(unix)]
+const SD_LISTEN_FDS_START: std::os::unix::io::RawFd = 3;
+
+/// Where the API accepts connections
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum Listen {
+    /// `host`:`port`
+    #[default]
+    Tcp,
+    /// A Unix domain socket at this path
+    Unix(PathBuf),
+    /// The socket passed by systemd socket activation (`LISTEN_FDS`)
+    Systemd,
+}
+
+impl FromStr for Listen {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "tcp" => Ok(Listen::Tcp),
+            "systemd" => Ok(Listen::Systemd),
+            other => match other.strip_prefix("unix:") {
+                Some(path) if !path.is_empty() => Ok(Listen::Unix(PathBuf::from(path))),
+                _ => Err(format!(
+                    "Unknown listener {}; expected tcp, unix:<path> or systemd",
+                    other
+                )),
+            },
+        }
+    }
+}
+
+impl fmt::Display for Listen {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Listen::Tcp => write!(f, "tcp"),
+            Listen::Unix(path) => write!(f, "unix:{}", path.display()),
+            Listen::Systemd => write!(f, "systemd"),
+        }
+    }
+}
+
+impl Serialize for Listen {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// Unix file permissions, written in octal (`660` or `0660`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SocketMode(pub u32);
+
+impl Default for SocketMode {
+    /// Owner and group may connect
+    fn default() -> Self {
+        SocketMode(0o660)
+    }
+}
+
+impl FromStr for SocketMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let digits = s.trim().trim_start_matches("0o");
+        match u32::from_str_radix(digits, 8) {
+            Ok(mode) if mode <= 0o777 => Ok(SocketMode(mode)),
+            _ => Err(format!("Invalid socket mode {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for SocketMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04o}", self.0)
+    }
+}
+
+impl Serialize for SocketMode {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// A bound API listener
+pub enum Listener {
+    Tcp(TcpListener),
+    #[cfg(unix)]
+    Unix {
+        listener: tokio::net::UnixListener,
+        /// Socket file to remove on shutdown; `None` when systemd owns it
+        path: Option<PathBuf>,
+    },
+}
+
+impl Listener {
+    /// Bind the listener selected by `config.listen`
+    pub async fn bind(config: &Config) -> io::Result<Self> {
+        match &config.listen {
+            Listen::Tcp => {
+                let addr = SocketAddr::from((config.host, config.port));
+                Ok(Listener::Tcp(TcpListener::bind(addr).await?))
+            }
+            #[cfg(unix)]
+            Listen::Unix(path) => Listener::bind_unix(path.clone(), config.unix_socket_mode),
+            #[cfg(unix)]
+            Listen::Systemd => Listener::from_systemd(),
+            #[cfg(not(unix))]
+            other => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                format!("Listener {} needs a Unix platform", other),
+            )),
+        }
+    }
+
+    /// Bind a Unix socket with `mode` permissions. A stale socket file
+    /// left by a previous run is replaced; one that still accepts
+    /// connections is an error.
+    #[cfg(unix)]
+    pub fn bind_unix(path: PathBuf, mode: SocketMode) -> io::Result<Self> {
+        use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+
+        if let Ok(metadata) = std::fs::symlink_metadata(&path) {
+            if !metadata.file_type().is_socket() {
+                return Err(io::Error::new(
+                    io::ErrorKind::AlreadyExists,
+                    format!("{} exists and is not a socket", path.display()),
+                ));
+            }
+            if std::os::unix::net::UnixStream::connect(&path).is_ok() {
+                return Err(io::Error::new(
+                    io::ErrorKind::AddrInUse,
+                    format!("{} is in use by another process", path.display()),
+                ));
+            }
+            std::fs::remove_file(&path)?;
+        }
+
+        let listener = tokio::net::UnixListener::bind(&path)?;
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(mode.0))?;
+        Ok(Listener::Unix {
+            listener,
+            path: Some(path),
+        })
+    }
+
+    /// The socket systemd passed to this process, checked against
+    /// `LISTEN_PID` and `LISTEN_FDS`. The variables are removed once the
+    /// socket is adopted so child processes do not try to claim it too.
+    #[cfg(unix)]
+    pub fn from_systemd() -> io::Result<Self> {
+        let for_us = std::env::var("LISTEN_PID")
+            .ok()
+            .and_then(|pid| pid.trim().parse::<u32>().ok())
+            == Some(std::process::id());
+        let count: u32 = std::env::var("LISTEN_FDS")
+            .ok()
+            .and_then(|n| n.trim().parse().ok())
+            .unwrap_or(0);
+        if !for_us || count == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                "No socket passed by systemd (LISTEN_PID/LISTEN_FDS)",
+            ));
+        }
+        if count > 1 {
+            warn!(count, "systemd passed several sockets; using the first");
+        }
+
+        // SAFETY: LISTEN_PID names this process, so systemd passed the
+        // descriptor to us and nothing else in the process owns it
+        let listener = unsafe { Listener::from_raw_fd(SD_LISTEN_FDS_START) };
+        for name in ["LISTEN_PID", "LISTEN_FDS", "LISTEN_FDNAMES"] {
+            std::env::remove_var(name);
+        }
+        listener
+    }
+
+    /// Take ownership of an inherited listening socket, TCP or Unix
+    ///
+    /// # Safety
+    ///
+    /// `fd` must be an open listening socket that nothing else owns.
+    #[cfg(unix)]
+    pub unsafe fn from_raw_fd(fd: std::os::unix::io::RawFd) -> io::Result<Self> {
+        use std::os::unix::io::{FromRawFd, IntoRawFd};
+
+        // `local_addr` fails for sockets of another address family
+        let unix = std::os::unix::net::UnixListener::from_raw_fd(fd);
+        if unix.local_addr().is_ok() {
+            unix.set_nonblocking(true)?;
+            return Ok(Listener::Unix {
+                listener: tokio::net::UnixListener::from_std(unix)?,
+                path: None,
+            });
+        }
+
+        let tcp = std::net::TcpListener::from_raw_fd(unix.into_raw_fd());
+        tcp.local_addr()?;
+        tcp.set_nonblocking(true)?;
+        Ok(Listener::Tcp(TcpListener::from_std(tcp)?))
+    }
+
+    /// Address for logs
+    pub fn describe(&self) -> String {
+        match self {
+            Listener::Tcp(listener) => match listener.local_addr() {
+                Ok(addr) => addr.to_string(),
+                Err(_) => "tcp".to_string(),
+            },
+            #[cfg(unix)]
+            Listener::Unix { listener, .. } => match listener
+                .local_addr()
+                .ok()
+                .and_then(|addr| addr.as_pathname().map(|p| p.to_path_buf()))
+            {
+                Some(path) => format!("unix:{}", path.display()),
+                None => "unix".to_string(),
+            },
+        }
+    }
+
+    async fn accept(&self) -> io::Result<Connection> {
+        match self {
+            Listener::Tcp(listener) => {
+                let (stream, peer) = listener.accept().await?;
+                Ok(Connection::Tcp(stream, peer))
+            }
+            #[cfg(unix)]
+            Listener::Unix { listener, .. } => {
+                let (stream, _) = listener.accept().await?;
+                Ok(Connection::Unix(stream))
+            }
+        }
+    }
+}
+
+impl Drop for Listener {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        if let Listener::Unix {
+            path: Some(path), ..
+        } = self
+        {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+enum Connection {
+    Tcp(TcpStream, SocketAddr),
+    #[cfg(unix)]
+    Unix(tokio::net::UnixStream),
+}
+
+/// Serve `app` on `listener`, over TLS when `tls` is set, until `shutdown`
+/// completes. Then stop accepting and give open requests up to
+/// `drain_timeout` to finish. Returns how many requests were still running
+/// when the timeout cut them off.
+///
+/// Requests over a Unix socket carry a loopback `ConnectInfo`, so a local
+/// proxy is rate limited as `127.0.0.1` unless listed in `trusted_proxies`.
+pub async fn serve<F>(
+    listener: Listener,
+    app: Router,
+    tls: Option<TlsConfig>,
+    shutdown: F,
+    drain_timeout: Duration,
+) -> usize
+where
+    F: Future<Output = ()> + Send,
+{
+    let in_flight = InFlight::default();
+    let app = app.layer(from_fn_with_state(in_flight.clone(), track_in_flight));
+    let builder = auto::Builder::new(TokioExecutor::new());
+    let graceful = GracefulShutdown::new();
+    tokio::pin!(shutdown);
+
+    loop {
+        let connection = tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok(connection) => connection,
+                Err(e) => {
+                    // Usually out of file descriptors; back off instead of spinning
+                    warn!(error = %e, "Failed to accept connection");
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                    continue;
+                }
+            },
+            _ = &mut shutdown => break,
+        };
+
+        let acceptor = tls.as_ref().map(TlsConfig::acceptor);
+        let app = app.clone();
+        let builder = builder.clone();
+        let watcher = graceful.watcher();
+        match connection {
+            Connection::Tcp(stream, peer) => {
+                tokio::spawn(handle(stream, peer, acceptor, app, builder, watcher));
+            }
+            #[cfg(unix)]
+            Connection::Unix(stream) => {
+                let peer = SocketAddr::from((std::net::Ipv4Addr::LOCALHOST, 0));
+                tokio::spawn(handle(stream, peer, acceptor, app, builder, watcher));
+            }
+        }
+    }
+
+    drop(listener);
+    info!(
+        in_flight = in_flight.count(),
+        timeout_secs = drain_timeout.as_secs_f64(),
+        "Draining in-flight requests"
+    );
+    match tokio::time::timeout(drain_timeout, graceful.shutdown()).await {
+        Ok(()) => {
+            info!("All in-flight requests completed");
+            0
+        }
+        Err(_) => {
+            let aborted = in_flight.count();
+            warn!(
+                aborted,
+                "Drain timeout expired; aborting in-flight requests"
+            );
+            aborted
+        }
+    }
+}
+
+async fn handle<I>(
+    io: I,
+    peer: SocketAddr,
+    acceptor: Option<TlsAcceptor>,
+    app: Router,
+    builder: auto::Builder<TokioExecutor>,
+    watcher: Watcher,
+) where
+    I: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    let Some(acceptor) = acceptor else {
+        return serve_connection(io, peer, app, builder, watcher).await;
+    };
+    match tokio::time::timeout(HANDSHAKE_TIMEOUT, acceptor.accept(io)).await {
+        Ok(Ok(stream)) => serve_connection(stream, peer, app, builder, watcher).await,
+        Ok(Err(e)) => debug!(peer = %peer, error = %e, "TLS handshake failed"),
+        Err(_) => debug!(peer = %peer, "TLS handshake timed out"),
+    }
+}
+
+async fn serve_connection<I>(
+    io: I,
+    peer: SocketAddr,
+    app: Router,
+    builder: auto::Builder<TokioExecutor>,
+    watcher: Watcher,
+) where
+    I: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    let service = app.map_request(move |mut req: Request<Incoming>| {
+        req.extensions_mut().insert(ConnectInfo::<SocketAddr>(peer));
+        req
+    });
+    let conn = builder
+        .serve_connection_with_upgrades(TokioIo::new(io), TowerToHyperService::new(service))
+        .into_owned();
+    if let Err(e) = watcher.watch(conn).await {
+        debug!(peer = %peer, error = %e, "Connection closed with error");
+    }
+}
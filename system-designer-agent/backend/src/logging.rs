@@ -1,17 +1,334 @@
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
+use std::borrow::Cow;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-pub fn init(level: &str) -> anyhow::Result<()> {
-    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(level));
+use opentelemetry::trace::TracerProvider;
+use opentelemetry::KeyValue;
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::error::{OTelSdkError, OTelSdkResult};
+use opentelemetry_sdk::trace::{SdkTracerProvider, SpanData, SpanExporter};
+use opentelemetry_sdk::Resource;
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use tracing::Subscriber;
+use tracing_subscriber::field::MakeExt;
+use tracing_subscriber::fmt::format::debug_fn;
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::{
+    layer::SubscriberExt, reload, util::SubscriberInitExt, EnvFilter, Layer, Registry,
+};
 
-    let fmt_layer = tracing_subscriber::fmt::layer()
-        .with_target(true)
-        .with_file(true)
-        .with_line_number(true);
+use crate::config::Config;
+use crate::error::AppError;
+use crate::redact::{RedactingMakeWriter, Redactor, MASK};
+
+/// `service.name` reported on exported spans
+pub const SERVICE_NAME: &str = "system-designer";
+
+/// Console log line format
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// Human-readable lines
+    #[default]
+    Text,
+    /// One JSON object per event, for log shippers
+    Json,
+}
+
+impl FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "text" | "pretty" => Ok(LogFormat::Text),
+            "json" => Ok(LogFormat::Json),
+            other => Err(format!("Unknown log format: {}", other)),
+        }
+    }
+}
+
+/// Keeps span exporters alive; dropping it flushes pending spans
+pub struct Telemetry {
+    provider: Option<SdkTracerProvider>,
+    filter: LogFilter,
+}
+
+impl Telemetry {
+    /// Handle for changing the log filter while the server runs
+    pub fn log_filter(&self) -> LogFilter {
+        self.filter.clone()
+    }
+}
+
+/// Replaces the filter of the installed subscriber
+#[derive(Clone)]
+pub struct LogFilter(reload::Handle<EnvFilter, Registry>);
+
+impl LogFilter {
+    /// Switch to `directives`, e.g. `info,backend=debug`
+    pub fn set(&self, directives: &str) -> Result<(), AppError> {
+        self.replace(LogFilter::parse(directives)?)
+    }
+
+    /// Check `directives` without applying them
+    pub fn parse(directives: &str) -> Result<EnvFilter, AppError> {
+        EnvFilter::try_new(directives)
+            .map_err(|e| AppError::Config(format!("Invalid log filter {}: {}", directives, e)))
+    }
+
+    /// Swap in a parsed filter; fails only once the subscriber is gone
+    pub fn replace(&self, filter: EnvFilter) -> Result<(), AppError> {
+        self.0
+            .reload(filter)
+            .map_err(|e| AppError::Internal(e.into()))
+    }
+}
+
+impl From<reload::Handle<EnvFilter, Registry>> for LogFilter {
+    fn from(handle: reload::Handle<EnvFilter, Registry>) -> Self {
+        LogFilter(handle)
+    }
+}
+
+impl Drop for Telemetry {
+    fn drop(&mut self) {
+        if let Some(provider) = self.provider.take() {
+            if let Err(e) = provider.shutdown() {
+                eprintln!("Failed to flush trace spans: {}", e);
+            }
+        }
+    }
+}
+
+pub fn init(config: &Config) -> anyhow::Result<Telemetry> {
+    let filter =
+        EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(&config.log_level));
+    let (filter, handle) = reload::Layer::new(filter);
+
+    let fmt_layer = fmt_layer(config, std::io::stdout)?;
+
+    let provider = tracer_provider(config)?;
+    let otel_layer = provider
+        .as_ref()
+        .map(|provider| tracing_opentelemetry::layer().with_tracer(provider.tracer(SERVICE_NAME)));
 
     tracing_subscriber::registry()
         .with(filter)
         .with(fmt_layer)
+        .with(otel_layer)
         .init();
 
-    Ok(())
+    Ok(Telemetry {
+        provider,
+        filter: LogFilter(handle),
+    })
+}
+
+/// Console layer in the configured format writing to `writer`. Sensitive
+/// fields are masked by name and emails, API keys and tokens by pattern
+/// before anything is written.
+pub fn fmt_layer<S, W>(
+    config: &Config,
+    writer: W,
+) -> Result<Box<dyn Layer<S> + Send + Sync>, AppError>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    W: for<'w> MakeWriter<'w> + Send + Sync + 'static,
+{
+    let redactor = Arc::new(Redactor::new(
+        &config.log_redact_fields,
+        &config.log_redact_patterns,
+    )?);
+
+    let layer = tracing_subscriber::fmt::layer()
+        .with_target(true)
+        .with_file(true)
+        .with_line_number(true);
+
+    let layer = match config.log_format {
+        LogFormat::Text => {
+            // Field values are redacted as they are formatted, since text
+            // lines have no reliable field boundaries afterwards
+            let fields = debug_fn(move |writer, field, value| {
+                let name = field.name();
+                if redactor.is_sensitive(name) {
+                    return write!(writer, "{}={}", name, MASK);
+                }
+                let value = format!("{:?}", value);
+                if name == "message" {
+                    write!(writer, "{}", redactor.redact(&value))
+                } else {
+                    write!(writer, "{}={}", name, redactor.redact(&value))
+                }
+            })
+            .delimited(" ");
+            layer.fmt_fields(fields).with_writer(writer).boxed()
+        }
+        LogFormat::Json => layer
+            .json()
+            .with_current_span(true)
+            .with_writer(RedactingMakeWriter::new(writer, redactor))
+            .boxed(),
+    };
+    Ok(layer)
+}
+
+/// Span pipeline for the configured exporters: OTLP over HTTP
+/// (`APP_OTLP_ENDPOINT`) and/or a JSON lines file (`APP_TRACE_FILE`).
+/// Attributes are redacted like console fields before they leave the
+/// process. `None` when neither exporter is set.
+pub fn tracer_provider(config: &Config) -> anyhow::Result<Option<SdkTracerProvider>> {
+    if config.otlp_endpoint.is_none() && config.trace_file.is_none() {
+        return Ok(None);
+    }
+
+    let redactor = Arc::new(Redactor::new(
+        &config.log_redact_fields,
+        &config.log_redact_patterns,
+    )?);
+
+    let mut builder = SdkTracerProvider::builder()
+        .with_resource(Resource::builder().with_service_name(SERVICE_NAME).build());
+
+    if let Some(endpoint) = &config.otlp_endpoint {
+        let exporter = opentelemetry_otlp::SpanExporter::builder()
+            .with_http()
+            .with_endpoint(endpoint)
+            .build()?;
+        builder = builder.with_batch_exporter(RedactingExporter::new(exporter, redactor.clone()));
+    }
+    if let Some(path) = &config.trace_file {
+        let exporter = FileExporter::create(path)?;
+        builder = builder.with_simple_exporter(RedactingExporter::new(exporter, redactor));
+    }
+
+    Ok(Some(builder.build()))
+}
+
+/// Masks sensitive span and event attributes by name, and emails, API keys
+/// and tokens by pattern, before `inner` exports them
+#[derive(Debug)]
+pub struct RedactingExporter<E> {
+    inner: E,
+    redactor: Arc<Redactor>,
+}
+
+impl<E> RedactingExporter<E> {
+    pub fn new(inner: E, redactor: Arc<Redactor>) -> Self {
+        RedactingExporter { inner, redactor }
+    }
+
+    fn redact(&self, attributes: &mut [KeyValue]) {
+        for attribute in attributes {
+            if self.redactor.is_sensitive(attribute.key.as_str()) {
+                attribute.value = MASK.into();
+            } else if let opentelemetry::Value::String(text) = &attribute.value {
+                if let Cow::Owned(text) = self.redactor.redact(text.as_str()) {
+                    attribute.value = text.into();
+                }
+            }
+        }
+    }
+}
+
+impl<E: SpanExporter> SpanExporter for RedactingExporter<E> {
+    async fn export(&self, mut batch: Vec<SpanData>) -> OTelSdkResult {
+        for span in &mut batch {
+            self.redact(&mut span.attributes);
+            for event in span.events.events.iter_mut() {
+                // tracing-opentelemetry names events after their message
+                if let Cow::Owned(name) = self.redactor.redact(&event.name) {
+                    event.name = name.into();
+                }
+                self.redact(&mut event.attributes);
+            }
+        }
+        self.inner.export(batch).await
+    }
+
+    fn shutdown_with_timeout(&mut self, timeout: Duration) -> OTelSdkResult {
+        self.inner.shutdown_with_timeout(timeout)
+    }
+
+    fn force_flush(&mut self) -> OTelSdkResult {
+        self.inner.force_flush()
+    }
+
+    fn set_resource(&mut self, resource: &Resource) {
+        self.inner.set_resource(resource);
+    }
+}
+
+/// Appends finished spans to a file, one JSON object per line. Meant for
+/// tests and local debugging without a collector.
+#[derive(Debug)]
+pub struct FileExporter {
+    file: Mutex<File>,
+}
+
+impl FileExporter {
+    pub fn create(path: &Path) -> std::io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(FileExporter {
+            file: Mutex::new(file),
+        })
+    }
+}
+
+impl SpanExporter for FileExporter {
+    async fn export(&self, batch: Vec<SpanData>) -> OTelSdkResult {
+        let mut lines = String::new();
+        for span in &batch {
+            lines.push_str(&span_json(span).to_string());
+            lines.push('\n');
+        }
+
+        let mut file = self
+            .file
+            .lock()
+            .map_err(|_| OTelSdkError::InternalFailure("trace file lock poisoned".to_string()))?;
+        file.write_all(lines.as_bytes())
+            .and_then(|_| file.flush())
+            .map_err(|e| OTelSdkError::InternalFailure(e.to_string()))
+    }
+}
+
+fn span_json(span: &SpanData) -> Value {
+    let events: Vec<Value> = span
+        .events
+        .iter()
+        .map(
+            |event| json!({ "name": event.name, "attributes": attributes_json(&event.attributes) }),
+        )
+        .collect();
+
+    json!({
+        "name": span.name,
+        "trace_id": span.span_context.trace_id().to_string(),
+        "span_id": span.span_context.span_id().to_string(),
+        "parent_span_id": span.parent_span_id.to_string(),
+        "start_unix_nanos": unix_nanos(span.start_time),
+        "end_unix_nanos": unix_nanos(span.end_time),
+        "attributes": attributes_json(&span.attributes),
+        "events": events,
+    })
+}
+
+fn attributes_json(attributes: &[KeyValue]) -> Map<String, Value> {
+    attributes
+        .iter()
+        .map(|kv| (kv.key.to_string(), Value::from(kv.value.as_str())))
+        .collect()
+}
+
+fn unix_nanos(time: SystemTime) -> u128 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos()
 }
@@ -1,65 +1,48 @@
-use std::net::SocketAddr;
-use std::sync::atomic::Ordering;
+use std::process::ExitCode;
 
-use backend::{app_with_config, config::Config, health::READY, logging};
-use tracing::info;
+use backend::{cli::ConfigArgs, config::Config, error::AppError, server};
+use clap::Parser;
 
-#[tokio::main]
-async fn main() -> anyhow::Result<()> {
-    // Load configuration from environment
-    let config = Config::from_env().map_err(|e| anyhow::anyhow!("Configuration error: {}", e))?;
-
-    // Initialize logging
-    logging::init(&config.log_level)?;
-
-    // Build the application
-    let app = app_with_config(&config)?;
-
-    let addr = SocketAddr::from((config.host, config.port));
-    info!(address = %addr, "Server starting");
-
-    let listener = tokio::net::TcpListener::bind(addr).await?;
-
-    // Serve with graceful shutdown
-    axum::serve(listener, app)
-        .with_graceful_shutdown(shutdown_signal())
-        .await?;
-
-    info!("Server shutdown complete");
-    Ok(())
+/// System Designer HTTP API
+#[derive(Parser, Debug)]
+#[command(name = "backend", version, about)]
+struct Args {
+    #[command(flatten)]
+    config: ConfigArgs,
 }
 
-async fn shutdown_signal() {
-    let ctrl_c = async {
-        tokio::signal::ctrl_c()
-            .await
-            .expect("Failed to install Ctrl+C handler");
-    };
-
-    #[cfg(unix)]
-    let terminate = async {
-        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
-            .expect("Failed to install SIGTERM handler")
-            .recv()
-            .await;
+#[tokio::main]
+async fn main() -> ExitCode {
+    let args = Args::parse();
+
+    // Defaults, then the TOML file, then APP_* variables, then --set
+    let sources = args.config.sources(Vec::new());
+    let config = match Config::load(&sources) {
+        Ok(config) => config,
+        Err(e) => {
+            let e = AppError::from(e);
+            eprintln!("error: {}", e);
+            return ExitCode::from(e.exit_code());
+        }
     };
+    if args.config.print_config {
+        return match config.to_masked_toml() {
+            Ok(toml) => {
+                print!("{}", toml);
+                ExitCode::SUCCESS
+            }
+            Err(e) => {
+                eprintln!("error: {}", e);
+                ExitCode::from(e.exit_code())
+            }
+        };
+    }
 
-    #[cfg(not(unix))]
-    let terminate = std::future::pending::<()>();
-
-    tokio::select! {
-        _ = ctrl_c => {
-            info!("Received Ctrl+C, initiating graceful shutdown");
-        }
-        _ = terminate => {
-            info!("Received SIGTERM, initiating graceful shutdown");
+    match server::run(config, sources).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {:#}", e);
+            ExitCode::FAILURE
         }
     }
-
-    // Mark as not ready to stop accepting new requests
-    READY.store(false, Ordering::SeqCst);
-
-    // Give in-flight requests time to complete
-    info!("Waiting for in-flight requests to complete...");
-    tokio::time::sleep(std::time::Duration::from_secs(2)).await;
 }
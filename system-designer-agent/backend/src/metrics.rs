@@ -0,0 +1,184 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::extract::State;
+use axum::http::header;
+use axum::response::IntoResponse;
+use axum::routing::get;
+use axum::Router;
+use prometheus::{
+    Encoder, HistogramOpts, HistogramVec, IntCounterVec, IntGauge, Opts, Registry, TextEncoder,
+};
+
+use crate::error::{AppError, AppResult};
+use crate::health::check_memory;
+
+/// Route label for requests that matched no route, so probes of random
+/// paths cannot blow up label cardinality
+pub const UNMATCHED_ROUTE: &str = "unmatched";
+
+/// Prometheus collectors for the API. Each router gets its own registry;
+/// handlers and middleware find it as an `Arc<Metrics>` request extension.
+pub struct Metrics {
+    registry: Registry,
+    requests: IntCounterVec,
+    latency: HistogramVec,
+    output_bytes: HistogramVec,
+    validation_failures: IntCounterVec,
+    rate_limited: IntCounterVec,
+    config_reloads: IntCounterVec,
+    memory_ok: IntGauge,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        let requests = IntCounterVec::new(
+            Opts::new("http_requests_total", "HTTP requests by route and status"),
+            &["method", "route", "status"],
+        )
+        .expect("valid metric");
+        let latency = HistogramVec::new(
+            HistogramOpts::new(
+                "http_request_duration_seconds",
+                "HTTP request latency by route and status",
+            ),
+            &["method", "route", "status"],
+        )
+        .expect("valid metric");
+        let output_bytes = HistogramVec::new(
+            HistogramOpts::new("design_output_bytes", "Size of generated design documents")
+                .buckets(prometheus::exponential_buckets(256.0, 4.0, 8).expect("valid buckets")),
+            &["format"],
+        )
+        .expect("valid metric");
+        let validation_failures = IntCounterVec::new(
+            Opts::new(
+                "validation_failures_total",
+                "Rejected request fields by dotted path",
+            ),
+            &["field"],
+        )
+        .expect("valid metric");
+        let rate_limited = IntCounterVec::new(
+            Opts::new(
+                "rate_limit_rejections_total",
+                "Requests rejected by the rate limiter",
+            ),
+            &["group"],
+        )
+        .expect("valid metric");
+        let config_reloads = IntCounterVec::new(
+            Opts::new(
+                "config_reloads_total",
+                "Configuration reloads by result (success or failure)",
+            ),
+            &["result"],
+        )
+        .expect("valid metric");
+        let memory_ok = IntGauge::new(
+            "memory_ok",
+            "1 if more than 5% of system memory is available, as reported by /ready",
+        )
+        .expect("valid metric");
+
+        let registry = Registry::new();
+        for collector in [
+            Box::new(requests.clone()) as Box<dyn prometheus::core::Collector>,
+            Box::new(latency.clone()),
+            Box::new(output_bytes.clone()),
+            Box::new(validation_failures.clone()),
+            Box::new(rate_limited.clone()),
+            Box::new(config_reloads.clone()),
+            Box::new(memory_ok.clone()),
+        ] {
+            registry
+                .register(collector)
+                .expect("metric names are unique");
+        }
+
+        Metrics {
+            registry,
+            requests,
+            latency,
+            output_bytes,
+            validation_failures,
+            rate_limited,
+            config_reloads,
+            memory_ok,
+        }
+    }
+
+    pub fn observe_request(&self, method: &str, route: &str, status: u16, elapsed: Duration) {
+        let status = status.to_string();
+        let labels = [method, route, status.as_str()];
+        self.requests.with_label_values(&labels).inc();
+        self.latency
+            .with_label_values(&labels)
+            .observe(elapsed.as_secs_f64());
+    }
+
+    /// Size of a generated document; `format` is `markdown`, `json` or `yaml`
+    pub fn observe_output(&self, format: &str, bytes: usize) {
+        self.output_bytes
+            .with_label_values(&[format])
+            .observe(bytes as f64);
+    }
+
+    /// Count a rejected field. List indices are dropped from the path, so
+    /// `tools.apis[2]` is counted as `tools.apis`.
+    pub fn validation_failed(&self, field: &str) {
+        let field = match field.find('[') {
+            Some(index) => &field[..index],
+            None => field,
+        };
+        self.validation_failures.with_label_values(&[field]).inc();
+    }
+
+    pub fn rate_limited(&self, group: &str) {
+        self.rate_limited.with_label_values(&[group]).inc();
+    }
+
+    /// Count a configuration reload; rejected configurations are failures
+    pub fn config_reloaded(&self, success: bool) {
+        let result = if success { "success" } else { "failure" };
+        self.config_reloads.with_label_values(&[result]).inc();
+    }
+
+    /// Prometheus text exposition of every collector. Gauges that reflect
+    /// the host are refreshed on each scrape.
+    pub fn render(&self) -> AppResult<String> {
+        self.memory_ok.set(i64::from(check_memory()));
+
+        let mut buffer = Vec::new();
+        TextEncoder::new()
+            .encode(&self.registry.gather(), &mut buffer)
+            .map_err(|e| AppError::Internal(e.into()))?;
+        String::from_utf8(buffer).map_err(|e| AppError::Internal(e.into()))
+    }
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Metrics::new()
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/metrics",
+    tag = "health",
+    responses((status = 200, description = "Prometheus text exposition format", body = String))
+)]
+pub async fn metrics_handler(State(metrics): State<Arc<Metrics>>) -> AppResult<impl IntoResponse> {
+    Ok((
+        [(header::CONTENT_TYPE, prometheus::TEXT_FORMAT)],
+        metrics.render()?,
+    ))
+}
+
+/// Router serving only `/metrics`, for a separate admin port
+pub fn router(metrics: Arc<Metrics>) -> Router {
+    Router::new()
+        .route("/metrics", get(metrics_handler))
+        .with_state(metrics)
+}
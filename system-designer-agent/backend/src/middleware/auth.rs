@@ -0,0 +1,421 @@
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use axum::{
+    body::Body,
+    extract::{FromRequestParts, State},
+    http::{header, request::Parts, HeaderMap, Request},
+    middleware::Next,
+    response::Response,
+};
+use jsonwebtoken::jwk::{AlgorithmParameters, JwkSet, KeyAlgorithm};
+use jsonwebtoken::{decode, decode_header, Algorithm, DecodingKey, Validation};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use tracing::{debug, warn};
+
+use crate::config::Config;
+use crate::error::AppError;
+use crate::middleware::rate_limit::API_KEY_HEADER;
+
+/// Permission granted to a principal; `admin` implies every other scope
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Scope {
+    #[serde(rename = "designs:read")]
+    DesignsRead,
+    #[serde(rename = "designs:write")]
+    DesignsWrite,
+    #[serde(rename = "admin")]
+    Admin,
+}
+
+impl Scope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Scope::DesignsRead => "designs:read",
+            Scope::DesignsWrite => "designs:write",
+            Scope::Admin => "admin",
+        }
+    }
+}
+
+impl fmt::Display for Scope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Scope {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "designs:read" => Ok(Scope::DesignsRead),
+            "designs:write" => Ok(Scope::DesignsWrite),
+            "admin" => Ok(Scope::Admin),
+            other => Err(AppError::BadRequest(format!("Unknown scope: {}", other))),
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthMethod {
+    ApiKey,
+    Jwt,
+}
+
+/// Authenticated caller, available to handlers as a request extension
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Principal {
+    /// API key name or JWT `sub` claim
+    pub subject: String,
+    pub method: AuthMethod,
+    pub scopes: Vec<Scope>,
+    /// Workspace the caller is bound to; unbound callers use the default
+    /// workspace unless they are admins
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub workspace: Option<String>,
+}
+
+impl Principal {
+    pub fn has_scope(&self, scope: Scope) -> bool {
+        self.scopes.contains(&Scope::Admin) || self.scopes.contains(&scope)
+    }
+}
+
+#[axum::async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for Principal {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts
+            .extensions
+            .get::<Principal>()
+            .cloned()
+            .ok_or_else(|| AppError::Unauthorized("Authentication required".to_string()))
+    }
+}
+
+/// A static API key; only the SHA-256 hex digest of the key is stored
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ApiKeyConfig {
+    pub name: String,
+    pub hash: String,
+    #[serde(default)]
+    pub scopes: Vec<Scope>,
+    pub workspace: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct JwtConfig {
+    /// JWKS file with `oct` (HS256) and `RSA` (RS256) keys, relative to the
+    /// auth configuration file
+    pub jwks: PathBuf,
+    pub issuer: Option<String>,
+    pub audience: Option<String>,
+}
+
+/// Auth configuration file layout:
+///
+/// ```toml
+/// [[api_keys]]
+/// name = "ci"
+/// hash = "<sha256 hex of the key, see `system-designer hash-key`>"
+/// scopes = ["designs:read", "designs:write"]
+/// workspace = "platform-team"   # optional; JWTs use the `workspace` claim
+///
+/// [jwt]
+/// jwks = "jwks.json"
+/// issuer = "https://auth.example.com"
+/// audience = "system-designer"
+/// ```
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct AuthConfig {
+    #[serde(default)]
+    pub api_keys: Vec<ApiKeyConfig>,
+    pub jwt: Option<JwtConfig>,
+}
+
+impl AuthConfig {
+    pub fn from_toml(source: &str) -> Result<Self, AppError> {
+        let config: AuthConfig = toml::from_str(source)
+            .map_err(|e| AppError::Config(format!("Invalid auth configuration: {}", e)))?;
+
+        if let Some(key) = config
+            .api_keys
+            .iter()
+            .find(|key| key.hash.len() != 64 || hex::decode(&key.hash).is_err())
+        {
+            return Err(AppError::Config(format!(
+                "API key {} must have a SHA-256 hex hash",
+                key.name
+            )));
+        }
+
+        Ok(config)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, AppError> {
+        let source = fs::read_to_string(path).map_err(|e| {
+            AppError::Config(format!(
+                "Cannot read auth configuration {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        Self::from_toml(&source)
+    }
+}
+
+/// SHA-256 hex digest stored in the auth configuration for an API key
+pub fn hash_api_key(key: &str) -> String {
+    hex::encode(Sha256::digest(key.as_bytes()))
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+struct VerificationKey {
+    kid: Option<String>,
+    algorithm: Algorithm,
+    key: DecodingKey,
+}
+
+struct JwtVerifier {
+    keys: Vec<VerificationKey>,
+    issuer: Option<String>,
+    audience: Option<String>,
+}
+
+impl JwtVerifier {
+    fn load(config: &JwtConfig, base_dir: &Path) -> Result<Self, AppError> {
+        let path = base_dir.join(&config.jwks);
+        let source = fs::read_to_string(&path)
+            .map_err(|e| AppError::Config(format!("Cannot read JWKS {}: {}", path.display(), e)))?;
+        let set: JwkSet = serde_json::from_str(&source)
+            .map_err(|e| AppError::Config(format!("Invalid JWKS {}: {}", path.display(), e)))?;
+
+        let keys = set
+            .keys
+            .iter()
+            .map(|jwk| {
+                let algorithm = match (&jwk.algorithm, jwk.common.key_algorithm) {
+                    (AlgorithmParameters::OctetKey(_), None | Some(KeyAlgorithm::HS256)) => {
+                        Algorithm::HS256
+                    }
+                    (AlgorithmParameters::RSA(_), None | Some(KeyAlgorithm::RS256)) => {
+                        Algorithm::RS256
+                    }
+                    _ => {
+                        return Err(AppError::Config(format!(
+                            "Unsupported JWK {}: only HS256 and RS256 keys are accepted",
+                            jwk.common.key_id.as_deref().unwrap_or("without kid")
+                        )))
+                    }
+                };
+                let key = DecodingKey::from_jwk(jwk)
+                    .map_err(|e| AppError::Config(format!("Invalid JWK: {}", e)))?;
+                Ok(VerificationKey {
+                    kid: jwk.common.key_id.clone(),
+                    algorithm,
+                    key,
+                })
+            })
+            .collect::<Result<Vec<_>, AppError>>()?;
+
+        Ok(JwtVerifier {
+            keys,
+            issuer: config.issuer.clone(),
+            audience: config.audience.clone(),
+        })
+    }
+
+    fn verify(&self, token: &str) -> Result<Principal, AppError> {
+        let invalid = || AppError::Unauthorized("Invalid token".to_string());
+
+        let header = decode_header(token).map_err(|_| invalid())?;
+        if !matches!(header.alg, Algorithm::HS256 | Algorithm::RS256) {
+            return Err(invalid());
+        }
+
+        let mut validation = Validation::new(header.alg);
+        if let Some(issuer) = &self.issuer {
+            validation.set_issuer(&[issuer]);
+        }
+        match &self.audience {
+            Some(audience) => validation.set_audience(&[audience]),
+            None => validation.validate_aud = false,
+        }
+
+        let claims = self
+            .keys
+            .iter()
+            .filter(|k| k.algorithm == header.alg)
+            .filter(|k| header.kid.is_none() || k.kid == header.kid)
+            .find_map(|k| decode::<Value>(token, &k.key, &validation).ok())
+            .ok_or_else(invalid)?
+            .claims;
+
+        Ok(Principal {
+            subject: claims["sub"].as_str().unwrap_or("unknown").to_string(),
+            method: AuthMethod::Jwt,
+            scopes: token_scopes(&claims),
+            workspace: claims["workspace"].as_str().map(str::to_string),
+        })
+    }
+}
+
+/// Scopes from the `scope` (space separated or array) or `scp` claim;
+/// unknown values are ignored
+fn token_scopes(claims: &Value) -> Vec<Scope> {
+    let claim = claims.get("scope").or_else(|| claims.get("scp"));
+    let names: Vec<&str> = match claim {
+        Some(Value::String(s)) => s.split_whitespace().collect(),
+        Some(Value::Array(items)) => items.iter().filter_map(Value::as_str).collect(),
+        _ => Vec::new(),
+    };
+    names.into_iter().filter_map(|s| s.parse().ok()).collect()
+}
+
+/// Verifies API keys and JWTs; when disabled every request is let through
+pub struct Authenticator {
+    enabled: bool,
+    api_keys: Vec<ApiKeyConfig>,
+    jwt: Option<JwtVerifier>,
+}
+
+impl Authenticator {
+    pub fn disabled() -> Self {
+        Authenticator {
+            enabled: false,
+            api_keys: Vec::new(),
+            jwt: None,
+        }
+    }
+
+    /// `base_dir` resolves the relative JWKS path
+    pub fn new(config: &AuthConfig, base_dir: &Path) -> Result<Self, AppError> {
+        let jwt = config
+            .jwt
+            .as_ref()
+            .map(|jwt| JwtVerifier::load(jwt, base_dir))
+            .transpose()?;
+
+        Ok(Authenticator {
+            enabled: true,
+            api_keys: config.api_keys.clone(),
+            jwt,
+        })
+    }
+
+    /// Create an authenticator from the configured auth file, if any
+    pub fn from_config(config: &Config) -> Result<Self, AppError> {
+        match &config.auth_config {
+            Some(path) => {
+                let base_dir = path.parent().unwrap_or(Path::new("."));
+                Self::new(&AuthConfig::load(path)?, base_dir)
+            }
+            None => {
+                warn!("APP_AUTH_CONFIG not set, the API accepts unauthenticated requests");
+                Ok(Self::disabled())
+            }
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Identify the caller from `X-Api-Key` or `Authorization: Bearer`
+    pub fn authenticate(&self, headers: &HeaderMap) -> Result<Principal, AppError> {
+        let bearer = headers
+            .get(header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .map(str::trim);
+        let api_key = headers
+            .get(API_KEY_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .map(str::trim);
+
+        match (api_key, bearer) {
+            (Some(key), _) => self.verify_api_key(key),
+            // Three dot-separated segments is a JWT, anything else an API key
+            (None, Some(token)) if token.split('.').count() == 3 => match &self.jwt {
+                Some(jwt) => jwt.verify(token),
+                None => Err(AppError::Unauthorized(
+                    "JWT authentication is not configured".to_string(),
+                )),
+            },
+            (None, Some(token)) => self.verify_api_key(token),
+            (None, None) => Err(AppError::Unauthorized("Missing credentials".to_string())),
+        }
+    }
+
+    fn verify_api_key(&self, key: &str) -> Result<Principal, AppError> {
+        let digest = Sha256::digest(key.as_bytes());
+        self.api_keys
+            .iter()
+            .find(|entry| {
+                hex::decode(&entry.hash)
+                    .map(|stored| constant_time_eq(&stored, &digest))
+                    .unwrap_or(false)
+            })
+            .map(|entry| Principal {
+                subject: entry.name.clone(),
+                method: AuthMethod::ApiKey,
+                scopes: entry.scopes.clone(),
+                workspace: entry.workspace.clone(),
+            })
+            .ok_or_else(|| AppError::Unauthorized("Invalid API key".to_string()))
+    }
+}
+
+/// Middleware state: the scope a route group requires
+#[derive(Clone)]
+pub struct RequireScope {
+    auth: Arc<Authenticator>,
+    scope: Scope,
+}
+
+impl RequireScope {
+    pub fn new(auth: Arc<Authenticator>, scope: Scope) -> Self {
+        RequireScope { auth, scope }
+    }
+}
+
+pub async fn authorize(
+    State(required): State<RequireScope>,
+    mut req: Request<Body>,
+    next: Next,
+) -> Result<Response, AppError> {
+    if !required.auth.is_enabled() {
+        return Ok(next.run(req).await);
+    }
+
+    // Already identified by the rate limiter
+    let principal = match req.extensions_mut().remove::<Principal>() {
+        Some(principal) => principal,
+        None => required.auth.authenticate(req.headers())?,
+    };
+    if !principal.has_scope(required.scope) {
+        warn!(subject = %principal.subject, scope = %required.scope, "Missing scope");
+        return Err(AppError::Forbidden(format!(
+            "Missing scope {}",
+            required.scope
+        )));
+    }
+
+    debug!(subject = %principal.subject, method = ?principal.method, "Authenticated");
+    req.extensions_mut().insert(principal);
+    Ok(next.run(req).await)
+}
@@ -1,31 +1,72 @@
+use std::sync::{Arc, RwLock};
+
 use axum::http::{HeaderValue, Method};
 use tower_http::cors::{AllowOrigin, CorsLayer};
 
 use crate::config::Config;
 use crate::error::AppError;
+use crate::middleware::rate_limit::API_KEY_HEADER;
+use crate::middleware::request_id::REQUEST_ID_HEADER;
+
+/// Allowed origins, shared with the CORS layer so a config reload can
+/// replace them. An empty list allows every origin (development mode).
+#[derive(Clone, Default)]
+pub struct CorsOrigins(Arc<RwLock<Arc<Vec<HeaderValue>>>>);
+
+impl CorsOrigins {
+    pub fn from_config(config: &Config) -> Result<Self, AppError> {
+        let origins = CorsOrigins::default();
+        origins.set(config)?;
+        Ok(origins)
+    }
+
+    /// Replace the list; nothing changes if any origin is invalid
+    pub fn set(&self, config: &Config) -> Result<(), AppError> {
+        self.replace(CorsOrigins::parse(config)?);
+        Ok(())
+    }
 
-pub fn cors_layer(config: &Config) -> Result<CorsLayer, AppError> {
-    if config.cors_origins.is_empty() {
-        // No CORS restrictions (development mode)
-        return Ok(CorsLayer::permissive());
+    /// The configured origins as header values
+    pub fn parse(config: &Config) -> Result<Vec<HeaderValue>, AppError> {
+        config
+            .cors_origins
+            .iter()
+            .map(|origin| {
+                origin
+                    .parse()
+                    .map_err(|_| AppError::Config(format!("Invalid CORS origin: {}", origin)))
+            })
+            .collect()
     }
 
-    let origins: Vec<HeaderValue> = config
-        .cors_origins
-        .iter()
-        .map(|origin| {
-            origin
-                .parse()
-                .map_err(|_| AppError::Config(format!("Invalid CORS origin: {}", origin)))
-        })
-        .collect::<Result<Vec<_>, _>>()?;
-
-    Ok(CorsLayer::new()
-        .allow_origin(AllowOrigin::list(origins))
-        .allow_methods([Method::GET, Method::POST, Method::OPTIONS])
+    pub fn replace(&self, origins: Vec<HeaderValue>) {
+        *self.0.write().expect("CORS origins poisoned") = Arc::new(origins);
+    }
+
+    pub fn allows(&self, origin: &HeaderValue) -> bool {
+        let origins = self.0.read().expect("CORS origins poisoned").clone();
+        origins.is_empty() || origins.contains(origin)
+    }
+}
+
+pub fn cors_layer(origins: CorsOrigins) -> CorsLayer {
+    CorsLayer::new()
+        .allow_origin(AllowOrigin::predicate(move |origin, _| {
+            origins.allows(origin)
+        }))
+        .allow_methods([
+            Method::GET,
+            Method::POST,
+            Method::PUT,
+            Method::DELETE,
+            Method::OPTIONS,
+        ])
         .allow_headers([
             axum::http::header::CONTENT_TYPE,
             axum::http::header::AUTHORIZATION,
+            axum::http::HeaderName::from_static(API_KEY_HEADER),
+            axum::http::HeaderName::from_static(REQUEST_ID_HEADER),
         ])
-        .max_age(std::time::Duration::from_secs(3600)))
+        .expose_headers([axum::http::HeaderName::from_static(REQUEST_ID_HEADER)])
+        .max_age(std::time::Duration::from_secs(3600))
 }
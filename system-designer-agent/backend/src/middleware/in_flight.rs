@@ -0,0 +1,40 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use axum::{
+    body::Body,
+    extract::{Request, State},
+    middleware::Next,
+    response::Response,
+};
+
+/// Number of requests being handled, so shutdown can report what a drain
+/// timeout cut off
+#[derive(Clone, Default)]
+pub struct InFlight(Arc<AtomicUsize>);
+
+impl InFlight {
+    pub fn count(&self) -> usize {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+/// Decrements the count when the request finishes or is dropped
+struct Guard(InFlight);
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        (self.0).0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Count the request as in flight until its response is ready
+pub async fn track_in_flight(
+    State(in_flight): State<InFlight>,
+    req: Request<Body>,
+    next: Next,
+) -> Response {
+    in_flight.0.fetch_add(1, Ordering::SeqCst);
+    let _guard = Guard(in_flight);
+    next.run(req).await
+}
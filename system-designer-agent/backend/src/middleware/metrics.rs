@@ -0,0 +1,33 @@
+use std::sync::Arc;
+use std::time::Instant;
+
+use axum::{
+    body::Body,
+    extract::{MatchedPath, Request, State},
+    middleware::Next,
+    response::Response,
+};
+
+use crate::metrics::{Metrics, UNMATCHED_ROUTE};
+
+/// Record request count and latency by route template and status, and make
+/// the collectors available to handlers as an `Arc<Metrics>` extension
+pub async fn track_metrics(
+    State(metrics): State<Arc<Metrics>>,
+    mut req: Request<Body>,
+    next: Next,
+) -> Response {
+    let start = Instant::now();
+    let method = req.method().to_string();
+    let route = req
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| UNMATCHED_ROUTE.to_string());
+
+    req.extensions_mut().insert(metrics.clone());
+    let response = next.run(req).await;
+
+    metrics.observe_request(&method, &route, response.status().as_u16(), start.elapsed());
+    response
+}
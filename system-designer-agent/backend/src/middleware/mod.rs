@@ -1,5 +1,15 @@
+pub mod auth;
 pub mod cors;
+pub mod in_flight;
+pub mod metrics;
+pub mod rate_limit;
+pub mod request_id;
 pub mod security;
 
-pub use cors::cors_layer;
+pub use auth::{authorize, Authenticator, Principal, RequireScope, Scope};
+pub use cors::{cors_layer, CorsOrigins};
+pub use in_flight::{track_in_flight, InFlight};
+pub use metrics::track_metrics;
+pub use rate_limit::{rate_limit, RateLimiter};
+pub use request_id::{request_id, RequestId};
 pub use security::security_headers;
@@ -0,0 +1,237 @@
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::num::NonZeroU32;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+
+use axum::{
+    body::Body,
+    extract::{ConnectInfo, State},
+    http::{HeaderMap, Request},
+    middleware::Next,
+    response::Response,
+};
+use governor::clock::{Clock, DefaultClock};
+use governor::state::keyed::DashMapStateStore;
+use governor::{Quota, RateLimiter as Governor};
+use tracing::warn;
+
+use crate::error::AppError;
+use crate::metrics::Metrics;
+use crate::middleware::auth::{AuthMethod, Authenticator};
+
+/// Header carrying a client API key
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// Forget idle clients every this many checks so the key table stays bounded
+const RETAIN_INTERVAL: u64 = 1024;
+
+/// Resolve the client address for a request: the `X-Forwarded-For` address
+/// when the peer is a trusted proxy, otherwise the peer address itself
+pub fn client_address(
+    headers: &HeaderMap,
+    peer: Option<IpAddr>,
+    trusted_proxies: &[IpAddr],
+) -> IpAddr {
+    let peer = peer.unwrap_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED));
+    if !trusted_proxies.contains(&peer) {
+        return peer;
+    }
+
+    // Walk the chain from the nearest hop, skipping our own proxies. Hops
+    // further left were written by the client, so stop at the first one
+    // that is not a valid address.
+    let hops: Vec<&str> = headers
+        .get_all("x-forwarded-for")
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .collect();
+
+    let mut client = peer;
+    for hop in hops.iter().rev() {
+        let Ok(hop) = hop.trim().parse::<IpAddr>() else {
+            break;
+        };
+        client = hop;
+        if !trusted_proxies.contains(&hop) {
+            break;
+        }
+    }
+    client
+}
+
+/// The bucket a request draws from. Callers with valid credentials get one
+/// bucket per API key or JWT subject, wherever they connect from; everyone
+/// else, including made-up credentials, shares the bucket of their address,
+/// so credential guessing is throttled too.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ClientKey {
+    Principal(AuthMethod, String),
+    Address(IpAddr),
+}
+
+impl fmt::Display for ClientKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClientKey::Principal(method, subject) => write!(f, "{:?}:{}", method, subject),
+            ClientKey::Address(ip) => write!(f, "{}", ip),
+        }
+    }
+}
+
+type Limiter = Governor<ClientKey, DashMapStateStore<ClientKey>, DefaultClock>;
+
+/// Quota and trusted proxies in effect; replaced as a whole on reload
+struct Policy {
+    per_second: u32,
+    burst: u32,
+    limiter: Option<Arc<Limiter>>,
+    trusted_proxies: Arc<Vec<IpAddr>>,
+}
+
+/// Per-client quota for one route group. Clones share buckets, and
+/// [`RateLimiter::set_quota`] changes the quota for all of them.
+#[derive(Clone)]
+pub struct RateLimiter {
+    group: &'static str,
+    policy: Arc<RwLock<Arc<Policy>>>,
+    clock: DefaultClock,
+    checks: Arc<AtomicU64>,
+    auth: Option<Arc<Authenticator>>,
+}
+
+impl RateLimiter {
+    /// A `per_second` of zero disables limiting for the group
+    pub fn new(
+        group: &'static str,
+        per_second: u32,
+        burst: u32,
+        trusted_proxies: Vec<IpAddr>,
+    ) -> Self {
+        let clock = DefaultClock::default();
+        let policy = Policy::new(per_second, burst, trusted_proxies, &clock);
+
+        RateLimiter {
+            group,
+            policy: Arc::new(RwLock::new(Arc::new(policy))),
+            clock,
+            checks: Arc::new(AtomicU64::new(0)),
+            auth: None,
+        }
+    }
+
+    /// Key requests with valid credentials on their principal instead of
+    /// their address
+    pub fn with_authenticator(mut self, auth: Arc<Authenticator>) -> Self {
+        self.auth = Some(auth);
+        self
+    }
+
+    /// Switch to a new quota. Client buckets start full again when the
+    /// quota changes, and are kept when only the proxies change.
+    pub fn set_quota(&self, per_second: u32, burst: u32, trusted_proxies: Vec<IpAddr>) {
+        let mut policy = self.policy.write().expect("rate limit policy poisoned");
+        let next = if policy.per_second == per_second && policy.burst == burst {
+            Policy {
+                per_second,
+                burst,
+                limiter: policy.limiter.clone(),
+                trusted_proxies: Arc::new(trusted_proxies),
+            }
+        } else {
+            Policy::new(per_second, burst, trusted_proxies, &self.clock)
+        };
+        *policy = Arc::new(next);
+    }
+
+    fn policy(&self) -> Arc<Policy> {
+        self.policy
+            .read()
+            .expect("rate limit policy poisoned")
+            .clone()
+    }
+
+    pub fn group(&self) -> &'static str {
+        self.group
+    }
+
+    /// Take one cell from the client's bucket, or fail with the whole
+    /// seconds to wait before retrying
+    pub fn check(&self, key: &ClientKey) -> Result<(), AppError> {
+        let policy = self.policy();
+        let Some(limiter) = &policy.limiter else {
+            return Ok(());
+        };
+
+        if self.checks.fetch_add(1, Ordering::Relaxed) % RETAIN_INTERVAL == RETAIN_INTERVAL - 1 {
+            limiter.retain_recent();
+        }
+
+        limiter.check_key(key).map_err(|not_until| {
+            let wait = not_until.wait_time_from(self.clock.now());
+            let retry_after = wait.as_secs() + u64::from(wait.subsec_nanos() > 0);
+            AppError::RateLimited(retry_after.max(1))
+        })
+    }
+}
+
+impl Policy {
+    fn new(
+        per_second: u32,
+        burst: u32,
+        trusted_proxies: Vec<IpAddr>,
+        clock: &DefaultClock,
+    ) -> Self {
+        let limiter = NonZeroU32::new(per_second).map(|rate| {
+            let burst = NonZeroU32::new(burst).unwrap_or(rate);
+            let quota = Quota::per_second(rate).allow_burst(burst);
+            Arc::new(Governor::new(quota, DashMapStateStore::default(), clock))
+        });
+        Policy {
+            per_second,
+            burst,
+            limiter,
+            trusted_proxies: Arc::new(trusted_proxies),
+        }
+    }
+}
+
+/// Charge the request to its client's bucket. Principals identified here
+/// are passed on to [`authorize`](super::authorize) so credentials are only
+/// verified once.
+pub async fn rate_limit(
+    State(limiter): State<RateLimiter>,
+    mut req: Request<Body>,
+    next: Next,
+) -> Result<Response, AppError> {
+    let principal = limiter
+        .auth
+        .as_ref()
+        .filter(|auth| auth.is_enabled())
+        .and_then(|auth| auth.authenticate(req.headers()).ok());
+    let key = match &principal {
+        Some(principal) => ClientKey::Principal(principal.method, principal.subject.clone()),
+        None => {
+            let peer = req
+                .extensions()
+                .get::<ConnectInfo<SocketAddr>>()
+                .map(|ConnectInfo(addr)| addr.ip());
+            let trusted_proxies = limiter.policy().trusted_proxies.clone();
+            ClientKey::Address(client_address(req.headers(), peer, &trusted_proxies))
+        }
+    };
+
+    if let Err(e) = limiter.check(&key) {
+        warn!(group = limiter.group(), client = %key, "Rate limit exceeded");
+        if let Some(metrics) = req.extensions().get::<Arc<Metrics>>() {
+            metrics.rate_limited(limiter.group());
+        }
+        return Err(e);
+    }
+
+    if let Some(principal) = principal {
+        req.extensions_mut().insert(principal);
+    }
+    Ok(next.run(req).await)
+}
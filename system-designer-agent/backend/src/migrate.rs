@@ -0,0 +1,111 @@
+use serde_json::{Map, Value};
+
+use crate::error::AppError;
+use crate::DesignRequest;
+
+/// Version of the `DesignRequest` payload shape. Bump it together with a new
+/// entry in [`MIGRATIONS`] whenever a field is added, renamed or retyped.
+pub const SCHEMA_VERSION: u32 = 3;
+
+/// Payloads without a `schema_version` predate versioning
+const UNVERSIONED: u32 = 1;
+
+type Migration = fn(&mut Map<String, Value>);
+
+/// `MIGRATIONS[n]` upgrades a version `n + 1` payload to version `n + 2`
+const MIGRATIONS: &[Migration] = &[v1_to_v2, v2_to_v3];
+
+/// Payloads that may arrive in an older shape and are upgraded before they
+/// are deserialized. Only `DesignRequest` has more than one version.
+pub trait Upgrade {
+    fn upgrade(value: Value) -> Result<Value, AppError> {
+        Ok(value)
+    }
+}
+
+impl Upgrade for DesignRequest {
+    fn upgrade(value: Value) -> Result<Value, AppError> {
+        upgrade(value)
+    }
+}
+
+/// Upgrade a `DesignRequest` payload of any supported version to
+/// [`SCHEMA_VERSION`]
+pub fn upgrade(value: Value) -> Result<Value, AppError> {
+    upgrade_to(value, SCHEMA_VERSION)
+}
+
+/// Upgrade a `DesignRequest` payload step by step until it is at `target`.
+/// Fields a migration does not know about are left for deserialization to
+/// reject.
+pub fn upgrade_to(value: Value, target: u32) -> Result<Value, AppError> {
+    let Value::Object(mut payload) = value else {
+        return Err(AppError::BadRequest(
+            "Design request must be a JSON object".to_string(),
+        ));
+    };
+
+    let version = schema_version(&payload)?;
+    if version > SCHEMA_VERSION {
+        return Err(AppError::BadRequest(format!(
+            "schema_version {} is newer than the supported version {}",
+            version, SCHEMA_VERSION
+        )));
+    }
+
+    for (from, migration) in (version..target).zip(&MIGRATIONS[version as usize - 1..]) {
+        migration(&mut payload);
+        payload.insert("schema_version".to_string(), Value::from(from + 1));
+    }
+    Ok(Value::Object(payload))
+}
+
+/// Upgrade `value` and deserialize it. This is the entry point for every
+/// `DesignRequest` read from outside the process.
+pub fn design_request(value: Value) -> Result<DesignRequest, AppError> {
+    let value = upgrade(value)?;
+    serde_path_to_error::deserialize(value)
+        .map_err(|e| AppError::BadRequest(format!("Invalid design request: {}", e)))
+}
+
+fn schema_version(payload: &Map<String, Value>) -> Result<u32, AppError> {
+    match payload.get("schema_version") {
+        None => Ok(UNVERSIONED),
+        Some(version) => version
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .filter(|v| *v >= UNVERSIONED)
+            .ok_or_else(|| AppError::BadRequest(format!("Invalid schema_version: {}", version))),
+    }
+}
+
+/// Set `section.field` to an empty string unless the payload already has it
+fn add_text_field(payload: &mut Map<String, Value>, section: &str, field: &str) {
+    if let Some(Value::Object(section)) = payload.get_mut(section) {
+        section
+            .entry(field)
+            .or_insert_with(|| Value::String(String::new()));
+    }
+}
+
+/// Set `section.field` to an empty list unless the payload already has it
+fn add_list_field(payload: &mut Map<String, Value>, section: &str, field: &str) {
+    if let Some(Value::Object(section)) = payload.get_mut(section) {
+        section
+            .entry(field)
+            .or_insert_with(|| Value::Array(Vec::new()));
+    }
+}
+
+/// v2 added `memory.file_storage`, `orchestration.message_queues` and
+/// `testing.latency_testing`
+fn v1_to_v2(payload: &mut Map<String, Value>) {
+    add_text_field(payload, "memory", "file_storage");
+    add_text_field(payload, "orchestration", "message_queues");
+    add_text_field(payload, "testing", "latency_testing");
+}
+
+/// v3 added structured `tools.definitions`
+fn v2_to_v3(payload: &mut Map<String, Value>) {
+    add_list_field(payload, "tools", "definitions");
+}
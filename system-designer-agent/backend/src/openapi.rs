@@ -0,0 +1,236 @@
+use std::borrow::Cow;
+use std::sync::OnceLock;
+
+use axum::http::header;
+use axum::response::IntoResponse;
+use schemars::generate::SchemaSettings;
+use schemars::JsonSchema;
+use serde_json::Value;
+use utoipa::openapi::path::Operation;
+use utoipa::openapi::response::ResponseBuilder;
+use utoipa::openapi::schema::Schema;
+use utoipa::openapi::security::{ApiKey, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::openapi::{Content, RefOr, SecurityRequirement};
+use utoipa::{Modify, OpenApi, PartialSchema, ToSchema};
+
+use crate::middleware::rate_limit::API_KEY_HEADER;
+use crate::workspaces::CreateWorkspace;
+use crate::DesignRequest;
+
+/// Tags of operations that need neither credentials nor a workspace
+const PUBLIC_TAGS: &[&str] = &["health", "schema"];
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "System Designer API",
+        description = "Generate, lint, store and diff AI agent system designs.",
+        license(name = "MIT", identifier = "MIT")
+    ),
+    paths(
+        crate::health::health_check,
+        crate::health::readiness_check,
+        crate::metrics::metrics_handler,
+        openapi_json,
+        design_request_schema_json,
+        crate::generate_design,
+        crate::import_design,
+        crate::lint_design,
+        crate::diagram_design,
+        crate::list_templates,
+        crate::designs::list_designs,
+        crate::designs::create_design,
+        crate::designs::get_design,
+        crate::designs::update_design,
+        crate::designs::delete_design,
+        crate::designs::list_revisions,
+        crate::designs::diff_design,
+        crate::workspaces::list_workspaces,
+        crate::workspaces::create_workspace,
+        crate::workspaces::archive_workspace,
+    ),
+    components(schemas(crate::error::ErrorResponse)),
+    modifiers(&CommonResponses),
+    tags(
+        (name = "health", description = "Liveness, readiness and metrics"),
+        (name = "schema", description = "Machine-readable API documents"),
+        (name = "generate", description = "Render, lint, diagram and import designs"),
+        (name = "designs", description = "Stored designs and their revisions"),
+        (name = "workspaces", description = "Workspace administration"),
+    )
+)]
+struct ApiDoc;
+
+/// Adds the credentials and the error responses every route shares, so the
+/// path annotations only list the errors specific to them
+struct CommonResponses;
+
+impl Modify for CommonResponses {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "api_key",
+            SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::new(API_KEY_HEADER))),
+        );
+        components.add_security_scheme(
+            "bearer",
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .bearer_format("JWT")
+                    .build(),
+            ),
+        );
+
+        for item in openapi.paths.paths.values_mut() {
+            let operations = [
+                &mut item.get,
+                &mut item.post,
+                &mut item.put,
+                &mut item.delete,
+            ];
+            for operation in operations.into_iter().flatten() {
+                add_common_responses(operation);
+            }
+        }
+    }
+}
+
+fn add_common_responses(operation: &mut Operation) {
+    let public = operation
+        .tags
+        .iter()
+        .flatten()
+        .any(|tag| PUBLIC_TAGS.contains(&tag.as_str()));
+    if !public {
+        operation.security = Some(vec![
+            SecurityRequirement::new("api_key", Vec::<String>::new()),
+            SecurityRequirement::new("bearer", Vec::<String>::new()),
+        ]);
+        error_response(operation, "401", "Missing or invalid credentials");
+        error_response(operation, "403", "Credentials lack the required scope");
+    }
+    error_response(operation, "429", "Rate limit exceeded; see `Retry-After`");
+    error_response(operation, "500", "Unexpected server error");
+}
+
+fn error_response(operation: &mut Operation, status: &str, description: &str) {
+    let response = ResponseBuilder::new()
+        .description(description)
+        .content(
+            "application/json",
+            Content::new(Some(RefOr::Ref(utoipa::openapi::Ref::from_schema_name(
+                "ErrorResponse",
+            )))),
+        )
+        .build();
+    operation
+        .responses
+        .responses
+        .entry(status.to_string())
+        .or_insert(RefOr::T(response));
+}
+
+/// The OpenAPI 3.1 document for every route
+pub fn openapi() -> utoipa::openapi::OpenApi {
+    ApiDoc::openapi()
+}
+
+/// JSON Schema (draft 2020-12) of `DesignRequest`, including the
+/// `validator` length limits
+pub fn design_request_schema() -> Value {
+    SchemaSettings::draft2020_12()
+        .into_generator()
+        .into_root_schema_for::<DesignRequest>()
+        .to_value()
+}
+
+/// Component schemas of `T` and every type it references. Request bodies are
+/// described by schemars rather than utoipa so the OpenAPI document and the
+/// JSON Schema share the `validator` limits.
+fn schemars_components<T: JsonSchema>() -> Vec<(String, RefOr<Schema>)> {
+    let mut generator = SchemaSettings::draft2020_12()
+        .with(|settings| {
+            settings.definitions_path = "/components/schemas".into();
+            settings.meta_schema = None;
+        })
+        .into_generator();
+    generator.subschema_for::<T>();
+    generator
+        .take_definitions(true)
+        .into_iter()
+        .map(|(name, schema)| {
+            let schema = serde_json::from_value(schema)
+                .expect("schemars output is a valid OpenAPI 3.1 schema");
+            (name, schema)
+        })
+        .collect()
+}
+
+macro_rules! schemars_schema {
+    ($($ty:ty),*) => {$(
+        impl PartialSchema for $ty {
+            fn schema() -> RefOr<Schema> {
+                let name = <$ty as JsonSchema>::schema_name();
+                schemars_components::<$ty>()
+                    .into_iter()
+                    .find_map(|(component, schema)| (component == name).then_some(schema))
+                    .expect("schemars defines the requested type")
+            }
+        }
+
+        impl ToSchema for $ty {
+            fn name() -> Cow<'static, str> {
+                <$ty as JsonSchema>::schema_name()
+            }
+
+            fn schemas(schemas: &mut Vec<(String, RefOr<Schema>)>) {
+                let name = <$ty as JsonSchema>::schema_name();
+                schemas.extend(
+                    schemars_components::<$ty>()
+                        .into_iter()
+                        .filter(|(component, _)| *component != name),
+                );
+            }
+        }
+    )*};
+}
+
+schemars_schema!(DesignRequest, CreateWorkspace);
+
+fn cached(cell: &'static OnceLock<String>, build: fn() -> String) -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "application/json")],
+        cell.get_or_init(build).as_str(),
+    )
+}
+
+/// OpenAPI 3.1 document for this API
+#[utoipa::path(
+    get,
+    path = "/openapi.json",
+    tag = "schema",
+    responses((status = 200, description = "OpenAPI 3.1 document", body = Object))
+)]
+pub async fn openapi_json() -> impl IntoResponse {
+    static DOCUMENT: OnceLock<String> = OnceLock::new();
+    cached(&DOCUMENT, || {
+        openapi()
+            .to_pretty_json()
+            .expect("OpenAPI document serializes")
+    })
+}
+
+/// JSON Schema of the `DesignRequest` payload
+#[utoipa::path(
+    get,
+    path = "/schema/design-request.json",
+    tag = "schema",
+    responses((status = 200, description = "JSON Schema (draft 2020-12)", body = Object))
+)]
+pub async fn design_request_schema_json() -> impl IntoResponse {
+    static SCHEMA: OnceLock<String> = OnceLock::new();
+    cached(&SCHEMA, || {
+        serde_json::to_string_pretty(&design_request_schema()).expect("JSON Schema serializes")
+    })
+}
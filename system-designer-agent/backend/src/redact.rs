@@ -0,0 +1,187 @@
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::io::{self, Write};
+use std::sync::Arc;
+
+use regex::Regex;
+use tracing_subscriber::fmt::MakeWriter;
+
+use crate::error::AppError;
+
+/// Replacement for the value of a sensitive field
+pub const MASK: &str = "[REDACTED]";
+
+/// Log fields whose values are always masked. Request text such as the use
+/// case or prompt instructions routinely contains customer data.
+pub const DEFAULT_FIELDS: &[&str] = &[
+    "use_case",
+    "user_needs",
+    "instructions",
+    "custom_functions",
+    "authorization",
+    "api_key",
+    "password",
+    "secret",
+    "token",
+];
+
+/// Built-in patterns masked wherever they appear in a log line
+const DEFAULT_PATTERNS: &[(&str, &str)] = &[
+    // JWTs before generic bearer tokens, so the token kind is kept
+    (
+        r"\beyJ[A-Za-z0-9_-]+\.[A-Za-z0-9_-]+\.[A-Za-z0-9_-]+",
+        "[REDACTED:token]",
+    ),
+    (
+        r"(?i)\bbearer\s+[A-Za-z0-9._~+/-]+=*",
+        "Bearer [REDACTED:token]",
+    ),
+    (
+        r"\b(?:sk|pk|rk)[-_](?:live[-_]|test[-_])?[A-Za-z0-9_-]{16,}|\bAKIA[0-9A-Z]{16}\b|\bgh[pousr]_[A-Za-z0-9]{36,}\b|\bxox[abprs]-[A-Za-z0-9-]{10,}",
+        "[REDACTED:api_key]",
+    ),
+    (
+        r"(?i)\b(api[_-]?key|secret|password|passwd|token)(\s*[:=]\s*)[^\s,;&]+",
+        "$1$2[REDACTED]",
+    ),
+    (
+        r"[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}",
+        "[REDACTED:email]",
+    ),
+];
+
+/// Masks sensitive log fields by name, and emails, API keys and tokens by
+/// pattern anywhere in the text
+#[derive(Debug)]
+pub struct Redactor {
+    fields: HashSet<String>,
+    patterns: Vec<(Regex, String)>,
+    /// `"field": value` pairs in JSON log lines, for the sensitive fields
+    json_fields: Regex,
+}
+
+impl Redactor {
+    /// Built-in fields and patterns plus the configured ones. Extra patterns
+    /// are replaced with [`MASK`].
+    pub fn new(fields: &[String], patterns: &[String]) -> Result<Self, AppError> {
+        let fields: HashSet<String> = DEFAULT_FIELDS
+            .iter()
+            .map(|f| f.to_string())
+            .chain(fields.iter().map(|f| f.trim().to_string()))
+            .filter(|f| !f.is_empty())
+            .collect();
+
+        let mut compiled = Vec::new();
+        for (pattern, replacement) in DEFAULT_PATTERNS {
+            let regex = Regex::new(pattern).expect("built-in redaction pattern must compile");
+            compiled.push((regex, replacement.to_string()));
+        }
+        for pattern in patterns {
+            let regex = Regex::new(pattern).map_err(|e| {
+                AppError::Config(format!("Invalid log redaction pattern {}: {}", pattern, e))
+            })?;
+            compiled.push((regex, MASK.to_string()));
+        }
+
+        let names: Vec<String> = fields.iter().map(|f| regex::escape(f)).collect();
+        let json_fields = Regex::new(&format!(
+            r#""({})":\s*("(?:[^"\\]|\\.)*"|[^,}}\]]+)"#,
+            names.join("|")
+        ))
+        .expect("field names are escaped");
+
+        Ok(Redactor {
+            fields,
+            patterns: compiled,
+            json_fields,
+        })
+    }
+
+    pub fn is_sensitive(&self, field: &str) -> bool {
+        self.fields.contains(field)
+    }
+
+    /// Apply every pattern to free text
+    pub fn redact<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        let mut text = Cow::Borrowed(text);
+        for (regex, replacement) in &self.patterns {
+            if let Cow::Owned(replaced) = regex.replace_all(&text, replacement.as_str()) {
+                text = Cow::Owned(replaced);
+            }
+        }
+        text
+    }
+
+    /// Mask sensitive fields in a JSON log line, then apply the patterns
+    pub fn redact_json<'a>(&self, line: &'a str) -> Cow<'a, str> {
+        match self
+            .json_fields
+            .replace_all(line, format!(r#""$1":"{}""#, MASK).as_str())
+        {
+            Cow::Borrowed(line) => self.redact(line),
+            Cow::Owned(line) => Cow::Owned(self.redact(&line).into_owned()),
+        }
+    }
+}
+
+impl Default for Redactor {
+    fn default() -> Self {
+        Redactor::new(&[], &[]).expect("built-in redaction rules are valid")
+    }
+}
+
+/// `MakeWriter` that redacts each formatted JSON log line before passing it
+/// on to `inner`
+pub struct RedactingMakeWriter<M> {
+    inner: M,
+    redactor: Arc<Redactor>,
+}
+
+impl<M> RedactingMakeWriter<M> {
+    pub fn new(inner: M, redactor: Arc<Redactor>) -> Self {
+        RedactingMakeWriter { inner, redactor }
+    }
+}
+
+impl<'a, M: MakeWriter<'a>> MakeWriter<'a> for RedactingMakeWriter<M> {
+    type Writer = RedactingWriter<M::Writer>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        RedactingWriter {
+            inner: self.inner.make_writer(),
+            redactor: self.redactor.clone(),
+            buffer: Vec::new(),
+        }
+    }
+}
+
+/// Buffers one event and writes the redacted line when dropped
+pub struct RedactingWriter<W: Write> {
+    inner: W,
+    redactor: Arc<Redactor>,
+    buffer: Vec<u8>,
+}
+
+impl<W: Write> Write for RedactingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buffer.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if self.buffer.is_empty() {
+            return self.inner.flush();
+        }
+        let text = String::from_utf8_lossy(&self.buffer);
+        let redacted = self.redactor.redact_json(&text).into_owned();
+        self.buffer.clear();
+        self.inner.write_all(redacted.as_bytes())?;
+        self.inner.flush()
+    }
+}
+
+impl<W: Write> Drop for RedactingWriter<W> {
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
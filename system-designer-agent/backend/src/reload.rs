@@ -0,0 +1,221 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+use tracing::{error, info, warn};
+
+use crate::config::{Config, ConfigError, ConfigSources};
+use crate::error::AppError;
+use crate::logging::LogFilter;
+use crate::metrics::Metrics;
+use crate::middleware::{CorsOrigins, RateLimiter};
+use crate::tenant::WorkspaceResources;
+
+/// How often the configuration file is checked for changes
+pub const WATCH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Settings applied without a restart. Everything else is read once at
+/// startup; changing it logs a warning on reload.
+pub const RELOADABLE: &[&str] = &[
+    "cors_origins",
+    "log_level",
+    "rate_limit_per_second",
+    "rate_limit_burst",
+    "health_rate_limit_per_second",
+    "health_rate_limit_burst",
+    "trusted_proxies",
+    "template_dir",
+    "lint_config",
+    "workspace_dir",
+];
+
+/// Reconfigures a running router: CORS origins, rate-limit quotas, the log
+/// filter, and templates and lint rules.
+///
+/// Each piece is swapped behind an `Arc`, so requests already running finish
+/// with the settings they started with and new requests see the new ones.
+#[derive(Clone)]
+pub struct Reloader {
+    current: Arc<Mutex<Config>>,
+    cors: CorsOrigins,
+    api_limiter: RateLimiter,
+    health_limiter: RateLimiter,
+    resources: Arc<WorkspaceResources>,
+    metrics: Arc<Metrics>,
+    log_filter: Option<LogFilter>,
+}
+
+impl Reloader {
+    pub fn new(
+        config: &Config,
+        cors: CorsOrigins,
+        api_limiter: RateLimiter,
+        health_limiter: RateLimiter,
+        resources: Arc<WorkspaceResources>,
+        metrics: Arc<Metrics>,
+    ) -> Self {
+        Reloader {
+            current: Arc::new(Mutex::new(config.clone())),
+            cors,
+            api_limiter,
+            health_limiter,
+            resources,
+            metrics,
+            log_filter: None,
+        }
+    }
+
+    /// Also change the filter of the installed log subscriber
+    pub fn with_log_filter(mut self, filter: LogFilter) -> Self {
+        self.log_filter = Some(filter);
+        self
+    }
+
+    /// The configuration in effect
+    pub fn current(&self) -> Config {
+        self.current
+            .lock()
+            .expect("current config poisoned")
+            .clone()
+    }
+
+    /// Load `sources` again and apply the result. A configuration that fails
+    /// to load or validate is rejected and the running one is kept.
+    pub fn reload(&self, sources: &ConfigSources) -> Result<(), AppError> {
+        let result = Config::load(sources)
+            .map_err(AppError::from)
+            .and_then(|config| self.apply(config));
+
+        self.metrics.config_reloaded(result.is_ok());
+        match &result {
+            Ok(()) => info!("Configuration reloaded"),
+            Err(e) => {
+                error!(error = %e, "Configuration rejected; keeping the running configuration")
+            }
+        }
+        result
+    }
+
+    /// Switch the reloadable settings to those of `config`. Every piece is
+    /// built before anything is swapped, so a broken template or filter
+    /// leaves the running configuration untouched.
+    pub fn apply(&self, config: Config) -> Result<(), AppError> {
+        let errors = config.validate();
+        if !errors.is_empty() {
+            return Err(ConfigError(errors).into());
+        }
+
+        let mut current = self.current.lock().expect("current config poisoned");
+        let resources = WorkspaceResources::prepare(&config)?;
+        let origins = CorsOrigins::parse(&config)?;
+        let filter = match &self.log_filter {
+            Some(filter) if config.log_level != current.log_level => {
+                Some((filter, LogFilter::parse(&config.log_level)?))
+            }
+            _ => None,
+        };
+
+        // Replacing the filter is the only swap that can fail, so it goes first
+        if let Some((filter, directives)) = filter {
+            filter.replace(directives)?;
+        }
+        self.resources.install(resources);
+        self.cors.replace(origins);
+        self.api_limiter.set_quota(
+            config.rate_limit_per_second,
+            config.rate_limit_burst,
+            config.trusted_proxies.clone(),
+        );
+        self.health_limiter.set_quota(
+            config.health_rate_limit_per_second,
+            config.health_rate_limit_burst,
+            config.trusted_proxies.clone(),
+        );
+
+        let ignored = restart_only_changes(&current, &config);
+        if !ignored.is_empty() {
+            warn!(settings = ?ignored, "Changed settings take effect after a restart");
+        }
+
+        current.cors_origins = config.cors_origins;
+        current.log_level = config.log_level;
+        current.rate_limit_per_second = config.rate_limit_per_second;
+        current.rate_limit_burst = config.rate_limit_burst;
+        current.health_rate_limit_per_second = config.health_rate_limit_per_second;
+        current.health_rate_limit_burst = config.health_rate_limit_burst;
+        current.trusted_proxies = config.trusted_proxies;
+        current.template_dir = config.template_dir;
+        current.lint_config = config.lint_config;
+        current.workspace_dir = config.workspace_dir;
+        Ok(())
+    }
+}
+
+/// Settings that differ between `old` and `new` but are not [`RELOADABLE`]
+fn restart_only_changes(old: &Config, new: &Config) -> Vec<String> {
+    let (Ok(old), Ok(new)) = (toml::Table::try_from(old), toml::Table::try_from(new)) else {
+        return Vec::new();
+    };
+    let keys: BTreeSet<&String> = old
+        .keys()
+        .chain(new.keys())
+        .filter(|key| !RELOADABLE.contains(&key.as_str()))
+        .filter(|key| old.get(*key) != new.get(*key))
+        .collect();
+    keys.into_iter().cloned().collect()
+}
+
+/// Reload on SIGHUP and whenever the configuration file changes, until the
+/// task is aborted
+pub async fn watch(reloader: Reloader, sources: ConfigSources) {
+    let file = sources.config_file();
+    let mut seen = file.as_deref().and_then(modified);
+    let mut ticker = tokio::time::interval(WATCH_INTERVAL);
+
+    #[cfg(unix)]
+    let mut hangup = match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup()) {
+        Ok(signal) => Some(signal),
+        Err(e) => {
+            warn!(error = %e, "Cannot listen for SIGHUP; reloading on file changes only");
+            None
+        }
+    };
+
+    loop {
+        #[cfg(unix)]
+        let hangup_received = async {
+            match hangup.as_mut() {
+                Some(signal) => signal.recv().await,
+                None => std::future::pending().await,
+            }
+        };
+        #[cfg(not(unix))]
+        let hangup_received = std::future::pending::<Option<()>>();
+
+        tokio::select! {
+            _ = hangup_received => {
+                info!("Received SIGHUP, reloading configuration");
+            }
+            _ = ticker.tick() => {
+                let Some(path) = file.as_deref() else { continue };
+                let now = modified(path);
+                if now == seen {
+                    continue;
+                }
+                seen = now;
+                info!(file = %path.display(), "Configuration file changed, reloading");
+            }
+        }
+
+        let reloader = reloader.clone();
+        let sources = sources.clone();
+        // Templates are read from disk, so keep the file IO off the runtime
+        let _ = tokio::task::spawn_blocking(move || reloader.reload(&sources)).await;
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
@@ -0,0 +1,167 @@
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use minijinja::{context, Environment, Value};
+use tracing::debug;
+
+use crate::config::Config;
+use crate::diagram::mermaid_flowchart;
+use crate::error::AppError;
+use crate::lint::Finding;
+use crate::tools::function_calling;
+use crate::DesignRequest;
+
+/// Name of the built-in template shipped with the crate
+pub const DEFAULT_TEMPLATE: &str = "default";
+
+const DEFAULT_TEMPLATE_SOURCE: &str = include_str!("templates/default.md");
+
+/// File extensions picked up from a user template directory
+const TEMPLATE_EXTENSIONS: &[&str] = &["md", "j2", "jinja"];
+
+/// Renders a `DesignRequest` into a document using named templates
+pub struct Renderer {
+    env: Environment<'static>,
+}
+
+impl Renderer {
+    /// Create a renderer holding only the built-in default template
+    pub fn new() -> Self {
+        let mut env = Environment::new();
+        env.add_filter("list", list_filter);
+        env.add_filter("flag", flag_filter);
+        env.add_filter("cell", cell_filter);
+        env.add_template(DEFAULT_TEMPLATE, DEFAULT_TEMPLATE_SOURCE)
+            .expect("built-in template must compile");
+
+        Renderer { env }
+    }
+
+    /// Create a renderer with the built-in template plus any templates
+    /// found in the configured template directory
+    pub fn from_config(config: &Config) -> Result<Self, AppError> {
+        let mut renderer = Renderer::new();
+        if let Some(dir) = &config.template_dir {
+            renderer.load_dir(dir)?;
+        }
+        Ok(renderer)
+    }
+
+    /// Load every template file in `dir`, keyed by its file stem.
+    /// A user template named `default` replaces the built-in one.
+    pub fn load_dir(&mut self, dir: &Path) -> Result<(), AppError> {
+        let entries = fs::read_dir(dir).map_err(|e| {
+            AppError::Config(format!(
+                "Cannot read template directory {}: {}",
+                dir.display(),
+                e
+            ))
+        })?;
+
+        for entry in entries {
+            let path = entry
+                .map_err(|e| AppError::Config(format!("Cannot read template entry: {}", e)))?
+                .path();
+
+            let is_template = path
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| TEMPLATE_EXTENSIONS.contains(&ext));
+            if !path.is_file() || !is_template {
+                continue;
+            }
+
+            let Some(name) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                continue;
+            };
+
+            let source = fs::read_to_string(&path).map_err(|e| {
+                AppError::Config(format!("Cannot read template {}: {}", path.display(), e))
+            })?;
+
+            self.env
+                .add_template_owned(name.to_string(), source)
+                .map_err(|e| {
+                    AppError::Config(format!("Invalid template {}: {}", path.display(), e))
+                })?;
+
+            debug!(template = name, "Loaded design template");
+        }
+
+        Ok(())
+    }
+
+    /// Names of all available templates, sorted
+    pub fn template_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .env
+            .templates()
+            .map(|(name, _)| name.to_string())
+            .collect();
+        names.sort();
+        names
+    }
+
+    /// Render `request` with the template called `name`. Templates see the
+    /// request sections at the top level, lint results as `findings`, the
+    /// Mermaid architecture flowchart as `diagram` and the descriptions of
+    /// known models, workflow patterns, platforms and interaction modes as
+    /// `descriptions` (none for free-form values), and the tool definitions
+    /// in function-calling format as the JSON string `function_calling`.
+    pub fn render(
+        &self,
+        name: &str,
+        request: &DesignRequest,
+        findings: &[Finding],
+    ) -> Result<String, AppError> {
+        let template = self
+            .env
+            .get_template(name)
+            .map_err(|_| AppError::BadRequest(format!("Unknown template: {}", name)))?;
+
+        template
+            .render(context! {
+                findings => Value::from_serialize(findings),
+                diagram => mermaid_flowchart(request),
+                function_calling => serde_json::to_string_pretty(&function_calling(&request.tools.definitions))
+                    .expect("tool definitions serialize to JSON"),
+                descriptions => context! {
+                    base_model => request.model.base_model.description(),
+                    workflow => request.orchestration.workflow.description(),
+                    platform => request.interface.platform.description(),
+                    interaction_mode => request.interface.interaction_mode.description(),
+                },
+                ..Value::from_serialize(request)
+            })
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Template {} failed: {}", name, e)))
+    }
+}
+
+impl Default for Renderer {
+    fn default() -> Self {
+        Renderer::new()
+    }
+}
+
+/// Shared renderer holding only the built-in template
+pub fn builtin() -> &'static Renderer {
+    static BUILTIN: OnceLock<Renderer> = OnceLock::new();
+    BUILTIN.get_or_init(Renderer::new)
+}
+
+/// Render a list of strings the way `{:?}` formats a `Vec<String>`
+fn list_filter(items: Vec<String>) -> String {
+    format!("{:?}", items)
+}
+
+/// Render a boolean as Rust's `true`/`false`; minijinja prints booleans
+/// Python-style as `True`/`False`, which would not match `core_generate_design`
+fn flag_filter(value: bool) -> String {
+    value.to_string()
+}
+
+/// Escape text for a Markdown table cell
+fn cell_filter(value: String) -> String {
+    value.replace('|', "\\|").replace('\n', "<br>")
+}
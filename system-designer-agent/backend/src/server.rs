@@ -0,0 +1,116 @@
+use std::net::SocketAddr;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tracing::info;
+
+use crate::config::{Config, ConfigSources};
+use crate::health::READY;
+use crate::listener::{self, Listener};
+use crate::metrics::{self, Metrics};
+use crate::tls::TlsConfig;
+use crate::{app_with_reloader, logging, reload};
+
+/// Initialize logging and serve the API until a shutdown signal arrives.
+/// `sources` are loaded again on SIGHUP or when the config file changes.
+pub async fn run(config: Config, sources: ConfigSources) -> anyhow::Result<()> {
+    // Initialize logging; span exporters are flushed when this is dropped
+    let telemetry = logging::init(&config)?;
+
+    // Build the application
+    let metrics = Arc::new(Metrics::new());
+    let (app, reloader) = app_with_reloader(&config, metrics.clone())?;
+    let watcher = tokio::spawn(reload::watch(
+        reloader.with_log_filter(telemetry.log_filter()),
+        sources,
+    ));
+
+    // Metrics on their own port stay reachable for scrapers when the API
+    // port is not exposed
+    let admin = match config.metrics_port {
+        Some(port) if config.metrics_enabled => {
+            let addr = SocketAddr::from((config.host, port));
+            let listener = tokio::net::TcpListener::bind(addr).await?;
+            info!(address = %addr, "Metrics server starting");
+            Some(tokio::spawn(async move {
+                axum::serve(listener, metrics::router(metrics)).await
+            }))
+        }
+        _ => None,
+    };
+
+    let tls = TlsConfig::from_config(&config)?;
+    let listener = Listener::bind(&config).await?;
+    info!(
+        address = %listener.describe(),
+        tls = tls.is_some(),
+        client_cert = tls.as_ref().is_some_and(TlsConfig::requires_client_cert),
+        "Server starting"
+    );
+
+    let cert_watcher = tls.clone().map(|tls| tokio::spawn(tls.watch()));
+    let pre_stop_delay = Duration::from_secs(config.pre_stop_delay_secs);
+    let drain_timeout = Duration::from_secs(config.drain_timeout_secs);
+    listener::serve(
+        listener,
+        app,
+        tls,
+        shutdown_signal(pre_stop_delay),
+        drain_timeout,
+    )
+    .await;
+    if let Some(cert_watcher) = cert_watcher {
+        cert_watcher.abort();
+    }
+
+    watcher.abort();
+    if let Some(admin) = admin {
+        admin.abort();
+    }
+
+    info!("Server shutdown complete");
+    Ok(())
+}
+
+/// Resolves once a shutdown signal arrived and the pre-stop delay passed.
+/// Until then the server keeps accepting requests while `/ready` reports
+/// not ready, so load balancers can take it out of rotation first.
+async fn shutdown_signal(pre_stop_delay: Duration) {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("Failed to install Ctrl+C handler");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("Failed to install SIGTERM handler")
+            .recv()
+            .await;
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {
+            info!("Received Ctrl+C, initiating graceful shutdown");
+        }
+        _ = terminate => {
+            info!("Received SIGTERM, initiating graceful shutdown");
+        }
+    }
+
+    // Mark as not ready so load balancers stop sending new requests
+    READY.store(false, Ordering::SeqCst);
+
+    if !pre_stop_delay.is_zero() {
+        info!(
+            delay_secs = pre_stop_delay.as_secs(),
+            "Reporting not ready before draining"
+        );
+        tokio::time::sleep(pre_stop_delay).await;
+    }
+}
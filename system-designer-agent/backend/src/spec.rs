@@ -0,0 +1,163 @@
+use axum::http::{header, HeaderMap};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::ToSchema;
+
+use crate::error::AppError;
+use crate::DesignRequest;
+
+/// Version of the `DesignSpec` document layout. Bump on breaking changes.
+pub const SPEC_VERSION: &str = "1.0";
+
+/// Vendor media type requesting the structured spec as JSON
+pub const SPEC_JSON_MEDIA_TYPE: &str = "application/vnd.system-designer.spec+json";
+
+const YAML_MEDIA_TYPES: &[&str] = &["application/yaml", "application/x-yaml", "text/yaml"];
+
+/// Canonical machine-readable form of a design
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, ToSchema)]
+pub struct DesignSpec {
+    pub spec_version: String,
+    pub metadata: SpecMetadata,
+    #[serde(flatten)]
+    pub design: DesignRequest,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, ToSchema)]
+pub struct SpecMetadata {
+    pub title: String,
+    pub generator: String,
+    pub generator_version: String,
+    pub generated_at: DateTime<Utc>,
+}
+
+impl DesignSpec {
+    /// Build a spec from a request, stamped with the current time
+    pub fn from_request(request: &DesignRequest) -> Self {
+        Self::from_request_at(request, Utc::now())
+    }
+
+    /// Build a spec from a request with an explicit generation timestamp
+    pub fn from_request_at(request: &DesignRequest, generated_at: DateTime<Utc>) -> Self {
+        let design = normalize(request);
+
+        DesignSpec {
+            spec_version: SPEC_VERSION.to_string(),
+            metadata: SpecMetadata {
+                title: design.title(),
+                generator: "system-designer-agent".to_string(),
+                generator_version: env!("CARGO_PKG_VERSION").to_string(),
+                generated_at,
+            },
+            design,
+        }
+    }
+
+    pub fn to_json(&self) -> Result<String, AppError> {
+        serde_json::to_string_pretty(self).map_err(|e| AppError::Internal(e.into()))
+    }
+
+    pub fn to_yaml(&self) -> Result<String, AppError> {
+        serde_yaml::to_string(self).map_err(|e| AppError::Internal(e.into()))
+    }
+}
+
+/// Trim every string and drop blank or duplicate list entries. Tool
+/// parameters are JSON Schema, where blank strings and repeated entries carry
+/// meaning, so they are copied through unchanged.
+fn normalize(request: &DesignRequest) -> DesignRequest {
+    let mut value = serde_json::to_value(request).expect("DesignRequest serializes to JSON");
+    normalize_value(&mut value);
+    let mut design: DesignRequest =
+        serde_json::from_value(value).expect("normalized DesignRequest keeps its shape");
+
+    for tool in &mut design.tools.definitions {
+        if let Some(original) = request
+            .tools
+            .definitions
+            .iter()
+            .find(|original| original.name.trim() == tool.name)
+        {
+            tool.parameters = original.parameters.clone();
+        }
+    }
+    design
+}
+
+fn normalize_value(value: &mut Value) {
+    match value {
+        Value::String(s) => *s = s.trim().to_string(),
+        Value::Array(items) => {
+            items.iter_mut().for_each(normalize_value);
+            let mut seen = Vec::with_capacity(items.len());
+            items.retain(|item| {
+                if item.as_str().is_some_and(str::is_empty) || seen.contains(item) {
+                    return false;
+                }
+                seen.push(item.clone());
+                true
+            });
+        }
+        Value::Object(map) => map.values_mut().for_each(normalize_value),
+        _ => {}
+    }
+}
+
+/// Output format for `/generate`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// The `{ "markdown": ... }` envelope (default)
+    Markdown,
+    /// `DesignSpec` serialized as JSON
+    Json,
+    /// `DesignSpec` serialized as YAML
+    Yaml,
+}
+
+impl OutputFormat {
+    /// Pick a format from the `format` query parameter, falling back to the
+    /// `Accept` header. Plain `application/json` keeps the Markdown envelope
+    /// so existing clients are unaffected.
+    pub fn negotiate(format: Option<&str>, headers: &HeaderMap) -> Result<Self, AppError> {
+        if let Some(format) = format {
+            return format.parse();
+        }
+
+        let accept = headers
+            .get(header::ACCEPT)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default();
+
+        for media_type in accept.split(',') {
+            let media_type = media_type.split(';').next().unwrap_or_default().trim();
+            if media_type.eq_ignore_ascii_case(SPEC_JSON_MEDIA_TYPE) {
+                return Ok(OutputFormat::Json);
+            }
+            if YAML_MEDIA_TYPES
+                .iter()
+                .any(|yaml| media_type.eq_ignore_ascii_case(yaml))
+            {
+                return Ok(OutputFormat::Yaml);
+            }
+        }
+
+        Ok(OutputFormat::Markdown)
+    }
+}
+
+impl std::str::FromStr for OutputFormat {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "markdown" | "md" => Ok(OutputFormat::Markdown),
+            "json" => Ok(OutputFormat::Json),
+            "yaml" | "yml" => Ok(OutputFormat::Yaml),
+            other => Err(AppError::BadRequest(format!(
+                "Unsupported format: {} (expected markdown, json or yaml)",
+                other
+            ))),
+        }
+    }
+}
@@ -0,0 +1,23 @@
+use std::sync::Arc;
+
+use crate::config::Config;
+use crate::error::AppError;
+use crate::store::{self, Store};
+use crate::tenant::WorkspaceResources;
+
+/// Shared state handed to every request handler
+#[derive(Clone)]
+pub struct AppState {
+    /// Templates and lint rules per workspace, see [`crate::tenant::Tenant`]
+    pub resources: Arc<WorkspaceResources>,
+    pub store: Arc<dyn Store>,
+}
+
+impl AppState {
+    pub fn from_config(config: &Config) -> Result<Self, AppError> {
+        Ok(AppState {
+            resources: Arc::new(WorkspaceResources::from_config(config)?),
+            store: store::from_config(config)?,
+        })
+    }
+}
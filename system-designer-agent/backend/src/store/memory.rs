@@ -0,0 +1,225 @@
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use super::{
+    DesignRevision, DesignStore, DesignSummary, RevisionSummary, StoredDesign, Workspace,
+    WorkspaceStore, DEFAULT_WORKSPACE,
+};
+use crate::error::AppResult;
+use crate::DesignRequest;
+
+struct Entry {
+    workspace: String,
+    design: StoredDesign,
+    revisions: Vec<DesignRevision>,
+}
+
+impl Entry {
+    fn record_revision(&mut self) {
+        self.revisions.push(DesignRevision {
+            design_id: self.design.id.clone(),
+            revision: self.design.revision,
+            request: self.design.request.clone(),
+            rendered: self.design.rendered.clone(),
+            created_at: self.design.updated_at,
+        });
+    }
+}
+
+/// Non-persistent store, used for tests and when no database is configured
+pub struct MemoryStore {
+    designs: RwLock<HashMap<String, Entry>>,
+    workspaces: RwLock<HashMap<String, Workspace>>,
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        let default = Workspace {
+            id: DEFAULT_WORKSPACE.to_string(),
+            name: "Default".to_string(),
+            created_at: Utc::now(),
+            archived_at: None,
+        };
+
+        MemoryStore {
+            designs: RwLock::new(HashMap::new()),
+            workspaces: RwLock::new(HashMap::from([(default.id.clone(), default)])),
+        }
+    }
+}
+
+impl Default for MemoryStore {
+    fn default() -> Self {
+        MemoryStore::new()
+    }
+}
+
+#[async_trait]
+impl DesignStore for MemoryStore {
+    async fn create(
+        &self,
+        workspace: &str,
+        request: DesignRequest,
+        rendered: String,
+    ) -> AppResult<StoredDesign> {
+        let now = Utc::now();
+        let mut entry = Entry {
+            workspace: workspace.to_string(),
+            design: StoredDesign {
+                id: Uuid::new_v4().to_string(),
+                title: request.title(),
+                revision: 1,
+                request,
+                rendered,
+                created_at: now,
+                updated_at: now,
+            },
+            revisions: Vec::new(),
+        };
+        entry.record_revision();
+
+        let design = entry.design.clone();
+        self.designs.write().await.insert(design.id.clone(), entry);
+        Ok(design)
+    }
+
+    async fn get(&self, workspace: &str, id: &str) -> AppResult<Option<StoredDesign>> {
+        Ok(self
+            .designs
+            .read()
+            .await
+            .get(id)
+            .filter(|entry| entry.workspace == workspace)
+            .map(|entry| entry.design.clone()))
+    }
+
+    async fn list(&self, workspace: &str) -> AppResult<Vec<DesignSummary>> {
+        let mut summaries: Vec<DesignSummary> = self
+            .designs
+            .read()
+            .await
+            .values()
+            .filter(|entry| entry.workspace == workspace)
+            .map(|entry| DesignSummary::from(&entry.design))
+            .collect();
+        summaries.sort_by_key(|s| Reverse(s.updated_at));
+        Ok(summaries)
+    }
+
+    async fn update(
+        &self,
+        workspace: &str,
+        id: &str,
+        request: DesignRequest,
+        rendered: String,
+    ) -> AppResult<Option<StoredDesign>> {
+        let mut designs = self.designs.write().await;
+        let Some(entry) = designs
+            .get_mut(id)
+            .filter(|entry| entry.workspace == workspace)
+        else {
+            return Ok(None);
+        };
+
+        let design = &mut entry.design;
+        design.title = request.title();
+        design.revision += 1;
+        design.request = request;
+        design.rendered = rendered;
+        design.updated_at = Utc::now();
+        entry.record_revision();
+
+        Ok(Some(entry.design.clone()))
+    }
+
+    async fn delete(&self, workspace: &str, id: &str) -> AppResult<bool> {
+        let mut designs = self.designs.write().await;
+        if designs
+            .get(id)
+            .is_some_and(|entry| entry.workspace == workspace)
+        {
+            designs.remove(id);
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    async fn revisions(
+        &self,
+        workspace: &str,
+        id: &str,
+    ) -> AppResult<Option<Vec<RevisionSummary>>> {
+        Ok(self
+            .designs
+            .read()
+            .await
+            .get(id)
+            .filter(|entry| entry.workspace == workspace)
+            .map(|entry| entry.revisions.iter().map(RevisionSummary::from).collect()))
+    }
+
+    async fn revision(
+        &self,
+        workspace: &str,
+        id: &str,
+        revision: u32,
+    ) -> AppResult<Option<DesignRevision>> {
+        Ok(self
+            .designs
+            .read()
+            .await
+            .get(id)
+            .filter(|entry| entry.workspace == workspace)
+            .and_then(|entry| {
+                entry
+                    .revisions
+                    .iter()
+                    .find(|r| r.revision == revision)
+                    .cloned()
+            }))
+    }
+}
+
+#[async_trait]
+impl WorkspaceStore for MemoryStore {
+    async fn create_workspace(&self, id: &str, name: &str) -> AppResult<Option<Workspace>> {
+        let mut workspaces = self.workspaces.write().await;
+        if workspaces.contains_key(id) {
+            return Ok(None);
+        }
+
+        let workspace = Workspace {
+            id: id.to_string(),
+            name: name.to_string(),
+            created_at: Utc::now(),
+            archived_at: None,
+        };
+        workspaces.insert(workspace.id.clone(), workspace.clone());
+        Ok(Some(workspace))
+    }
+
+    async fn workspace(&self, id: &str) -> AppResult<Option<Workspace>> {
+        Ok(self.workspaces.read().await.get(id).cloned())
+    }
+
+    async fn list_workspaces(&self) -> AppResult<Vec<Workspace>> {
+        let mut workspaces: Vec<Workspace> =
+            self.workspaces.read().await.values().cloned().collect();
+        workspaces.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok(workspaces)
+    }
+
+    async fn archive_workspace(&self, id: &str) -> AppResult<Option<Workspace>> {
+        let mut workspaces = self.workspaces.write().await;
+        Ok(workspaces.get_mut(id).map(|workspace| {
+            workspace.archived_at.get_or_insert_with(Utc::now);
+            workspace.clone()
+        }))
+    }
+}
@@ -0,0 +1,174 @@
+pub mod memory;
+pub mod sqlite;
+
+pub use memory::MemoryStore;
+pub use sqlite::SqliteStore;
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tracing::warn;
+use utoipa::ToSchema;
+
+use crate::config::Config;
+use crate::error::AppResult;
+use crate::DesignRequest;
+
+/// Workspace that exists in every store and holds designs saved before
+/// workspaces were introduced
+pub const DEFAULT_WORKSPACE: &str = "default";
+
+/// A saved design with its rendered output
+#[derive(Serialize, Debug, Clone, PartialEq, ToSchema)]
+pub struct StoredDesign {
+    pub id: String,
+    pub title: String,
+    /// Number of the latest revision, starting at 1
+    pub revision: u32,
+    pub request: DesignRequest,
+    pub rendered: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Listing entry for a saved design, without the payload
+#[derive(Serialize, Debug, Clone, PartialEq, ToSchema)]
+pub struct DesignSummary {
+    pub id: String,
+    pub title: String,
+    pub revision: u32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<&StoredDesign> for DesignSummary {
+    fn from(design: &StoredDesign) -> Self {
+        DesignSummary {
+            id: design.id.clone(),
+            title: design.title.clone(),
+            revision: design.revision,
+            created_at: design.created_at,
+            updated_at: design.updated_at,
+        }
+    }
+}
+
+/// Immutable snapshot of a design, recorded on every create and update
+#[derive(Serialize, Debug, Clone, PartialEq, ToSchema)]
+pub struct DesignRevision {
+    pub design_id: String,
+    pub revision: u32,
+    pub request: DesignRequest,
+    pub rendered: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Listing entry for a revision, without the payload
+#[derive(Serialize, Debug, Clone, PartialEq, ToSchema)]
+pub struct RevisionSummary {
+    pub revision: u32,
+    pub title: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<&DesignRevision> for RevisionSummary {
+    fn from(revision: &DesignRevision) -> Self {
+        RevisionSummary {
+            revision: revision.revision,
+            title: revision.request.title(),
+            created_at: revision.created_at,
+        }
+    }
+}
+
+/// A tenant that owns designs, templates and lint configuration
+#[derive(Serialize, Debug, Clone, PartialEq, ToSchema)]
+pub struct Workspace {
+    pub id: String,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    /// Archived workspaces are read-only
+    pub archived_at: Option<DateTime<Utc>>,
+}
+
+impl Workspace {
+    pub fn is_archived(&self) -> bool {
+        self.archived_at.is_some()
+    }
+}
+
+/// Persistence for saved designs. Every query is scoped to a workspace; ids
+/// from another workspace behave as unknown.
+#[async_trait]
+pub trait DesignStore: Send + Sync {
+    /// Save a new design and return it with its generated id
+    async fn create(
+        &self,
+        workspace: &str,
+        request: DesignRequest,
+        rendered: String,
+    ) -> AppResult<StoredDesign>;
+
+    async fn get(&self, workspace: &str, id: &str) -> AppResult<Option<StoredDesign>>;
+
+    /// All designs in the workspace, most recently updated first
+    async fn list(&self, workspace: &str) -> AppResult<Vec<DesignSummary>>;
+
+    /// Replace the request and rendered output, recording a new revision;
+    /// `None` if the id is unknown
+    async fn update(
+        &self,
+        workspace: &str,
+        id: &str,
+        request: DesignRequest,
+        rendered: String,
+    ) -> AppResult<Option<StoredDesign>>;
+
+    /// Returns `false` if the id is unknown. Revisions are deleted too.
+    async fn delete(&self, workspace: &str, id: &str) -> AppResult<bool>;
+
+    /// Revision history, oldest first; `None` if the id is unknown
+    async fn revisions(&self, workspace: &str, id: &str)
+        -> AppResult<Option<Vec<RevisionSummary>>>;
+
+    async fn revision(
+        &self,
+        workspace: &str,
+        id: &str,
+        revision: u32,
+    ) -> AppResult<Option<DesignRevision>>;
+}
+
+/// Persistence for workspaces. The `default` workspace always exists.
+#[async_trait]
+pub trait WorkspaceStore: Send + Sync {
+    /// `None` if a workspace with this id already exists
+    async fn create_workspace(&self, id: &str, name: &str) -> AppResult<Option<Workspace>>;
+
+    async fn workspace(&self, id: &str) -> AppResult<Option<Workspace>>;
+
+    /// All workspaces including archived ones, by id
+    async fn list_workspaces(&self) -> AppResult<Vec<Workspace>>;
+
+    /// Mark the workspace archived, keeping the first archive time;
+    /// `None` if the id is unknown
+    async fn archive_workspace(&self, id: &str) -> AppResult<Option<Workspace>>;
+}
+
+/// Designs and workspaces kept in the same backend
+pub trait Store: DesignStore + WorkspaceStore {}
+
+impl<T: DesignStore + WorkspaceStore> Store for T {}
+
+/// Open the store selected by the configuration
+pub fn from_config(config: &Config) -> AppResult<Arc<dyn Store>> {
+    match &config.database_path {
+        Some(path) => Ok(Arc::new(SqliteStore::open(path)?)),
+        None => {
+            warn!("APP_DATABASE_PATH not set, saved designs are kept in memory only");
+            Ok(Arc::new(MemoryStore::new()))
+        }
+    }
+}
@@ -0,0 +1,428 @@
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use uuid::Uuid;
+
+use super::{
+    DesignRevision, DesignStore, DesignSummary, RevisionSummary, StoredDesign, Workspace,
+    WorkspaceStore, DEFAULT_WORKSPACE,
+};
+use crate::error::{AppError, AppResult};
+use crate::migrate;
+use crate::DesignRequest;
+
+const SCHEMA: &str = r#"
+CREATE TABLE IF NOT EXISTS workspaces (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    archived_at TEXT
+);
+
+CREATE TABLE IF NOT EXISTS designs (
+    id TEXT PRIMARY KEY,
+    workspace TEXT NOT NULL DEFAULT 'default',
+    title TEXT NOT NULL,
+    request TEXT NOT NULL,
+    rendered TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS design_revisions (
+    design_id TEXT NOT NULL,
+    revision INTEGER NOT NULL,
+    request TEXT NOT NULL,
+    rendered TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    PRIMARY KEY (design_id, revision)
+);
+
+-- Designs saved before revisions were tracked start at revision 1
+INSERT OR IGNORE INTO design_revisions (design_id, revision, request, rendered, created_at)
+SELECT id, 1, request, rendered, updated_at FROM designs
+WHERE id NOT IN (SELECT design_id FROM design_revisions);
+"#;
+
+/// Run after `SCHEMA`, once the `workspace` column is known to exist
+const WORKSPACE_INDEX: &str =
+    "CREATE INDEX IF NOT EXISTS designs_workspace ON designs (workspace, updated_at)";
+
+/// Columns of `designs` plus the latest revision number
+const DESIGN_COLUMNS: &str = "id, title, request, rendered, created_at, updated_at, \
+    (SELECT MAX(revision) FROM design_revisions r WHERE r.design_id = designs.id) AS revision";
+
+/// Revisions of design `?1`, provided it belongs to workspace `?2`
+const SELECT_REVISIONS: &str = "SELECT r.* FROM design_revisions r \
+    JOIN designs d ON d.id = r.design_id WHERE r.design_id = ?1 AND d.workspace = ?2";
+
+/// Embedded SQLite store. Queries run on the blocking thread pool.
+#[derive(Clone)]
+pub struct SqliteStore {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl SqliteStore {
+    /// Open (or create) the database file at `path`
+    pub fn open(path: &Path) -> AppResult<Self> {
+        let conn = Connection::open(path).map_err(|e| {
+            AppError::Config(format!("Cannot open database {}: {}", path.display(), e))
+        })?;
+        Self::init(conn)
+    }
+
+    /// Open a private in-memory database
+    pub fn open_in_memory() -> AppResult<Self> {
+        let conn = Connection::open_in_memory().map_err(|e| AppError::Internal(e.into()))?;
+        Self::init(conn)
+    }
+
+    fn init(conn: Connection) -> AppResult<Self> {
+        Self::migrate(&conn)
+            .map_err(|e| AppError::Config(format!("Cannot initialize database schema: {}", e)))?;
+        Ok(SqliteStore {
+            conn: Arc::new(Mutex::new(conn)),
+        })
+    }
+
+    fn migrate(conn: &Connection) -> rusqlite::Result<()> {
+        conn.execute_batch(SCHEMA)?;
+
+        // Databases created before workspaces existed lack the column; their
+        // designs belong to the default workspace
+        let has_workspace = conn
+            .prepare("SELECT 1 FROM pragma_table_info('designs') WHERE name = 'workspace'")?
+            .exists([])?;
+        if !has_workspace {
+            conn.execute_batch(
+                "ALTER TABLE designs ADD COLUMN workspace TEXT NOT NULL DEFAULT 'default'",
+            )?;
+        }
+        conn.execute_batch(WORKSPACE_INDEX)?;
+
+        conn.execute(
+            "INSERT OR IGNORE INTO workspaces (id, name, created_at) VALUES (?1, 'Default', ?2)",
+            params![DEFAULT_WORKSPACE, Utc::now()],
+        )?;
+        Ok(())
+    }
+
+    async fn with_conn<T, F>(&self, f: F) -> AppResult<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Connection) -> rusqlite::Result<T> + Send + 'static,
+    {
+        let conn = Arc::clone(&self.conn);
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            f(&conn)
+        })
+        .await
+        .map_err(|e| AppError::Internal(e.into()))?
+        .map_err(|e| AppError::Internal(e.into()))
+    }
+}
+
+fn encode_request(request: &DesignRequest) -> AppResult<String> {
+    serde_json::to_string(request).map_err(|e| AppError::Internal(e.into()))
+}
+
+/// Rows written by older versions are upgraded to the current schema
+fn request_from_row(row: &Row) -> rusqlite::Result<DesignRequest> {
+    let request: String = row.get("request")?;
+    let invalid = |e: Box<dyn std::error::Error + Send + Sync>| {
+        rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, e)
+    };
+    let value = serde_json::from_str(&request).map_err(|e| invalid(Box::new(e)))?;
+    migrate::design_request(value).map_err(|e| invalid(Box::new(e)))
+}
+
+fn design_from_row(row: &Row) -> rusqlite::Result<StoredDesign> {
+    Ok(StoredDesign {
+        id: row.get("id")?,
+        title: row.get("title")?,
+        revision: row.get("revision")?,
+        request: request_from_row(row)?,
+        rendered: row.get("rendered")?,
+        created_at: row.get("created_at")?,
+        updated_at: row.get("updated_at")?,
+    })
+}
+
+fn revision_from_row(row: &Row) -> rusqlite::Result<DesignRevision> {
+    Ok(DesignRevision {
+        design_id: row.get("design_id")?,
+        revision: row.get("revision")?,
+        request: request_from_row(row)?,
+        rendered: row.get("rendered")?,
+        created_at: row.get("created_at")?,
+    })
+}
+
+fn workspace_from_row(row: &Row) -> rusqlite::Result<Workspace> {
+    Ok(Workspace {
+        id: row.get("id")?,
+        name: row.get("name")?,
+        created_at: row.get("created_at")?,
+        archived_at: row.get("archived_at")?,
+    })
+}
+
+fn select_workspace(conn: &Connection, id: &str) -> rusqlite::Result<Option<Workspace>> {
+    conn.query_row(
+        "SELECT * FROM workspaces WHERE id = ?1",
+        params![id],
+        workspace_from_row,
+    )
+    .optional()
+}
+
+fn select_design(
+    conn: &Connection,
+    workspace: &str,
+    id: &str,
+) -> rusqlite::Result<Option<StoredDesign>> {
+    conn.query_row(
+        &format!(
+            "SELECT {} FROM designs WHERE id = ?1 AND workspace = ?2",
+            DESIGN_COLUMNS
+        ),
+        params![id, workspace],
+        design_from_row,
+    )
+    .optional()
+}
+
+fn insert_revision(
+    conn: &Connection,
+    id: &str,
+    revision: u32,
+    encoded: &str,
+    rendered: &str,
+    created_at: DateTime<Utc>,
+) -> rusqlite::Result<usize> {
+    conn.execute(
+        "INSERT INTO design_revisions (design_id, revision, request, rendered, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![id, revision, encoded, rendered, created_at],
+    )
+}
+
+#[async_trait]
+impl DesignStore for SqliteStore {
+    async fn create(
+        &self,
+        workspace: &str,
+        request: DesignRequest,
+        rendered: String,
+    ) -> AppResult<StoredDesign> {
+        let workspace = workspace.to_string();
+        let now = Utc::now();
+        let design = StoredDesign {
+            id: Uuid::new_v4().to_string(),
+            title: request.title(),
+            revision: 1,
+            request,
+            rendered,
+            created_at: now,
+            updated_at: now,
+        };
+        let encoded = encode_request(&design.request)?;
+
+        let row = design.clone();
+        self.with_conn(move |conn| {
+            let tx = conn.unchecked_transaction()?;
+            tx.execute(
+                "INSERT INTO designs (id, workspace, title, request, rendered, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    row.id,
+                    workspace,
+                    row.title,
+                    encoded,
+                    row.rendered,
+                    row.created_at,
+                    row.updated_at
+                ],
+            )?;
+            insert_revision(&tx, &row.id, row.revision, &encoded, &row.rendered, now)?;
+            tx.commit()
+        })
+        .await?;
+
+        Ok(design)
+    }
+
+    async fn get(&self, workspace: &str, id: &str) -> AppResult<Option<StoredDesign>> {
+        let workspace = workspace.to_string();
+        let id = id.to_string();
+        self.with_conn(move |conn| select_design(conn, &workspace, &id))
+            .await
+    }
+
+    async fn list(&self, workspace: &str) -> AppResult<Vec<DesignSummary>> {
+        let workspace = workspace.to_string();
+        self.with_conn(move |conn| {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {} FROM designs WHERE workspace = ?1 ORDER BY updated_at DESC",
+                DESIGN_COLUMNS
+            ))?;
+            let rows = stmt.query_map(params![workspace], |row| {
+                Ok(DesignSummary {
+                    id: row.get("id")?,
+                    title: row.get("title")?,
+                    revision: row.get("revision")?,
+                    created_at: row.get("created_at")?,
+                    updated_at: row.get("updated_at")?,
+                })
+            })?;
+            rows.collect()
+        })
+        .await
+    }
+
+    async fn update(
+        &self,
+        workspace: &str,
+        id: &str,
+        request: DesignRequest,
+        rendered: String,
+    ) -> AppResult<Option<StoredDesign>> {
+        let workspace = workspace.to_string();
+        let id = id.to_string();
+        let title = request.title();
+        let encoded = encode_request(&request)?;
+        let now = Utc::now();
+
+        self.with_conn(move |conn| {
+            let tx = conn.unchecked_transaction()?;
+            let Some(current) = select_design(&tx, &workspace, &id)? else {
+                return Ok(None);
+            };
+
+            tx.execute(
+                "UPDATE designs SET title = ?2, request = ?3, rendered = ?4, updated_at = ?5
+                 WHERE id = ?1",
+                params![id, title, encoded, rendered, now],
+            )?;
+            insert_revision(&tx, &id, current.revision + 1, &encoded, &rendered, now)?;
+            let updated = select_design(&tx, &workspace, &id)?;
+            tx.commit()?;
+            Ok(updated)
+        })
+        .await
+    }
+
+    async fn delete(&self, workspace: &str, id: &str) -> AppResult<bool> {
+        let workspace = workspace.to_string();
+        let id = id.to_string();
+        self.with_conn(move |conn| {
+            let tx = conn.unchecked_transaction()?;
+            let deleted = tx.execute(
+                "DELETE FROM designs WHERE id = ?1 AND workspace = ?2",
+                params![id, workspace],
+            )?;
+            if deleted > 0 {
+                tx.execute(
+                    "DELETE FROM design_revisions WHERE design_id = ?1",
+                    params![id],
+                )?;
+            }
+            tx.commit()?;
+            Ok(deleted > 0)
+        })
+        .await
+    }
+
+    async fn revisions(
+        &self,
+        workspace: &str,
+        id: &str,
+    ) -> AppResult<Option<Vec<RevisionSummary>>> {
+        let workspace = workspace.to_string();
+        let id = id.to_string();
+        self.with_conn(move |conn| {
+            let mut stmt = conn.prepare(&format!("{} ORDER BY r.revision", SELECT_REVISIONS))?;
+            let revisions: Vec<RevisionSummary> = stmt
+                .query_map(params![id, workspace], revision_from_row)?
+                .map(|revision| revision.map(|r| RevisionSummary::from(&r)))
+                .collect::<rusqlite::Result<_>>()?;
+            Ok((!revisions.is_empty()).then_some(revisions))
+        })
+        .await
+    }
+
+    async fn revision(
+        &self,
+        workspace: &str,
+        id: &str,
+        revision: u32,
+    ) -> AppResult<Option<DesignRevision>> {
+        let workspace = workspace.to_string();
+        let id = id.to_string();
+        self.with_conn(move |conn| {
+            conn.query_row(
+                &format!("{} AND r.revision = ?3", SELECT_REVISIONS),
+                params![id, workspace, revision],
+                revision_from_row,
+            )
+            .optional()
+        })
+        .await
+    }
+}
+
+#[async_trait]
+impl WorkspaceStore for SqliteStore {
+    async fn create_workspace(&self, id: &str, name: &str) -> AppResult<Option<Workspace>> {
+        let workspace = Workspace {
+            id: id.to_string(),
+            name: name.to_string(),
+            created_at: Utc::now(),
+            archived_at: None,
+        };
+
+        let row = workspace.clone();
+        let inserted = self
+            .with_conn(move |conn| {
+                conn.execute(
+                    "INSERT OR IGNORE INTO workspaces (id, name, created_at) VALUES (?1, ?2, ?3)",
+                    params![row.id, row.name, row.created_at],
+                )
+            })
+            .await?;
+
+        Ok((inserted > 0).then_some(workspace))
+    }
+
+    async fn workspace(&self, id: &str) -> AppResult<Option<Workspace>> {
+        let id = id.to_string();
+        self.with_conn(move |conn| select_workspace(conn, &id))
+            .await
+    }
+
+    async fn list_workspaces(&self) -> AppResult<Vec<Workspace>> {
+        self.with_conn(|conn| {
+            let mut stmt = conn.prepare("SELECT * FROM workspaces ORDER BY id")?;
+            let rows = stmt.query_map([], workspace_from_row)?;
+            rows.collect()
+        })
+        .await
+    }
+
+    async fn archive_workspace(&self, id: &str) -> AppResult<Option<Workspace>> {
+        let id = id.to_string();
+        let now = Utc::now();
+        self.with_conn(move |conn| {
+            conn.execute(
+                "UPDATE workspaces SET archived_at = COALESCE(archived_at, ?2) WHERE id = ?1",
+                params![id, now],
+            )?;
+            select_workspace(conn, &id)
+        })
+        .await
+    }
+}
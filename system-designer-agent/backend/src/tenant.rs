@@ -0,0 +1,240 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use axum::extract::FromRequestParts;
+use axum::http::request::Parts;
+use tracing::{debug, warn};
+
+use crate::config::Config;
+use crate::error::{AppError, AppResult};
+use crate::lint::{LintConfig, Linter};
+use crate::middleware::{Principal, Scope};
+use crate::renderer::Renderer;
+use crate::state::AppState;
+use crate::store::DEFAULT_WORKSPACE;
+
+/// Header naming the workspace a request acts on
+pub const WORKSPACE_HEADER: &str = "x-workspace-id";
+
+/// Workspace a request is scoped to, with its templates and lint rules.
+///
+/// An authenticated principal uses the workspace it is bound to, or the
+/// default workspace when unbound; only admins may pick another one with
+/// `X-Workspace-Id`. When authentication is disabled every caller uses the
+/// header or falls back to the default workspace.
+#[derive(Clone)]
+pub struct Tenant {
+    pub workspace: String,
+    pub archived: bool,
+    pub renderer: Arc<Renderer>,
+    pub linter: Arc<Linter>,
+}
+
+impl Tenant {
+    /// Archived workspaces stay readable but reject changes
+    pub fn ensure_writable(&self) -> AppResult<()> {
+        if self.archived {
+            return Err(AppError::Forbidden(format!(
+                "Workspace {} is archived",
+                self.workspace
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[axum::async_trait]
+impl FromRequestParts<AppState> for Tenant {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> AppResult<Self> {
+        let requested = match parts.headers.get(WORKSPACE_HEADER) {
+            Some(value) => Some(
+                value
+                    .to_str()
+                    .map_err(|_| AppError::BadRequest("Invalid X-Workspace-Id header".to_string()))?
+                    .trim()
+                    .to_string(),
+            ),
+            None => None,
+        };
+        let principal = parts.extensions.get::<Principal>();
+
+        let id = resolve(principal, requested)?;
+        let workspace = state
+            .store
+            .workspace(&id)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("Workspace {} not found", id)))?;
+
+        let (renderer, linter) = state.resources.get(&workspace.id).await?;
+        Ok(Tenant {
+            archived: workspace.is_archived(),
+            workspace: workspace.id,
+            renderer,
+            linter,
+        })
+    }
+}
+
+fn resolve(principal: Option<&Principal>, requested: Option<String>) -> AppResult<String> {
+    // Without authentication there is no principal and the header decides
+    let Some(principal) = principal else {
+        return Ok(requested.unwrap_or_else(|| DEFAULT_WORKSPACE.to_string()));
+    };
+
+    let own = principal
+        .workspace
+        .clone()
+        .unwrap_or_else(|| DEFAULT_WORKSPACE.to_string());
+    match requested {
+        Some(requested) if requested != own => {
+            if principal.has_scope(Scope::Admin) {
+                Ok(requested)
+            } else {
+                warn!(
+                    subject = %principal.subject,
+                    workspace = %requested,
+                    "Workspace outside principal binding"
+                );
+                Err(AppError::Forbidden(format!(
+                    "Not allowed to access workspace {}",
+                    requested
+                )))
+            }
+        }
+        _ => Ok(own),
+    }
+}
+
+type Resources = (Arc<Renderer>, Arc<Linter>);
+
+/// Renderers and linters per workspace, built on first use.
+///
+/// Every workspace starts from the shared templates and lint configuration.
+/// With a workspace directory configured, templates in
+/// `<dir>/<workspace>/templates/` are added on top (replacing shared ones of
+/// the same name) and `<dir>/<workspace>/lint.toml` replaces the shared lint
+/// configuration.
+pub struct WorkspaceResources {
+    loaded: Mutex<Loaded>,
+}
+
+/// Everything [`WorkspaceResources::reload`] swaps in one step
+pub struct Loaded {
+    config: Config,
+    shared: Resources,
+    cache: HashMap<String, Resources>,
+}
+
+impl Loaded {
+    fn from_config(config: &Config) -> Result<Self, AppError> {
+        Ok(Loaded {
+            config: config.clone(),
+            shared: (
+                Arc::new(Renderer::from_config(config)?),
+                Arc::new(Linter::from_config(config)?),
+            ),
+            cache: HashMap::new(),
+        })
+    }
+}
+
+impl WorkspaceResources {
+    pub fn from_config(config: &Config) -> Result<Self, AppError> {
+        Ok(WorkspaceResources {
+            loaded: Mutex::new(Loaded::from_config(config)?),
+        })
+    }
+
+    /// Replace the shared templates and lint rules and forget every
+    /// workspace's overrides. Nothing changes if loading fails; requests
+    /// already running keep the renderer and linter they started with.
+    pub fn reload(&self, config: &Config) -> Result<(), AppError> {
+        self.install(WorkspaceResources::prepare(config)?);
+        Ok(())
+    }
+
+    /// Load the shared templates and lint rules for `config` without
+    /// swapping them in
+    pub fn prepare(config: &Config) -> Result<Loaded, AppError> {
+        Loaded::from_config(config)
+    }
+
+    /// Swap in resources built by [`WorkspaceResources::prepare`]
+    pub fn install(&self, loaded: Loaded) {
+        *self.loaded.lock().expect("workspace cache poisoned") = loaded;
+    }
+
+    /// Renderer and linter for `workspace`. Overrides are read from disk on
+    /// a blocking thread without holding the cache lock; if a reload swapped
+    /// the shared resources meanwhile, the result serves this request but is
+    /// not cached.
+    pub async fn get(&self, workspace: &str) -> Result<Resources, AppError> {
+        let (config, shared) = {
+            let loaded = self.loaded.lock().expect("workspace cache poisoned");
+            if let Some(resources) = loaded.cache.get(workspace) {
+                return Ok(resources.clone());
+            }
+            (loaded.config.clone(), loaded.shared.clone())
+        };
+
+        let name = workspace.to_string();
+        let base = shared.clone();
+        let resources = tokio::task::spawn_blocking(move || load_workspace(&config, &name, base))
+            .await
+            .map_err(|e| AppError::Internal(e.into()))??;
+        // Without an override directory the shared resources apply
+        let Some(resources) = resources else {
+            return Ok(shared);
+        };
+
+        let mut loaded = self.loaded.lock().expect("workspace cache poisoned");
+        if !Arc::ptr_eq(&loaded.shared.0, &shared.0) {
+            return Ok(resources);
+        }
+        Ok(loaded
+            .cache
+            .entry(workspace.to_string())
+            .or_insert(resources)
+            .clone())
+    }
+}
+
+/// Shared resources with `workspace`'s templates and lint rules applied, or
+/// `None` when it has no override directory
+fn load_workspace(
+    config: &Config,
+    workspace: &str,
+    shared: Resources,
+) -> AppResult<Option<Resources>> {
+    let Some(dir) = workspace_dir(config, workspace) else {
+        return Ok(None);
+    };
+
+    let templates = dir.join("templates");
+    let renderer = if templates.is_dir() {
+        let mut renderer = Renderer::from_config(config)?;
+        renderer.load_dir(&templates)?;
+        Arc::new(renderer)
+    } else {
+        shared.0
+    };
+
+    let lint_config = dir.join("lint.toml");
+    let linter = if lint_config.is_file() {
+        Arc::new(Linter::with_config(&LintConfig::load(&lint_config)?))
+    } else {
+        shared.1
+    };
+
+    debug!(workspace, "Loaded workspace templates and lint rules");
+    Ok(Some((renderer, linter)))
+}
+
+/// Override directory for `workspace`, if one exists
+fn workspace_dir(config: &Config, workspace: &str) -> Option<PathBuf> {
+    let dir = config.workspace_dir.as_ref()?.join(workspace);
+    dir.is_dir().then_some(dir)
+}
@@ -0,0 +1,153 @@
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::SystemTime;
+
+use rustls::server::WebPkiClientVerifier;
+use rustls::{RootCertStore, ServerConfig};
+use rustls_pki_types::pem::PemObject;
+use rustls_pki_types::{CertificateDer, PrivateKeyDer};
+use tokio_rustls::TlsAcceptor;
+use tracing::{error, info};
+
+use crate::config::Config;
+use crate::error::AppError;
+use crate::reload::WATCH_INTERVAL;
+
+/// Certificate, key and optional client CA files
+#[derive(Debug, Clone)]
+struct TlsPaths {
+    cert: PathBuf,
+    key: PathBuf,
+    client_ca: Option<PathBuf>,
+}
+
+/// rustls server settings that follow the certificate files on disk.
+/// Clones share the settings; each new connection uses the latest ones.
+#[derive(Clone)]
+pub struct TlsConfig {
+    paths: TlsPaths,
+    current: Arc<RwLock<Arc<ServerConfig>>>,
+}
+
+impl TlsConfig {
+    /// `None` unless `tls_cert` and `tls_key` are configured
+    pub fn from_config(config: &Config) -> Result<Option<Self>, AppError> {
+        let (Some(cert), Some(key)) = (&config.tls_cert, &config.tls_key) else {
+            return Ok(None);
+        };
+        let paths = TlsPaths {
+            cert: cert.clone(),
+            key: key.clone(),
+            client_ca: config.tls_client_ca.clone(),
+        };
+        let server = server_config(&paths)?;
+        Ok(Some(TlsConfig {
+            paths,
+            current: Arc::new(RwLock::new(Arc::new(server))),
+        }))
+    }
+
+    /// Whether clients must present a certificate
+    pub fn requires_client_cert(&self) -> bool {
+        self.paths.client_ca.is_some()
+    }
+
+    pub fn acceptor(&self) -> TlsAcceptor {
+        TlsAcceptor::from(self.current.read().expect("TLS config poisoned").clone())
+    }
+
+    /// Read the files again. On failure the previous certificate stays in
+    /// use; established connections are never affected.
+    pub fn reload(&self) -> Result<(), AppError> {
+        let server = server_config(&self.paths)?;
+        *self.current.write().expect("TLS config poisoned") = Arc::new(server);
+        Ok(())
+    }
+
+    /// Reload whenever one of the files changes, until the task is aborted
+    pub async fn watch(self) {
+        let mut seen = self.modified();
+        let mut ticker = tokio::time::interval(WATCH_INTERVAL);
+        loop {
+            ticker.tick().await;
+            let now = self.modified();
+            if now == seen {
+                continue;
+            }
+            seen = now;
+
+            let tls = self.clone();
+            match tokio::task::spawn_blocking(move || tls.reload()).await {
+                Ok(Ok(())) => info!(cert = %self.paths.cert.display(), "TLS certificate reloaded"),
+                Ok(Err(e)) => {
+                    error!(error = %e, "TLS certificate rejected; keeping the previous one")
+                }
+                Err(e) => error!(error = %e, "TLS certificate reload failed"),
+            }
+        }
+    }
+
+    fn modified(&self) -> Vec<Option<SystemTime>> {
+        [
+            Some(&self.paths.cert),
+            Some(&self.paths.key),
+            self.paths.client_ca.as_ref(),
+        ]
+        .into_iter()
+        .flatten()
+        .map(|path| std::fs::metadata(path).and_then(|m| m.modified()).ok())
+        .collect()
+    }
+}
+
+fn server_config(paths: &TlsPaths) -> Result<ServerConfig, AppError> {
+    let certs = load_certs(&paths.cert)?;
+    let key = PrivateKeyDer::from_pem_file(&paths.key)
+        .map_err(|e| AppError::Config(format!("Invalid TLS key {}: {}", paths.key.display(), e)))?;
+
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let builder = ServerConfig::builder_with_provider(provider.clone())
+        .with_safe_default_protocol_versions()
+        .map_err(|e| AppError::Config(format!("TLS setup failed: {}", e)))?;
+
+    let builder = match &paths.client_ca {
+        Some(path) => {
+            let mut roots = RootCertStore::empty();
+            for cert in load_certs(path)? {
+                roots.add(cert).map_err(|e| {
+                    AppError::Config(format!("Invalid client CA {}: {}", path.display(), e))
+                })?;
+            }
+            let verifier = WebPkiClientVerifier::builder_with_provider(Arc::new(roots), provider)
+                .build()
+                .map_err(|e| {
+                    AppError::Config(format!("Invalid client CA {}: {}", path.display(), e))
+                })?;
+            builder.with_client_cert_verifier(verifier)
+        }
+        None => builder.with_no_client_auth(),
+    };
+
+    let mut server = builder.with_single_cert(certs, key).map_err(|e| {
+        AppError::Config(format!(
+            "TLS key does not match {}: {}",
+            paths.cert.display(),
+            e
+        ))
+    })?;
+    server.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+    Ok(server)
+}
+
+fn load_certs(path: &Path) -> Result<Vec<CertificateDer<'static>>, AppError> {
+    let invalid =
+        |e: String| AppError::Config(format!("Invalid certificate {}: {}", path.display(), e));
+    let certs = CertificateDer::pem_file_iter(path)
+        .map_err(|e| invalid(e.to_string()))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| invalid(e.to_string()))?;
+    if certs.is_empty() {
+        return Err(invalid("no certificates found".to_string()));
+    }
+    Ok(certs)
+}
@@ -0,0 +1,122 @@
+use std::collections::HashSet;
+
+use schemars::{json_schema, JsonSchema, Schema, SchemaGenerator};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use validator::{Validate, ValidationError};
+
+/// A tool the agent can call, described well enough to generate a
+/// function-calling definition and to review its risk
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Validate, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ToolDefinition {
+    /// Function name the model calls; letters, digits, `_` and `-`
+    #[validate(length(min = 1, max = 64), custom(function = "validate_name"))]
+    pub name: String,
+    #[serde(default)]
+    #[validate(length(max = 1000))]
+    pub description: String,
+    /// JSON Schema of the arguments; must describe an object
+    #[serde(default = "empty_parameters")]
+    #[schemars(schema_with = "parameters_schema")]
+    #[validate(custom(function = "validate_parameters"))]
+    pub parameters: Value,
+    #[serde(default)]
+    pub auth: AuthMethod,
+    pub side_effect: SideEffect,
+    /// e.g. `100 requests/minute`; empty when the tool is not rate limited
+    #[serde(default)]
+    #[validate(length(max = 100))]
+    pub rate_limit: String,
+}
+
+/// How the tool authenticates against the service behind it
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Default, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthMethod {
+    #[default]
+    None,
+    ApiKey,
+    Bearer,
+    #[serde(rename = "oauth2")]
+    OAuth2,
+    Basic,
+    Mtls,
+}
+
+/// What calling the tool does to the outside world
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SideEffect {
+    /// Only reads data
+    Read,
+    /// Creates or changes data
+    Write,
+    /// Deletes data, moves money or does anything else that cannot be undone
+    Destructive,
+}
+
+fn empty_parameters() -> Value {
+    json!({ "type": "object", "properties": {} })
+}
+
+fn parameters_schema(_: &mut SchemaGenerator) -> Schema {
+    json_schema!({
+        "type": "object",
+        "default": empty_parameters(),
+    })
+}
+
+fn validate_name(name: &str) -> Result<(), ValidationError> {
+    if name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        Ok(())
+    } else {
+        Err(ValidationError::new("tool_name")
+            .with_message("must contain only letters, digits, '_' and '-'".into()))
+    }
+}
+
+fn validate_parameters(parameters: &Value) -> Result<(), ValidationError> {
+    let is_object_schema = parameters
+        .as_object()
+        .is_some_and(|schema| schema.get("type").is_none_or(|t| t == "object"));
+    if is_object_schema {
+        Ok(())
+    } else {
+        Err(ValidationError::new("parameters")
+            .with_message("must be a JSON Schema describing an object".into()))
+    }
+}
+
+/// Tool names must be unique for the model to tell the functions apart
+pub fn validate_unique_names(tools: &[ToolDefinition]) -> Result<(), ValidationError> {
+    let mut seen = HashSet::new();
+    match tools.iter().find(|tool| !seen.insert(tool.name.as_str())) {
+        None => Ok(()),
+        Some(tool) => Err(ValidationError::new("unique")
+            .with_message(format!("tool name {:?} is used more than once", tool.name).into())),
+    }
+}
+
+/// The definitions in the function-calling format shared by the OpenAI
+/// Chat Completions API and most compatible providers
+pub fn function_calling(tools: &[ToolDefinition]) -> Value {
+    tools
+        .iter()
+        .map(|tool| {
+            let mut function = Map::new();
+            function.insert("name".to_string(), Value::from(tool.name.as_str()));
+            if !tool.description.is_empty() {
+                function.insert(
+                    "description".to_string(),
+                    Value::from(tool.description.as_str()),
+                );
+            }
+            function.insert("parameters".to_string(), tool.parameters.clone());
+            json!({ "type": "function", "function": function })
+        })
+        .collect()
+}
@@ -0,0 +1,33 @@
+pub mod ui;
+pub mod wizard;
+
+use std::path::PathBuf;
+
+use ratatui::crossterm::event::{self, Event, KeyEventKind};
+
+use crate::error::AppError;
+use crate::DesignRequest;
+
+pub use self::wizard::Wizard;
+
+/// Run the interactive design wizard in the current terminal until the
+/// user quits; the spec is written to `output` on save
+pub fn run(request: DesignRequest, output: PathBuf) -> Result<(), AppError> {
+    let mut wizard = Wizard::new(request, output);
+    let mut terminal = ratatui::init();
+
+    let result = (|| -> std::io::Result<()> {
+        while !wizard.quit {
+            terminal.draw(|frame| ui::draw(frame, &wizard))?;
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press {
+                    wizard.handle_key(key);
+                }
+            }
+        }
+        Ok(())
+    })();
+
+    ratatui::restore();
+    result.map_err(|e| AppError::Internal(e.into()))
+}
@@ -0,0 +1,135 @@
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, Paragraph, Wrap};
+use ratatui::Frame;
+
+use super::wizard::{FieldKind, Screen, Wizard, STEPS};
+
+const ACCENT: Color = Color::Blue;
+
+/// Draw the whole wizard: step list, form or preview, and key hints
+pub fn draw(frame: &mut Frame, wizard: &Wizard) {
+    let [main, footer] =
+        Layout::vertical([Constraint::Min(0), Constraint::Length(2)]).areas(frame.area());
+    let [sidebar, content] =
+        Layout::horizontal([Constraint::Length(28), Constraint::Min(0)]).areas(main);
+
+    draw_steps(frame, sidebar, wizard);
+    match wizard.screen {
+        Screen::Form => draw_form(frame, content, wizard),
+        Screen::Preview => draw_preview(frame, content, wizard),
+    }
+    draw_footer(frame, footer, wizard);
+}
+
+fn draw_steps(frame: &mut Frame, area: Rect, wizard: &Wizard) {
+    let items: Vec<ListItem> = STEPS
+        .iter()
+        .enumerate()
+        .map(|(i, step)| {
+            let (marker, style) = if wizard.screen == Screen::Form && i == wizard.step {
+                (
+                    "▸",
+                    Style::default().fg(ACCENT).add_modifier(Modifier::BOLD),
+                )
+            } else if i < wizard.step || wizard.screen == Screen::Preview {
+                ("✓", Style::default().fg(Color::Green))
+            } else {
+                (" ", Style::default().fg(Color::DarkGray))
+            };
+            ListItem::new(Line::styled(
+                format!("{} {}. {}", marker, i + 1, step.name),
+                style,
+            ))
+        })
+        .collect();
+
+    frame.render_widget(
+        List::new(items).block(Block::default().borders(Borders::ALL).title(" Architect ")),
+        area,
+    );
+}
+
+fn draw_form(frame: &mut Frame, area: Rect, wizard: &Wizard) {
+    let step = wizard.current_step();
+    let mut lines: Vec<Line> = Vec::new();
+
+    for (i, field) in step.fields.iter().enumerate() {
+        let focused = i == wizard.field;
+        let label_style = if focused {
+            Style::default().fg(ACCENT).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().add_modifier(Modifier::BOLD)
+        };
+        lines.push(Line::styled(field.label, label_style));
+
+        let editing = focused && field.kind != FieldKind::Flag;
+        let value = if editing {
+            wizard.buffer.clone()
+        } else {
+            wizard.display_value(step, field)
+        };
+        let mut spans = vec![if value.is_empty() {
+            Span::styled(field.placeholder, Style::default().fg(Color::DarkGray))
+        } else {
+            Span::raw(value)
+        }];
+        if editing {
+            spans.insert(usize::from(!wizard.buffer.is_empty()), Span::raw("█"));
+        }
+        lines.push(Line::from(spans));
+
+        for message in wizard.errors_for(step, field) {
+            lines.push(Line::styled(
+                format!("  ✗ {}", message),
+                Style::default().fg(Color::Red),
+            ));
+        }
+        lines.push(Line::default());
+    }
+
+    let title = format!(
+        " Step {} of {}: {} ",
+        wizard.step + 1,
+        STEPS.len(),
+        step.name
+    );
+    frame.render_widget(
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .block(Block::default().borders(Borders::ALL).title(title)),
+        area,
+    );
+}
+
+fn draw_preview(frame: &mut Frame, area: Rect, wizard: &Wizard) {
+    frame.render_widget(
+        Paragraph::new(wizard.preview.as_str())
+            .wrap(Wrap { trim: false })
+            .scroll((wizard.scroll, 0))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(format!(" Preview: {} ", wizard.output.display())),
+            ),
+        area,
+    );
+}
+
+fn draw_footer(frame: &mut Frame, area: Rect, wizard: &Wizard) {
+    let hints = match wizard.screen {
+        Screen::Form => {
+            "Tab/Enter next field · Shift-Tab previous · PgUp/PgDn step · Space toggle · Ctrl-P preview · Ctrl-S save · Esc quit"
+        }
+        Screen::Preview => "↑/↓ scroll · s save · b back to form · q quit",
+    };
+    let mut lines = vec![Line::styled(hints, Style::default().fg(Color::DarkGray))];
+    if let Some(status) = &wizard.status {
+        lines.insert(
+            0,
+            Line::styled(status.as_str(), Style::default().fg(Color::Yellow)),
+        );
+    }
+    frame.render_widget(Paragraph::new(lines), area);
+}
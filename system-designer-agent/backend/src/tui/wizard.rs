@@ -0,0 +1,539 @@
+use std::fs;
+use std::path::PathBuf;
+
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde_json::Value;
+use validator::{Validate, ValidationError};
+
+use crate::error::AppError;
+use crate::extractors::field_errors;
+use crate::{core_generate_design, DesignRequest};
+
+/// How a form field is edited and stored in the `DesignRequest`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldKind {
+    Text,
+    /// Comma separated in the form, a list in the request
+    List,
+    /// Toggled with space
+    Flag,
+}
+
+#[derive(Debug)]
+pub struct Field {
+    pub label: &'static str,
+    pub key: &'static str,
+    pub placeholder: &'static str,
+    pub kind: FieldKind,
+}
+
+#[derive(Debug)]
+pub struct Step {
+    pub name: &'static str,
+    /// `DesignRequest` section the step fills
+    pub key: &'static str,
+    pub fields: &'static [Field],
+}
+
+const fn text(label: &'static str, key: &'static str, placeholder: &'static str) -> Field {
+    Field {
+        label,
+        key,
+        placeholder,
+        kind: FieldKind::Text,
+    }
+}
+
+const fn list(label: &'static str, key: &'static str, placeholder: &'static str) -> Field {
+    Field {
+        label,
+        key,
+        placeholder,
+        kind: FieldKind::List,
+    }
+}
+
+const fn flag(label: &'static str, key: &'static str) -> Field {
+    Field {
+        label,
+        key,
+        placeholder: "",
+        kind: FieldKind::Flag,
+    }
+}
+
+/// The eight wizard steps, matching the `steps` array of the web UI
+pub const STEPS: &[Step] = &[
+    Step {
+        name: "Purpose & Scope",
+        key: "purpose",
+        fields: &[
+            text("Use Case", "use_case", "e.g. Enterprise Knowledge Base"),
+            text("Success Criteria", "success_criteria", "e.g. < 2s Latency"),
+            text(
+                "User Needs",
+                "user_needs",
+                "What specific problems are we solving?",
+            ),
+            text(
+                "Constraints",
+                "constraints",
+                "Budget, Tech Stack, Regulatory requirements",
+            ),
+        ],
+    },
+    Step {
+        name: "System Prompt Design",
+        key: "prompt",
+        fields: &[
+            text("Role / Persona", "role", "Who is this agent?"),
+            text("Primary Goals", "goals", "What should it achieve?"),
+            text(
+                "Core Instructions",
+                "instructions",
+                "Detailed behavioral logic...",
+            ),
+            text("Guardrails", "guardrails", "What should it NEVER do?"),
+        ],
+    },
+    Step {
+        name: "Choose LLM",
+        key: "model",
+        fields: &[
+            text("Base Model", "base_model", "Claude 3.5, GPT-4o..."),
+            text("Context Window", "context_window", "128k, 200k..."),
+            text(
+                "Temperature / Params",
+                "parameters",
+                "Temp: 0.7, Top-P: 0.9",
+            ),
+            text(
+                "Cost/Latency Tradeoff",
+                "cost_latency_tradeoff",
+                "Focus on quality or speed?",
+            ),
+        ],
+    },
+    Step {
+        name: "Tools & Integrations",
+        key: "tools",
+        fields: &[
+            list(
+                "APIs & Connectors",
+                "apis",
+                "Stripe, Twilio, Slack (comma separated)",
+            ),
+            list(
+                "MCP Servers",
+                "mcp_servers",
+                "filesystem, github, brave-search",
+            ),
+            text(
+                "Custom Functions / Tools",
+                "custom_functions",
+                "Define specialized agent capabilities...",
+            ),
+        ],
+    },
+    Step {
+        name: "Memory Systems",
+        key: "memory",
+        fields: &[
+            flag("Episodic", "episodic"),
+            flag("Working", "working_memory"),
+            text(
+                "Vector Database",
+                "vector_db",
+                "Pinecone, Weaviate, Milvus...",
+            ),
+            text(
+                "SQL / Structured Storage",
+                "sql_db",
+                "PostgreSQL, MongoDB...",
+            ),
+            text("File Storage", "file_storage", "S3, GCS, local disk..."),
+        ],
+    },
+    Step {
+        name: "Orchestration",
+        key: "orchestration",
+        fields: &[
+            text(
+                "Workflow Patterns",
+                "workflow",
+                "Router, Sequential, Graph-based...",
+            ),
+            text(
+                "System Triggers",
+                "triggers",
+                "User input, Event-based, CRON...",
+            ),
+            text(
+                "Error Handling Logic",
+                "error_handling",
+                "How do we handle hallucinations or API failures?",
+            ),
+            text(
+                "Message Queues",
+                "message_queues",
+                "Kafka, RabbitMQ, SQS...",
+            ),
+        ],
+    },
+    Step {
+        name: "User Interface",
+        key: "interface",
+        fields: &[
+            text("Platform", "platform", "Web App, CLI, Slack Bot..."),
+            text(
+                "Interaction Mode",
+                "interaction_mode",
+                "Chat, Proactive, Voice...",
+            ),
+            text(
+                "API Endpoint Strategy",
+                "api_endpoint",
+                "REST, WebSocket, GraphQL...",
+            ),
+        ],
+    },
+    Step {
+        name: "Testing & Evals",
+        key: "testing",
+        fields: &[
+            list(
+                "Unit Test Scenarios",
+                "unit_tests",
+                "e.g. Identity check, Tool failure (comma separated)",
+            ),
+            text(
+                "Latency Testing",
+                "latency_testing",
+                "p95 under 2s, load test with 100 concurrent users...",
+            ),
+            text(
+                "Quality Metrics",
+                "quality_metrics",
+                "Accuracy, RAG retrieval score, CSAT...",
+            ),
+            text(
+                "Evaluation Strategy",
+                "evals",
+                "LLM-as-a-judge, Human review, Semantic comparison...",
+            ),
+        ],
+    },
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Screen {
+    Form,
+    Preview,
+}
+
+/// Wizard state, driven one key event at a time
+pub struct Wizard {
+    pub request: DesignRequest,
+    pub output: PathBuf,
+    pub step: usize,
+    pub field: usize,
+    pub screen: Screen,
+    /// Text of the focused field while it is being edited
+    pub buffer: String,
+    /// `(path, message)` validation errors for the whole request
+    pub errors: Vec<(String, String)>,
+    /// Rendered spec shown on the preview screen
+    pub preview: String,
+    pub scroll: u16,
+    pub status: Option<String>,
+    /// Answers changed since the last save
+    pub unsaved: bool,
+    /// Quit was pressed with unsaved answers; pressing it again quits
+    pub confirm_quit: bool,
+    pub quit: bool,
+}
+
+impl Wizard {
+    pub fn new(request: DesignRequest, output: PathBuf) -> Self {
+        let mut wizard = Wizard {
+            request,
+            output,
+            step: 0,
+            field: 0,
+            screen: Screen::Form,
+            buffer: String::new(),
+            errors: Vec::new(),
+            preview: String::new(),
+            scroll: 0,
+            status: None,
+            unsaved: false,
+            confirm_quit: false,
+            quit: false,
+        };
+        wizard.load_buffer();
+        wizard.revalidate();
+        wizard
+    }
+
+    pub fn current_step(&self) -> &'static Step {
+        &STEPS[self.step]
+    }
+
+    pub fn current_field(&self) -> &'static Field {
+        &self.current_step().fields[self.field]
+    }
+
+    /// Dotted request path of a field, e.g. `purpose.use_case`
+    pub fn path(step: &Step, field: &Field) -> String {
+        format!("{}.{}", step.key, field.key)
+    }
+
+    /// Validation errors for one field, including list items
+    pub fn errors_for(&self, step: &Step, field: &Field) -> Vec<&str> {
+        let path = Self::path(step, field);
+        self.errors
+            .iter()
+            .filter(|(p, _)| *p == path || p.starts_with(&format!("{}[", path)))
+            .map(|(_, message)| message.as_str())
+            .collect()
+    }
+
+    fn step_is_valid(&self) -> bool {
+        let step = self.current_step();
+        step.fields
+            .iter()
+            .all(|field| self.errors_for(step, field).is_empty())
+    }
+
+    /// Form text of a field as stored in the request
+    pub fn display_value(&self, step: &Step, field: &Field) -> String {
+        match self.value(step, field) {
+            Value::String(s) => s,
+            Value::Bool(b) => if b { "[x]" } else { "[ ]" }.to_string(),
+            Value::Array(items) => items
+                .iter()
+                .filter_map(Value::as_str)
+                .collect::<Vec<_>>()
+                .join(", "),
+            _ => String::new(),
+        }
+    }
+
+    fn value(&self, step: &Step, field: &Field) -> Value {
+        let request =
+            serde_json::to_value(&self.request).expect("DesignRequest serializes to JSON");
+        request[step.key][field.key].clone()
+    }
+
+    fn set_value(&mut self, value: Value) {
+        let step = self.current_step();
+        let field = self.current_field();
+        let mut request =
+            serde_json::to_value(&self.request).expect("DesignRequest serializes to JSON");
+        request[step.key][field.key] = value;
+        self.request =
+            serde_json::from_value(request).expect("form values match DesignRequest field types");
+        self.unsaved = true;
+        self.revalidate();
+    }
+
+    fn load_buffer(&mut self) {
+        self.buffer = match self.current_field().kind {
+            FieldKind::Flag => String::new(),
+            _ => self.display_value(self.current_step(), self.current_field()),
+        };
+    }
+
+    fn commit_buffer(&mut self) {
+        let value = match self.current_field().kind {
+            FieldKind::Text => Value::String(self.buffer.clone()),
+            FieldKind::List => Value::Array(
+                self.buffer
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|item| !item.is_empty())
+                    .map(|item| Value::String(item.to_string()))
+                    .collect(),
+            ),
+            FieldKind::Flag => return,
+        };
+        self.set_value(value);
+    }
+
+    fn revalidate(&mut self) {
+        self.errors = match self.request.validate() {
+            Ok(()) => Vec::new(),
+            Err(e) => field_errors(&e)
+                .into_iter()
+                .map(|(path, error)| (path, describe(error)))
+                .collect(),
+        };
+    }
+
+    fn focus(&mut self, step: usize, field: usize) {
+        self.step = step;
+        self.field = field;
+        self.load_buffer();
+    }
+
+    fn next_field(&mut self) {
+        if self.field + 1 < self.current_step().fields.len() {
+            self.focus(self.step, self.field + 1);
+        } else {
+            self.next_step();
+        }
+    }
+
+    fn previous_field(&mut self) {
+        if self.field > 0 {
+            self.focus(self.step, self.field - 1);
+        } else if self.step > 0 {
+            let step = self.step - 1;
+            self.focus(step, STEPS[step].fields.len() - 1);
+        }
+    }
+
+    /// Move on to the next step, or the preview after the last one; blocked
+    /// while the current step has validation errors
+    fn next_step(&mut self) {
+        if !self.step_is_valid() {
+            self.status = Some(format!(
+                "Fix the errors in {} first",
+                self.current_step().name
+            ));
+            return;
+        }
+        if self.step + 1 < STEPS.len() {
+            self.focus(self.step + 1, 0);
+        } else {
+            self.open_preview();
+        }
+    }
+
+    fn previous_step(&mut self) {
+        if self.step > 0 {
+            self.focus(self.step - 1, 0);
+        }
+    }
+
+    fn open_preview(&mut self) {
+        if let Some((path, message)) = self.errors.first() {
+            self.status = Some(format!("{}: {}", path, message));
+            return;
+        }
+        self.preview = core_generate_design(&self.request);
+        self.scroll = 0;
+        self.screen = Screen::Preview;
+        self.status = None;
+    }
+
+    /// Write the rendered spec to the output path
+    pub fn save(&mut self) -> Result<(), AppError> {
+        if let Some((path, message)) = self.errors.first() {
+            return Err(AppError::Validation(format!("{}: {}", path, message)));
+        }
+        let markdown = core_generate_design(&self.request);
+        fs::write(&self.output, markdown).map_err(|e| {
+            AppError::Internal(anyhow::anyhow!(
+                "Cannot write {}: {}",
+                self.output.display(),
+                e
+            ))
+        })?;
+        self.unsaved = false;
+        self.status = Some(format!("Saved to {}", self.output.display()));
+        Ok(())
+    }
+
+    /// Quit right away when everything is saved, otherwise only once the
+    /// user confirmed by pressing the quit key twice
+    fn request_quit(&mut self, confirmed: bool) {
+        if !self.unsaved || confirmed {
+            self.quit = true;
+            return;
+        }
+        self.confirm_quit = true;
+        self.status =
+            Some("Unsaved answers will be lost. Press again to quit, or save first".to_string());
+    }
+
+    fn save_and_report(&mut self) {
+        if let Err(e) = self.save() {
+            self.status = Some(e.to_string());
+        }
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        // Any other key cancels a pending quit
+        let confirmed = std::mem::take(&mut self.confirm_quit);
+        match (key.code, ctrl) {
+            (KeyCode::Char('c'), true) => {
+                self.quit = true;
+                return;
+            }
+            (KeyCode::Char('s'), true) => {
+                self.save_and_report();
+                return;
+            }
+            _ => {}
+        }
+
+        match self.screen {
+            Screen::Form => self.handle_form_key(key, ctrl, confirmed),
+            Screen::Preview => self.handle_preview_key(key, confirmed),
+        }
+    }
+
+    fn handle_form_key(&mut self, key: KeyEvent, ctrl: bool, confirmed: bool) {
+        self.status = None;
+        match key.code {
+            KeyCode::Tab | KeyCode::Down | KeyCode::Enter => self.next_field(),
+            KeyCode::BackTab | KeyCode::Up => self.previous_field(),
+            KeyCode::PageDown => self.next_step(),
+            KeyCode::PageUp => self.previous_step(),
+            KeyCode::Char('p') if ctrl => self.open_preview(),
+            KeyCode::Esc => self.request_quit(confirmed),
+            KeyCode::Char(' ') if self.current_field().kind == FieldKind::Flag => {
+                let value = self.value(self.current_step(), self.current_field());
+                self.set_value(Value::Bool(!value.as_bool().unwrap_or(false)));
+            }
+            KeyCode::Char(c) if !ctrl && self.current_field().kind != FieldKind::Flag => {
+                self.buffer.push(c);
+                self.commit_buffer();
+            }
+            KeyCode::Backspace if self.current_field().kind != FieldKind::Flag => {
+                self.buffer.pop();
+                self.commit_buffer();
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_preview_key(&mut self, key: KeyEvent, confirmed: bool) {
+        match key.code {
+            KeyCode::Down | KeyCode::Char('j') => self.scroll = self.scroll.saturating_add(1),
+            KeyCode::Up | KeyCode::Char('k') => self.scroll = self.scroll.saturating_sub(1),
+            KeyCode::PageDown => self.scroll = self.scroll.saturating_add(20),
+            KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(20),
+            KeyCode::Char('s') => self.save_and_report(),
+            KeyCode::Char('q') => self.request_quit(confirmed),
+            KeyCode::Esc | KeyCode::Char('b') => {
+                self.screen = Screen::Form;
+                self.status = None;
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Message shown under a field, spelling out length limits
+fn describe(error: &ValidationError) -> String {
+    if let Some(message) = &error.message {
+        return message.to_string();
+    }
+    match (error.code.as_ref(), error.params.get("max")) {
+        ("length", Some(max)) => format!("length must be at most {}", max),
+        (code, _) => format!("invalid ({})", code),
+    }
+}
@@ -0,0 +1,103 @@
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::Json;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use tracing::info;
+use validator::{Validate, ValidationError};
+
+use crate::error::{AppError, AppResult, ErrorResponse};
+use crate::extractors::ValidatedJson;
+use crate::migrate::Upgrade;
+use crate::state::AppState;
+use crate::store::{Workspace, DEFAULT_WORKSPACE};
+
+#[derive(Deserialize, Debug, Validate, JsonSchema)]
+pub struct CreateWorkspace {
+    /// Lowercase letters, digits and dashes; also used as a directory name
+    /// for workspace templates
+    #[validate(length(min = 1, max = 64), custom(function = "validate_slug"))]
+    pub id: String,
+    #[validate(length(min = 1, max = 200))]
+    pub name: String,
+}
+
+impl Upgrade for CreateWorkspace {}
+
+fn validate_slug(id: &str) -> Result<(), ValidationError> {
+    let valid = id
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+    if valid && !id.starts_with('-') {
+        Ok(())
+    } else {
+        Err(ValidationError::new("slug")
+            .with_message("must contain only lowercase letters, digits and dashes".into()))
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/admin/workspaces",
+    tag = "workspaces",
+    request_body = CreateWorkspace,
+    responses(
+        (status = 201, description = "Workspace created", body = Workspace),
+        (status = 409, description = "The workspace already exists", body = ErrorResponse),
+        (status = 422, description = "Invalid ID or name", body = ErrorResponse),
+    )
+)]
+pub async fn create_workspace(
+    State(state): State<AppState>,
+    ValidatedJson(payload): ValidatedJson<CreateWorkspace>,
+) -> AppResult<(StatusCode, Json<Workspace>)> {
+    let workspace = state
+        .store
+        .create_workspace(&payload.id, payload.name.trim())
+        .await?
+        .ok_or_else(|| AppError::Conflict(format!("Workspace {} already exists", payload.id)))?;
+
+    info!(workspace = %workspace.id, "Workspace created");
+    Ok((StatusCode::CREATED, Json(workspace)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/admin/workspaces",
+    tag = "workspaces",
+    responses((status = 200, description = "All workspaces", body = Vec<Workspace>))
+)]
+pub async fn list_workspaces(State(state): State<AppState>) -> AppResult<Json<Vec<Workspace>>> {
+    Ok(Json(state.store.list_workspaces().await?))
+}
+
+#[utoipa::path(
+    post,
+    path = "/admin/workspaces/{id}/archive",
+    tag = "workspaces",
+    params(("id" = String, Path, description = "Workspace ID")),
+    responses(
+        (status = 200, description = "Workspace is now read-only", body = Workspace),
+        (status = 400, description = "The default workspace cannot be archived", body = ErrorResponse),
+        (status = 404, description = "No such workspace", body = ErrorResponse),
+    )
+)]
+pub async fn archive_workspace(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> AppResult<Json<Workspace>> {
+    if id == DEFAULT_WORKSPACE {
+        return Err(AppError::BadRequest(
+            "The default workspace cannot be archived".to_string(),
+        ));
+    }
+
+    let workspace = state
+        .store
+        .archive_workspace(&id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Workspace {} not found", id)))?;
+
+    info!(workspace = %workspace.id, "Workspace archived");
+    Ok(Json(workspace))
+}
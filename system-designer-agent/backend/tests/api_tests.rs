@@ -252,3 +252,69 @@ async fn test_boolean_rendering_logic() {
     // Verify that "false" is rendered in the markdown text
     assert!(markdown.contains("- **Episodic Memory:** false"));
 }
+
+/// Send `payload` to `/generate` and return the status and JSON body
+async fn post_generate(payload: serde_json::Value) -> (StatusCode, serde_json::Value) {
+    let response = app()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/generate")
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_vec(&payload).unwrap()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    let status = response.status();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    (status, serde_json::from_slice(&body).unwrap())
+}
+
+fn frontend_payload() -> serde_json::Value {
+    json!({
+        "purpose": { "use_case": "Docs Bot", "user_needs": "", "success_criteria": "", "constraints": "" },
+        "prompt": { "goals": "", "role": "", "instructions": "", "guardrails": "" },
+        "model": { "base_model": "", "parameters": "", "context_window": "", "cost_latency_tradeoff": "" },
+        "tools": { "apis": [], "mcp_servers": [], "custom_functions": "" },
+        "memory": { "episodic": false, "working_memory": true, "vector_db": "", "sql_db": "", "file_storage": "S3 bucket" },
+        "orchestration": { "workflow": "", "triggers": "", "error_handling": "", "message_queues": "Kafka" },
+        "interface": { "platform": "", "interaction_mode": "", "api_endpoint": "" },
+        "testing": { "unit_tests": [], "latency_testing": "p95 under 2s", "quality_metrics": "", "evals": "" }
+    })
+}
+
+#[tokio::test]
+async fn test_frontend_fields_are_rendered() {
+    let (status, body) = post_generate(frontend_payload()).await;
+
+    assert_eq!(status, StatusCode::OK);
+    let markdown = body["markdown"].as_str().unwrap();
+    assert!(markdown.contains("- **File Storage:** S3 bucket"));
+    assert!(markdown.contains("- **Message Queues:** Kafka"));
+    assert!(markdown.contains("- **Latency Testing:** p95 under 2s"));
+}
+
+#[tokio::test]
+async fn test_unknown_fields_are_rejected() {
+    let mut payload = frontend_payload();
+    payload["memory"]["graph_db"] = json!("Neo4j");
+
+    let (status, body) = post_generate(payload).await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let error = body["error"].as_str().unwrap();
+    assert!(error.contains("unknown field `graph_db`"), "{}", error);
+}
+
+#[tokio::test]
+async fn test_new_fields_are_validated() {
+    let mut payload = frontend_payload();
+    payload["testing"]["latency_testing"] = json!("x".repeat(1001));
+
+    let (status, body) = post_generate(payload).await;
+
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(body["code"], "VALIDATION_ERROR");
+}
@@ -0,0 +1,245 @@
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+    Router,
+};
+use backend::app_with_config;
+use backend::config::Config;
+use backend::middleware::auth::{hash_api_key, AuthConfig, Authenticator, Scope};
+use http_body_util::BodyExt;
+use jsonwebtoken::{encode, EncodingKey, Header};
+use serde_json::{json, Value};
+use tower::ServiceExt;
+
+const HMAC_SECRET: &[u8] = b"test-hmac-secret-for-system-designer";
+
+fn fixtures() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/auth")
+}
+
+fn auth_toml() -> String {
+    format!(
+        r#"
+[[api_keys]]
+name = "reader"
+hash = "{}"
+scopes = ["designs:read"]
+
+[[api_keys]]
+name = "ops"
+hash = "{}"
+scopes = ["admin"]
+
+[jwt]
+jwks = "{}"
+issuer = "https://auth.example.com"
+"#,
+        hash_api_key("reader-key"),
+        hash_api_key("ops-key"),
+        fixtures().join("jwks.json").display()
+    )
+}
+
+fn secured_app(dir: &tempfile::TempDir) -> Router {
+    let path = dir.path().join("auth.toml");
+    std::fs::write(&path, auth_toml()).unwrap();
+    let config = Config {
+        auth_config: Some(path),
+        ..Config::default_test()
+    };
+    app_with_config(&config).unwrap()
+}
+
+fn token(header: Header, key: &EncodingKey, scope: &str, issuer: &str) -> String {
+    let exp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+        + 300;
+    let claims = json!({ "sub": "alice", "iss": issuer, "exp": exp, "scope": scope });
+    encode(&header, &claims, key).unwrap()
+}
+
+fn hs256(scope: &str) -> String {
+    let mut header = Header::new(jsonwebtoken::Algorithm::HS256);
+    header.kid = Some("hs-1".to_string());
+    token(
+        header,
+        &EncodingKey::from_secret(HMAC_SECRET),
+        scope,
+        "https://auth.example.com",
+    )
+}
+
+fn rs256(scope: &str, issuer: &str) -> String {
+    let pem = std::fs::read(fixtures().join("rsa_private.pem")).unwrap();
+    let mut header = Header::new(jsonwebtoken::Algorithm::RS256);
+    header.kid = Some("rsa-1".to_string());
+    token(
+        header,
+        &EncodingKey::from_rsa_pem(&pem).unwrap(),
+        scope,
+        issuer,
+    )
+}
+
+async fn send(
+    app: &Router,
+    method: &str,
+    uri: &str,
+    auth: Option<(&str, String)>,
+) -> (StatusCode, Value) {
+    let mut request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("content-type", "application/json");
+    if let Some((name, value)) = auth {
+        request = request.header(name, value);
+    }
+    let response = app
+        .clone()
+        .oneshot(request.body(Body::from("{}")).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
+
+#[tokio::test]
+async fn test_missing_credentials_are_rejected() {
+    let dir = tempfile::tempdir().unwrap();
+    let app = secured_app(&dir);
+
+    let (status, body) = send(&app, "GET", "/templates", None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(body["code"], "UNAUTHORIZED");
+
+    let (status, _) = send(
+        &app,
+        "GET",
+        "/templates",
+        Some(("x-api-key", "wrong".into())),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    // Health checks stay public
+    let (status, _) = send(&app, "GET", "/health", None).await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_api_key_scopes() {
+    let dir = tempfile::tempdir().unwrap();
+    let app = secured_app(&dir);
+
+    let reader = || Some(("x-api-key", "reader-key".to_string()));
+    let (status, _) = send(&app, "GET", "/designs", reader()).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, body) = send(&app, "DELETE", "/designs/abc", reader()).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["code"], "FORBIDDEN");
+    assert_eq!(body["error"], "Missing scope designs:write");
+
+    // Admin implies every scope; API keys are also accepted as bearer tokens
+    let (status, _) = send(
+        &app,
+        "DELETE",
+        "/designs/abc",
+        Some(("authorization", "Bearer ops-key".to_string())),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_jwt_hs256_and_rs256() {
+    let dir = tempfile::tempdir().unwrap();
+    let app = secured_app(&dir);
+    let bearer = |token: String| Some(("authorization", format!("Bearer {}", token)));
+
+    let (status, _) = send(&app, "GET", "/designs", bearer(hs256("designs:read"))).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, _) = send(
+        &app,
+        "DELETE",
+        "/designs/abc",
+        bearer(rs256(
+            "designs:read designs:write",
+            "https://auth.example.com",
+        )),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, _) = send(
+        &app,
+        "DELETE",
+        "/designs/abc",
+        bearer(hs256("designs:read")),
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_invalid_tokens_are_rejected() {
+    let dir = tempfile::tempdir().unwrap();
+    let app = secured_app(&dir);
+    let bearer = |token: String| Some(("authorization", format!("Bearer {}", token)));
+
+    // Wrong issuer
+    let (status, _) = send(
+        &app,
+        "GET",
+        "/designs",
+        bearer(rs256("designs:read", "https://evil.example.com")),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    // Signed with a key that is not in the JWKS
+    let mut header = Header::new(jsonwebtoken::Algorithm::HS256);
+    header.kid = Some("hs-1".to_string());
+    let forged = token(
+        header,
+        &EncodingKey::from_secret(b"guessed"),
+        "admin",
+        "https://auth.example.com",
+    );
+    let (status, _) = send(&app, "GET", "/designs", bearer(forged)).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    // Tampered payload
+    let mut parts: Vec<String> = hs256("designs:read").split('.').map(String::from).collect();
+    parts[1].push('A');
+    let (status, _) = send(&app, "GET", "/designs", bearer(parts.join("."))).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+#[test]
+fn test_auth_config_rejects_plaintext_keys() {
+    let err = AuthConfig::from_toml(
+        r#"
+[[api_keys]]
+name = "ci"
+hash = "not-a-hash"
+"#,
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("API key ci"));
+
+    let config = AuthConfig::from_toml(&auth_toml()).unwrap();
+    let auth = Authenticator::new(&config, &fixtures()).unwrap();
+    let mut headers = axum::http::HeaderMap::new();
+    headers.insert("x-api-key", "ops-key".parse().unwrap());
+    let principal = auth.authenticate(&headers).unwrap();
+    assert_eq!(principal.subject, "ops");
+    assert!(principal.has_scope(Scope::DesignsWrite));
+}
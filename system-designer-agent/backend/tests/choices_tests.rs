@@ -0,0 +1,160 @@
+mod common;
+
+use backend::choices::{BaseModel, InteractionMode, Platform, WorkflowPattern};
+use backend::diagram::mermaid_flowchart;
+use backend::import::parse_design_markdown;
+use backend::lint::Linter;
+use backend::{core_generate_design, validate_design_request, DesignRequest};
+use serde_json::{json, Value};
+
+/// The shared sample with its choices spelled as aliases
+fn sample_payload() -> Value {
+    let mut payload = common::sample_payload();
+    payload["model"]["base_model"] = json!("claude-3-5-sonnet");
+    payload["orchestration"]["workflow"] = json!("plan and execute");
+    payload["interface"]["platform"] = json!("slack bot");
+    payload
+}
+
+fn sample_request() -> DesignRequest {
+    serde_json::from_value(sample_payload()).unwrap()
+}
+
+#[test]
+fn test_common_spellings_map_to_known_values() {
+    for spelling in ["ReAct", "react", "Re-Act", "REACT"] {
+        assert_eq!(WorkflowPattern::from(spelling), WorkflowPattern::ReAct);
+    }
+    assert_eq!(
+        WorkflowPattern::from("Plan & Execute"),
+        WorkflowPattern::PlanAndExecute
+    );
+    assert_eq!(BaseModel::from("gpt-4o"), BaseModel::Gpt4o);
+    assert_eq!(BaseModel::from("GPT-4"), BaseModel::Gpt4);
+    assert_eq!(Platform::from("Web App"), Platform::Web);
+    assert_eq!(Platform::from("command line"), Platform::Cli);
+    assert_eq!(InteractionMode::from("voice"), InteractionMode::Voice);
+
+    let request = sample_request();
+    assert_eq!(request.model.base_model, BaseModel::ClaudeSonnet);
+    assert_eq!(request.interface.platform, Platform::Slack);
+
+    // Known values serialize under their canonical label
+    let value = serde_json::to_value(&request).unwrap();
+    assert_eq!(value["model"]["base_model"], json!("Claude 3.5 Sonnet"));
+    assert_eq!(
+        value["orchestration"]["workflow"],
+        json!("Plan-and-Execute")
+    );
+}
+
+#[test]
+fn test_other_values_are_kept_verbatim() {
+    let mut payload = sample_payload();
+    payload["model"]["base_model"] = json!("in-house 7B ");
+    payload["orchestration"]["workflow"] = json!("");
+    let request: DesignRequest = serde_json::from_value(payload).unwrap();
+
+    assert_eq!(
+        request.model.base_model,
+        BaseModel::Other("in-house 7B ".to_string())
+    );
+    assert!(request.model.base_model.is_custom());
+    assert!(request.orchestration.workflow.is_blank());
+    assert!(!request.orchestration.workflow.is_custom());
+
+    let value = serde_json::to_value(&request).unwrap();
+    assert_eq!(value["model"]["base_model"], json!("in-house 7B "));
+    assert_eq!(value["orchestration"]["workflow"], json!(""));
+}
+
+#[test]
+fn test_length_limits_apply_to_free_form_text() {
+    let mut request = sample_request();
+    request.interface.platform = Platform::Other("x".repeat(101));
+
+    assert!(validate_design_request(&request).is_err());
+    assert_eq!(common::invalid_fields(&request), ["interface.platform"]);
+}
+
+#[test]
+fn test_known_values_are_described_and_skipped_on_import() {
+    let request = sample_request();
+    let markdown = core_generate_design(&request);
+
+    assert!(markdown.contains(&format!(
+        "- **Model Overview:** {}\n",
+        BaseModel::ClaudeSonnet.description().unwrap()
+    )));
+    assert!(markdown.contains(&format!(
+        "- **Pattern Overview:** {}\n",
+        WorkflowPattern::PlanAndExecute.description().unwrap()
+    )));
+    assert!(markdown.contains("- **Platform Overview:** A Slack app"));
+    assert!(markdown.contains("- **Interaction Overview:** Turn-based"));
+
+    let report = parse_design_markdown(&markdown).unwrap();
+    assert!(report.issues.is_empty(), "{:?}", report.issues);
+    assert_eq!(report.request, request);
+}
+
+#[test]
+fn test_free_form_values_have_no_description() {
+    let mut request = sample_request();
+    request.model.base_model = BaseModel::from("in-house 7B");
+    request.interface.interaction_mode = InteractionMode::default();
+
+    let markdown = core_generate_design(&request);
+    assert!(markdown.contains("- **Base Model:** in-house 7B\n- **Parameters:**"));
+    assert!(markdown.contains("- **Interaction Mode:** \n- **API Strategy:**"));
+    assert!(!markdown.contains("Model Overview"));
+    assert!(!markdown.contains("Interaction Overview"));
+}
+
+#[test]
+fn test_unrecognized_workflow_is_reported() {
+    let mut request = sample_request();
+
+    request.orchestration.workflow = WorkflowPattern::from("Hierarchical agents");
+    assert!(Linter::new()
+        .lint(&request)
+        .iter()
+        .all(|f| f.rule_id != "unrecognized-workflow"));
+    assert!(mermaid_flowchart(&request).contains("orchestrator -->|\"delegate\"| workers"));
+
+    request.orchestration.workflow = WorkflowPattern::from("Graph-based");
+    let findings = Linter::new().lint(&request);
+    let finding = findings
+        .iter()
+        .find(|f| f.rule_id == "unrecognized-workflow")
+        .unwrap();
+    assert_eq!(finding.path, "orchestration.workflow");
+    assert!(finding.message.contains("\"Graph-based\""));
+    assert!(mermaid_flowchart(&request).contains("orchestrator --> api_0"));
+}
+
+#[test]
+fn test_workflow_keywords_match_whole_words() {
+    let pattern = |text: &str| WorkflowPattern::from(text).pattern();
+
+    assert_eq!(pattern("Reactive event handling"), None);
+    assert_eq!(pattern("Reactor loop"), None);
+    assert_eq!(pattern("Blockchain indexer"), None);
+    assert_eq!(
+        pattern("ReAct loop with tools"),
+        Some(WorkflowPattern::ReAct)
+    );
+    assert_eq!(pattern("Re-Act agent"), Some(WorkflowPattern::ReAct));
+    assert_eq!(
+        pattern("Prompt chaining over documents"),
+        Some(WorkflowPattern::Sequential)
+    );
+    assert_eq!(
+        pattern("Hierarchical multi-agent crew"),
+        Some(WorkflowPattern::SupervisorWorker)
+    );
+    assert_eq!(
+        pattern("plan and execute, then review"),
+        Some(WorkflowPattern::PlanAndExecute)
+    );
+}
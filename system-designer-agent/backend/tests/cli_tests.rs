@@ -0,0 +1,109 @@
+mod common;
+
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+use backend::cli::{parse_request, InputFormat};
+use common::sample_payload;
+use serde_json::{json, Value};
+
+const BIN: &str = env!("CARGO_BIN_EXE_system-designer");
+
+fn run(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(BIN)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn test_generate_markdown_from_json_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let input = dir.path().join("request.json");
+    std::fs::write(&input, sample_payload().to_string()).unwrap();
+
+    let output = run(&["generate", input.to_str().unwrap()], "");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.starts_with("# System Design Specification: Support Bot"));
+}
+
+#[test]
+fn test_generate_json_spec_from_yaml_stdin_to_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let output_path = dir.path().join("spec.json");
+    let yaml = serde_yaml::to_string(&sample_payload()).unwrap();
+
+    let output = run(
+        &[
+            "generate",
+            "--format",
+            "json",
+            "--output",
+            output_path.to_str().unwrap(),
+        ],
+        &yaml,
+    );
+
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+    let spec: Value =
+        serde_json::from_str(&std::fs::read_to_string(&output_path).unwrap()).unwrap();
+    assert_eq!(spec["metadata"]["title"], "Support Bot");
+    assert_eq!(spec["model"]["base_model"], "GPT-4");
+}
+
+#[test]
+fn test_validate_reports_success() {
+    let output = run(&["validate", "-"], &sample_payload().to_string());
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "Support Bot is valid\n"
+    );
+}
+
+#[test]
+fn test_exit_codes_follow_error_kind() {
+    let mut too_long = sample_payload();
+    too_long["purpose"]["use_case"] = json!("x".repeat(501));
+    let output = run(&["validate"], &too_long.to_string());
+    assert_eq!(output.status.code(), Some(65));
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("Validation failed"));
+
+    let output = run(&["validate"], "{ not json");
+    assert_eq!(output.status.code(), Some(64));
+
+    let output = run(&["generate", "/nonexistent/request.json"], "");
+    assert_eq!(output.status.code(), Some(66));
+
+    let output = run(
+        &["generate", "--format", "pdf"],
+        &sample_payload().to_string(),
+    );
+    assert_eq!(output.status.code(), Some(64));
+}
+
+#[test]
+fn test_parse_request_formats_agree() {
+    let payload = sample_payload();
+    let yaml = serde_yaml::to_string(&payload).unwrap();
+
+    let from_json = parse_request(&payload.to_string(), InputFormat::Json).unwrap();
+    let from_yaml = parse_request(&yaml, InputFormat::Yaml).unwrap();
+    assert_eq!(from_json, from_yaml);
+}
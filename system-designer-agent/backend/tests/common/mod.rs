@@ -0,0 +1,45 @@
+//! Fixtures shared by the integration tests
+#![allow(dead_code)]
+
+use backend::extractors::field_errors;
+use backend::DesignRequest;
+use serde_json::{json, Value};
+use validator::Validate;
+
+/// A complete, valid design for a customer support bot. Tests override the
+/// fields they exercise.
+pub fn sample_payload() -> Value {
+    json!({
+        "purpose": { "use_case": "Support Bot", "user_needs": "Answers", "success_criteria": "CSAT", "constraints": "None" },
+        "prompt": { "goals": "Help", "role": "Agent", "instructions": "Be kind", "guardrails": "No PII" },
+        "model": { "base_model": "GPT-4", "parameters": "T=0.2", "context_window": "128k", "cost_latency_tradeoff": "Balanced" },
+        "tools": { "apis": ["Zendesk"], "mcp_servers": [], "custom_functions": "" },
+        "memory": { "episodic": false, "working_memory": true, "vector_db": "", "sql_db": "" },
+        "orchestration": { "workflow": "ReAct", "triggers": "Ticket", "error_handling": "Retry" },
+        "interface": { "platform": "Web", "interaction_mode": "Chat", "api_endpoint": "REST" },
+        "testing": { "unit_tests": [], "quality_metrics": "Accuracy", "evals": "Golden set" }
+    })
+}
+
+/// [`sample_payload`] for a different use case, e.g. to tell stored designs apart
+pub fn payload_for(use_case: &str) -> Value {
+    let mut payload = sample_payload();
+    payload["purpose"]["use_case"] = json!(use_case);
+    payload
+}
+
+pub fn sample_request() -> DesignRequest {
+    serde_json::from_value(sample_payload()).unwrap()
+}
+
+pub fn request_for(use_case: &str) -> DesignRequest {
+    serde_json::from_value(payload_for(use_case)).unwrap()
+}
+
+/// Dotted paths of every field that fails validation, e.g. `purpose.use_case`
+pub fn invalid_fields(request: &DesignRequest) -> Vec<String> {
+    match request.validate() {
+        Ok(()) => Vec::new(),
+        Err(e) => field_errors(&e).into_iter().map(|(path, _)| path).collect(),
+    }
+}
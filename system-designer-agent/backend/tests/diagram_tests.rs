@@ -0,0 +1,108 @@
+mod common;
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use backend::diagram::mermaid_flowchart;
+use backend::import::parse_design_markdown;
+use backend::{app, core_generate_design, DesignRequest};
+use http_body_util::BodyExt;
+use serde_json::{json, Value};
+use tower::ServiceExt;
+
+/// The shared sample with every component the diagram can draw
+fn sample_payload(workflow: &str) -> Value {
+    let mut payload = common::sample_payload();
+    payload["tools"]["mcp_servers"] = json!(["github"]);
+    payload["memory"] = json!({ "episodic": true, "working_memory": false, "vector_db": "Qdrant", "sql_db": "Postgres" });
+    payload["orchestration"]["workflow"] = json!(workflow);
+    payload["interface"]["platform"] = json!("Slack");
+    payload
+}
+
+fn flowchart(workflow: &str) -> String {
+    let request: DesignRequest = serde_json::from_value(sample_payload(workflow)).unwrap();
+    mermaid_flowchart(&request)
+}
+
+#[test]
+fn test_flowchart_contains_every_component() {
+    let chart = flowchart("ReAct");
+
+    assert!(chart.starts_with("flowchart LR\n"));
+    for node in [
+        "ui[\"Interface: Slack (Chat)\"]",
+        "orchestrator{{\"Orchestrator: ReAct\"}}",
+        "model[[\"Model: GPT-4\"]]",
+        "api_0[\"API: Zendesk\"]",
+        "mcp_0[\"MCP: github\"]",
+        "vector_db[(\"Vector DB: Qdrant\")]",
+        "sql_db[(\"SQL DB: Postgres\")]",
+    ] {
+        assert!(chart.contains(node), "missing {} in\n{}", node, chart);
+    }
+    assert!(chart.contains("orchestrator <-->|\"reason / act\"| model"));
+    assert!(chart.contains("orchestrator <-->|\"episodic memory\"| vector_db"));
+}
+
+#[test]
+fn test_edges_follow_workflow_pattern() {
+    let sequential = flowchart("Sequential pipeline");
+    assert!(sequential.contains("model --> api_0"));
+    assert!(sequential.contains("api_0 --> mcp_0"));
+    assert!(sequential.contains("mcp_0 -->|\"output\"| orchestrator"));
+
+    let plan = flowchart("Plan-and-Execute");
+    assert!(plan.contains("model -->|\"steps\"| executor"));
+    assert!(plan.contains("executor --> api_0"));
+
+    let router = flowchart("Router");
+    assert!(router.contains("orchestrator -.->|\"route\"| mcp_0"));
+
+    let supervisor = flowchart("Supervisor-Worker");
+    assert!(supervisor.contains("orchestrator -->|\"delegate\"| workers"));
+}
+
+#[test]
+fn test_labels_are_escaped() {
+    let mut payload = sample_payload("ReAct");
+    payload["tools"]["apis"] = json!(["Say \"hi\""]);
+    let request: DesignRequest = serde_json::from_value(payload).unwrap();
+
+    assert!(mermaid_flowchart(&request).contains("api_0[\"API: Say #quot;hi#quot;\"]"));
+}
+
+#[test]
+fn test_diagram_embedded_in_markdown_and_skipped_on_import() {
+    let request: DesignRequest = serde_json::from_value(sample_payload("ReAct")).unwrap();
+
+    let markdown = core_generate_design(&request);
+    assert!(markdown.contains("## 10. Architecture Diagram\n```mermaid\nflowchart LR\n"));
+
+    let report = parse_design_markdown(&markdown).unwrap();
+    assert!(report.issues.is_empty());
+    assert_eq!(report.request, request);
+}
+
+#[tokio::test]
+async fn test_diagram_endpoint() {
+    let response = app()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/diagram")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    serde_json::to_vec(&sample_payload("Router")).unwrap(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body_json: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body_json["mermaid"].as_str().unwrap(), flowchart("Router"));
+}
@@ -0,0 +1,127 @@
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use backend::import::parse_design_markdown;
+use backend::{app, core_generate_design, DesignRequest};
+use http_body_util::BodyExt;
+use serde_json::{json, Value};
+use tower::ServiceExt;
+
+fn sample_request() -> DesignRequest {
+    serde_json::from_value(json!({
+        "purpose": { "use_case": "Support Bot", "user_needs": "Fast answers\nin two languages", "success_criteria": "CSAT > 4", "constraints": "EU only" },
+        "prompt": { "goals": "Help", "role": "Agent", "instructions": "Step 1: greet\n\nStep 2: solve", "guardrails": "No PII" },
+        "model": { "base_model": "GPT-4", "parameters": "T=0.2", "context_window": "128k", "cost_latency_tradeoff": "Balanced" },
+        "tools": { "apis": ["Zendesk", "Say \"hi\", then \\leave", "Café\tBar"], "mcp_servers": ["github"], "custom_functions": "lookup_order" },
+        "memory": { "episodic": true, "working_memory": false, "vector_db": "Qdrant", "sql_db": "Postgres", "file_storage": "S3" },
+        "orchestration": { "workflow": "ReAct", "triggers": "Ticket created", "error_handling": "Retry", "message_queues": "SQS" },
+        "interface": { "platform": "Web", "interaction_mode": "Chat", "api_endpoint": "REST" },
+        "testing": { "unit_tests": [], "latency_testing": "p95 < 2s", "quality_metrics": "Accuracy", "evals": "Golden set" }
+    }))
+    .unwrap()
+}
+
+#[test]
+fn test_round_trip_generated_markdown() {
+    let request = sample_request();
+
+    let report = parse_design_markdown(&core_generate_design(&request)).unwrap();
+
+    assert_eq!(report.request, request);
+    assert!(report.issues.is_empty());
+}
+
+#[test]
+fn test_round_trip_keeps_headings_and_rules_inside_fields() {
+    let mut request = sample_request();
+    request.prompt.instructions =
+        "# Tone\nFriendly\n---\n# Escalation\nHand off to a human".to_string();
+    request.testing.evals = "Golden set\n---".to_string();
+
+    let report = parse_design_markdown(&core_generate_design(&request)).unwrap();
+
+    assert_eq!(report.request, request);
+    assert!(report.issues.is_empty());
+}
+
+#[test]
+fn test_reports_unparsed_sections_and_fields() {
+    let markdown = "# System Design Specification: Edited\n\n\
+        ## 1. Purpose & Scope\n\
+        - **Use Case:** Edited\n\
+        - **Budget:** 10k\n\n\
+        ## 4. Tools & Integrations\n\
+        - **External APIs:** Stripe, Twilio\n\
+        - **MCP Servers:** [\"unterminated]\n\n\
+        ## 9. Company Compliance\n\
+        - **Owner:** Platform team\n";
+
+    let report = parse_design_markdown(markdown).unwrap();
+
+    assert_eq!(report.request.purpose.use_case, "Edited");
+    assert_eq!(report.request.tools.apis, vec!["Stripe", "Twilio"]);
+    assert!(report.request.tools.mcp_servers.is_empty());
+
+    let described: Vec<(&str, Option<&str>)> = report
+        .issues
+        .iter()
+        .map(|i| (i.section.as_str(), i.field.as_deref()))
+        .collect();
+    assert_eq!(
+        described,
+        vec![
+            ("Purpose & Scope", Some("Budget")),
+            ("Tools & Integrations", Some("MCP Servers")),
+            ("Company Compliance", None),
+        ]
+    );
+}
+
+#[test]
+fn test_rejects_document_without_sections() {
+    assert!(parse_design_markdown("just some notes").is_err());
+}
+
+#[tokio::test]
+async fn test_import_endpoint() {
+    let markdown = core_generate_design(&sample_request());
+
+    let response = app()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/import")
+                .header("content-type", "text/markdown")
+                .body(Body::from(markdown))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body_json: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body_json["request"]["purpose"]["use_case"], "Support Bot");
+    assert_eq!(
+        body_json["request"]["tools"]["mcp_servers"],
+        json!(["github"])
+    );
+    assert_eq!(body_json["issues"], json!([]));
+}
+
+#[tokio::test]
+async fn test_import_endpoint_rejects_plain_text() {
+    let response = app()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/import")
+                .body(Body::from("not a design"))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
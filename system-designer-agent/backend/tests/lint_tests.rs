@@ -0,0 +1,143 @@
+mod common;
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use backend::app;
+use backend::config::Config;
+use backend::lint::{LintConfig, Linter, Severity};
+use backend::{core_generate_design, DesignRequest};
+use http_body_util::BodyExt;
+use serde_json::{json, Value};
+use tower::ServiceExt;
+
+/// The shared sample with episodic memory but no store, and MCP servers but
+/// no guardrails
+fn sample_payload() -> Value {
+    let mut payload = common::sample_payload();
+    payload["prompt"]["guardrails"] = json!("");
+    payload["tools"]["mcp_servers"] = json!(["github"]);
+    payload["memory"]["episodic"] = json!(true);
+    payload
+}
+
+fn sample_request() -> DesignRequest {
+    serde_json::from_value(sample_payload()).unwrap()
+}
+
+fn rule_ids(findings: &[backend::lint::Finding]) -> Vec<&str> {
+    findings.iter().map(|f| f.rule_id.as_str()).collect()
+}
+
+#[test]
+fn test_cross_field_rules() {
+    let findings = Linter::new().lint(&sample_request());
+
+    assert_eq!(
+        rule_ids(&findings),
+        vec!["episodic-memory-without-store", "mcp-without-guardrails"]
+    );
+    assert_eq!(findings[0].severity, Severity::Warning);
+    assert_eq!(findings[0].path, "memory.episodic");
+    assert_eq!(findings[1].path, "prompt.guardrails");
+}
+
+#[test]
+fn test_clean_design_has_no_findings() {
+    let mut request = sample_request();
+    request.memory.vector_db = "Qdrant".to_string();
+    request.prompt.guardrails = "Read-only GitHub access".to_string();
+
+    assert!(Linter::new().lint(&request).is_empty());
+}
+
+#[test]
+fn test_config_overrides_severity_and_disables_rules() {
+    let config = LintConfig::from_toml(
+        r#"
+        [rules.mcp-without-guardrails]
+        severity = "error"
+
+        [rules.episodic-memory-without-store]
+        enabled = false
+        "#,
+    )
+    .unwrap();
+
+    let findings = Linter::with_config(&config).lint(&sample_request());
+
+    assert_eq!(rule_ids(&findings), vec!["mcp-without-guardrails"]);
+    assert_eq!(findings[0].severity, Severity::Error);
+}
+
+#[test]
+fn test_config_rejects_unknown_rules() {
+    assert!(LintConfig::from_toml("[rules.no-such-rule]\nenabled = false\n").is_err());
+    assert!(LintConfig::from_toml("[rules.no-evals]\nlevel = \"info\"\n").is_err());
+}
+
+#[test]
+fn test_findings_rendered_in_markdown() {
+    let markdown = core_generate_design(&sample_request());
+
+    assert!(markdown.contains("## 9. Design Review Findings"));
+    assert!(markdown.contains("- **WARNING** `mcp-without-guardrails` (prompt.guardrails):"));
+}
+
+#[tokio::test]
+async fn test_lint_endpoint() {
+    let response = app()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/lint")
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_vec(&sample_payload()).unwrap()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body_json: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(
+        body_json["findings"][0]["rule_id"],
+        "episodic-memory-without-store"
+    );
+    assert_eq!(body_json["findings"][0]["severity"], "warning");
+}
+
+#[tokio::test]
+async fn test_generate_uses_configured_rules() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("lint.toml");
+    std::fs::write(&path, "[rules.mcp-without-guardrails]\nenabled = false\n").unwrap();
+    let config = Config {
+        lint_config: Some(path),
+        ..Config::default_test()
+    };
+
+    let response = backend::app_with_config(&config)
+        .unwrap()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/generate")
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_vec(&sample_payload()).unwrap()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body_json: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body_json["findings"].as_array().unwrap().len(), 1);
+    assert!(!body_json["markdown"]
+        .as_str()
+        .unwrap()
+        .contains("mcp-without-guardrails"));
+}
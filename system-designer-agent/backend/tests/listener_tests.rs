@@ -0,0 +1,162 @@
+use std::os::unix::fs::PermissionsExt;
+use std::os::unix::io::IntoRawFd;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use axum::body::Body;
+use backend::config::{Config, ConfigSources};
+use backend::listener::{self, Listen, Listener, SocketMode};
+use hyper::{Request, StatusCode};
+use hyper_util::rt::TokioIo;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::{TcpStream, UnixStream};
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+
+/// `GET /health` over an established connection
+async fn get_health<I>(io: I) -> StatusCode
+where
+    I: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    let (mut sender, conn) = hyper::client::conn::http1::handshake(TokioIo::new(io))
+        .await
+        .unwrap();
+    tokio::spawn(conn);
+    let request = Request::get("/health")
+        .header("host", "localhost")
+        .body(Body::empty())
+        .unwrap();
+    sender.send_request(request).await.unwrap().status()
+}
+
+/// Serve the default app on `listener` until the sender fires
+fn start(listener: Listener) -> (oneshot::Sender<()>, JoinHandle<usize>) {
+    let app = backend::app_with_config(&Config::default_test()).unwrap();
+    let (stop, stopped) = oneshot::channel::<()>();
+    let server = tokio::spawn(listener::serve(
+        listener,
+        app,
+        None,
+        async {
+            let _ = stopped.await;
+        },
+        Duration::from_secs(5),
+    ));
+    (stop, server)
+}
+
+#[tokio::test]
+async fn test_unix_socket_with_permissions() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("api.sock");
+
+    let listener = Listener::bind_unix(path.clone(), SocketMode(0o600)).unwrap();
+    assert_eq!(listener.describe(), format!("unix:{}", path.display()));
+    let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o600);
+
+    let (stop, server) = start(listener);
+    let stream = UnixStream::connect(&path).await.unwrap();
+    assert_eq!(get_health(stream).await, StatusCode::OK);
+
+    // Graceful shutdown stops accepting and removes the socket file
+    stop.send(()).unwrap();
+    tokio::time::timeout(Duration::from_secs(5), server)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(!path.exists());
+}
+
+#[tokio::test]
+async fn test_unix_socket_in_use_or_stale() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("api.sock");
+
+    let first = Listener::bind_unix(path.clone(), SocketMode::default()).unwrap();
+    let err = Listener::bind_unix(path.clone(), SocketMode::default())
+        .err()
+        .unwrap();
+    assert_eq!(err.kind(), std::io::ErrorKind::AddrInUse);
+
+    // A socket file nobody listens on is left over from a crash: replaced
+    let stale = std::os::unix::net::UnixListener::bind(dir.path().join("stale.sock")).unwrap();
+    drop(stale);
+    assert!(Listener::bind_unix(dir.path().join("stale.sock"), SocketMode::default()).is_ok());
+
+    std::fs::write(dir.path().join("file"), "").unwrap();
+    assert!(Listener::bind_unix(dir.path().join("file"), SocketMode::default()).is_err());
+    drop(first);
+}
+
+#[tokio::test]
+async fn test_inherited_sockets_and_shutdown() {
+    // What systemd hands over: an already listening TCP socket
+    let std_listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = std_listener.local_addr().unwrap();
+    let listener = unsafe { Listener::from_raw_fd(std_listener.into_raw_fd()) }.unwrap();
+    assert_eq!(listener.describe(), addr.to_string());
+
+    let (stop, server) = start(listener);
+    let stream = TcpStream::connect(addr).await.unwrap();
+    assert_eq!(get_health(stream).await, StatusCode::OK);
+
+    stop.send(()).unwrap();
+    tokio::time::timeout(Duration::from_secs(5), server)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(TcpStream::connect(addr).await.is_err());
+
+    // An inherited Unix socket belongs to systemd and is not removed
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("activated.sock");
+    let std_listener = std::os::unix::net::UnixListener::bind(&path).unwrap();
+    let listener = unsafe { Listener::from_raw_fd(std_listener.into_raw_fd()) }.unwrap();
+    let (stop, server) = start(listener);
+    let stream = UnixStream::connect(&path).await.unwrap();
+    assert_eq!(get_health(stream).await, StatusCode::OK);
+    stop.send(()).unwrap();
+    server.await.unwrap();
+    assert!(path.exists());
+}
+
+#[test]
+fn test_listen_settings() {
+    assert_eq!("tcp".parse::<Listen>(), Ok(Listen::Tcp));
+    assert_eq!("systemd".parse::<Listen>(), Ok(Listen::Systemd));
+    assert_eq!(
+        "unix:/run/designer.sock".parse::<Listen>(),
+        Ok(Listen::Unix(PathBuf::from("/run/designer.sock")))
+    );
+    assert!("unix:".parse::<Listen>().is_err());
+    assert_eq!("660".parse::<SocketMode>(), Ok(SocketMode(0o660)));
+    assert_eq!("0600".parse::<SocketMode>(), Ok(SocketMode(0o600)));
+    assert!("999".parse::<SocketMode>().is_err());
+
+    let errors = Config::load(&ConfigSources {
+        flags: vec![
+            (
+                "listen".to_string(),
+                "unix:/no/such/dir/api.sock".to_string(),
+            ),
+            ("unix_socket_mode".to_string(), "1777".to_string()),
+        ],
+        ..ConfigSources::default()
+    })
+    .unwrap_err()
+    .to_string();
+    assert!(
+        errors.contains("listen: directory of /no/such/dir/api.sock does not exist"),
+        "{}",
+        errors
+    );
+    assert!(
+        errors.contains("unix_socket_mode (from --set)"),
+        "{}",
+        errors
+    );
+
+    // Without LISTEN_PID naming this process there is nothing to inherit
+    assert!(Listener::from_systemd().is_err());
+}
@@ -0,0 +1,203 @@
+mod common;
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use axum::{
+    body::Body,
+    extract::connect_info::MockConnectInfo,
+    http::{header, Request, StatusCode},
+    Router,
+};
+use backend::app_with_config;
+use backend::config::Config;
+use backend::metrics::{self, Metrics};
+use common::payload_for;
+use http_body_util::BodyExt;
+use serde_json::{json, Value};
+use tower::ServiceExt;
+
+async fn send(app: &Router, method: &str, uri: &str, body: Option<Value>) -> StatusCode {
+    let body = body.map_or_else(Body::empty, |b| Body::from(b.to_string()));
+    let request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("content-type", "application/json")
+        .body(body)
+        .unwrap();
+    app.clone().oneshot(request).await.unwrap().status()
+}
+
+async fn scrape(app: &Router) -> String {
+    let response = app
+        .clone()
+        .oneshot(Request::get("/metrics").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers()[header::CONTENT_TYPE]
+        .to_str()
+        .unwrap()
+        .starts_with("text/plain"));
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    String::from_utf8(bytes.to_vec()).unwrap()
+}
+
+/// Value of the sample whose name and labels start with `prefix`
+fn sample(text: &str, prefix: &str) -> Option<f64> {
+    text.lines()
+        .find(|line| line.starts_with(prefix))
+        .and_then(|line| line.rsplit(' ').next())
+        .map(|value| value.parse().unwrap())
+}
+
+fn app(config: Config) -> Router {
+    app_with_config(&config)
+        .unwrap()
+        .layer(MockConnectInfo(SocketAddr::from(([10, 0, 0, 1], 4000))))
+}
+
+#[tokio::test]
+async fn test_requests_are_counted_by_route_template_and_status() {
+    let app = app(Config::default_test());
+
+    send(&app, "GET", "/designs/first", None).await;
+    send(&app, "GET", "/designs/second", None).await;
+    send(&app, "GET", "/health", None).await;
+    send(&app, "GET", "/no-such-route", None).await;
+
+    let text = scrape(&app).await;
+    assert_eq!(
+        sample(
+            &text,
+            r#"http_requests_total{method="GET",route="/designs/:id",status="404"}"#
+        ),
+        Some(2.0)
+    );
+    assert_eq!(
+        sample(
+            &text,
+            r#"http_requests_total{method="GET",route="/health",status="200"}"#
+        ),
+        Some(1.0)
+    );
+    assert_eq!(
+        sample(
+            &text,
+            r#"http_requests_total{method="GET",route="unmatched",status="404"}"#
+        ),
+        Some(1.0)
+    );
+    assert_eq!(
+        sample(
+            &text,
+            r#"http_request_duration_seconds_count{method="GET",route="/designs/:id",status="404"}"#
+        ),
+        Some(2.0)
+    );
+    assert!(!text.contains("/designs/first"));
+}
+
+#[tokio::test]
+async fn test_output_size_and_validation_failures() {
+    let app = app(Config::default_test());
+
+    let status = send(&app, "POST", "/generate", Some(payload_for("Bot"))).await;
+    assert_eq!(status, StatusCode::OK);
+    let status = send(
+        &app,
+        "POST",
+        "/generate?format=json",
+        Some(payload_for("Bot")),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let mut invalid = payload_for(&"x".repeat(600));
+    invalid["tools"]["apis"] = json!(vec!["api"; 51]);
+    let status = send(&app, "POST", "/lint", Some(invalid)).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+
+    let text = scrape(&app).await;
+    assert_eq!(
+        sample(&text, r#"design_output_bytes_count{format="markdown"}"#),
+        Some(1.0)
+    );
+    assert_eq!(
+        sample(&text, r#"design_output_bytes_count{format="json"}"#),
+        Some(1.0)
+    );
+    assert!(sample(&text, r#"design_output_bytes_sum{format="markdown"}"#).unwrap() > 1000.0);
+    assert_eq!(
+        sample(
+            &text,
+            r#"validation_failures_total{field="purpose.use_case"}"#
+        ),
+        Some(1.0)
+    );
+    assert_eq!(
+        sample(&text, r#"validation_failures_total{field="tools.apis"}"#),
+        Some(1.0)
+    );
+}
+
+#[tokio::test]
+async fn test_rate_limit_rejections_and_memory_gauge() {
+    let app = app(Config {
+        rate_limit_per_second: 1,
+        rate_limit_burst: 1,
+        ..Config::default_test()
+    });
+
+    assert_eq!(send(&app, "GET", "/templates", None).await, StatusCode::OK);
+    assert_eq!(
+        send(&app, "GET", "/templates", None).await,
+        StatusCode::TOO_MANY_REQUESTS
+    );
+
+    let text = scrape(&app).await;
+    assert_eq!(
+        sample(&text, r#"rate_limit_rejections_total{group="api"}"#),
+        Some(1.0)
+    );
+    let memory_ok = sample(&text, "memory_ok ").unwrap();
+    assert!(memory_ok == 0.0 || memory_ok == 1.0);
+}
+
+#[tokio::test]
+async fn test_metrics_can_be_disabled_or_moved() {
+    let disabled = app(Config {
+        metrics_enabled: false,
+        ..Config::default_test()
+    });
+    assert_eq!(
+        send(&disabled, "GET", "/metrics", None).await,
+        StatusCode::NOT_FOUND
+    );
+
+    // With a separate port the API router no longer serves /metrics, but
+    // requests are still recorded for the admin router
+    let collectors = Arc::new(Metrics::new());
+    let moved = backend::app_with_metrics(
+        &Config {
+            metrics_port: Some(9100),
+            ..Config::default_test()
+        },
+        collectors.clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        send(&moved, "GET", "/metrics", None).await,
+        StatusCode::NOT_FOUND
+    );
+
+    let admin = metrics::router(collectors);
+    let text = scrape(&admin).await;
+    assert_eq!(
+        sample(
+            &text,
+            r#"http_requests_total{method="GET",route="unmatched",status="404"}"#
+        ),
+        Some(1.0)
+    );
+}
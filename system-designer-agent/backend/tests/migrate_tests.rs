@@ -0,0 +1,148 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use backend::cli::{parse_request, InputFormat};
+use backend::migrate::{self, SCHEMA_VERSION};
+use backend::store::{DesignStore, SqliteStore, DEFAULT_WORKSPACE};
+use backend::{app, validate_design_request};
+use http_body_util::BodyExt;
+use serde_json::{json, Value};
+use tower::ServiceExt;
+
+/// `tests/fixtures/migrations/v<N>/<name>.json` holds a payload in schema
+/// version N. Every payload in `v<N>` has a counterpart with the same name in
+/// `v<N+1>` that it must upgrade to.
+fn fixture_dir(version: u32) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/migrations")
+        .join(format!("v{}", version))
+}
+
+fn fixtures(version: u32) -> Vec<(String, Value)> {
+    let mut fixtures: Vec<(String, Value)> = fs::read_dir(fixture_dir(version))
+        .unwrap_or_else(|e| panic!("no fixtures for schema version {}: {}", version, e))
+        .map(|entry| {
+            let path = entry.unwrap().path();
+            let name = path.file_name().unwrap().to_string_lossy().into_owned();
+            let value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+            (name, value)
+        })
+        .collect();
+    fixtures.sort_by(|a, b| a.0.cmp(&b.0));
+    fixtures
+}
+
+fn read_fixture(version: u32, name: &str) -> Value {
+    let path = fixture_dir(version).join(name);
+    serde_json::from_str(&fs::read_to_string(&path).unwrap())
+        .unwrap_or_else(|e| panic!("{}: {}", path.display(), e))
+}
+
+#[test]
+fn test_each_migration_step_matches_fixtures() {
+    for version in 1..SCHEMA_VERSION {
+        let inputs = fixtures(version);
+        assert!(
+            !inputs.is_empty(),
+            "migration from v{} has no fixtures",
+            version
+        );
+
+        for (name, input) in inputs {
+            let expected = read_fixture(version + 1, &name);
+            let upgraded = migrate::upgrade_to(input, version + 1).unwrap();
+            assert_eq!(upgraded, expected, "v{}/{}", version, name);
+        }
+    }
+}
+
+#[test]
+fn test_every_fixture_upgrades_to_a_valid_request() {
+    for version in 1..=SCHEMA_VERSION {
+        for (name, input) in fixtures(version) {
+            let request = migrate::design_request(input)
+                .unwrap_or_else(|e| panic!("v{}/{}: {}", version, name, e));
+            assert_eq!(request.schema_version, SCHEMA_VERSION);
+            validate_design_request(&request).unwrap();
+        }
+    }
+}
+
+#[test]
+fn test_unsupported_versions_are_rejected() {
+    let mut payload = read_fixture(SCHEMA_VERSION, "support_bot.json");
+
+    payload["schema_version"] = json!(SCHEMA_VERSION + 1);
+    let error = migrate::design_request(payload.clone()).unwrap_err();
+    assert!(error
+        .to_string()
+        .contains("newer than the supported version"));
+
+    payload["schema_version"] = json!("two");
+    let error = migrate::design_request(payload).unwrap_err();
+    assert!(error.to_string().contains("Invalid schema_version"));
+}
+
+#[tokio::test]
+async fn test_generate_accepts_old_payloads() {
+    let payload = read_fixture(1, "support_bot.json");
+
+    let response = app()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/generate?format=json")
+                .header("content-type", "application/json")
+                .body(Body::from(payload.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let spec: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(spec["schema_version"], json!(SCHEMA_VERSION));
+    assert_eq!(spec["memory"]["file_storage"], json!(""));
+}
+
+#[test]
+fn test_cli_accepts_old_yaml() {
+    let yaml = serde_yaml::to_string(&read_fixture(1, "support_bot.json")).unwrap();
+
+    let request = parse_request(&yaml, InputFormat::Yaml).unwrap();
+
+    let expected = migrate::design_request(read_fixture(2, "support_bot.json")).unwrap();
+    assert_eq!(request, expected);
+}
+
+#[tokio::test]
+async fn test_stored_designs_are_upgraded_on_read() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("designs.db");
+    let current = migrate::design_request(read_fixture(1, "support_bot.json")).unwrap();
+    let id = SqliteStore::open(&path)
+        .unwrap()
+        .create(DEFAULT_WORKSPACE, current.clone(), String::new())
+        .await
+        .unwrap()
+        .id;
+
+    // Simulate a row written before schema versioning existed
+    let old = read_fixture(1, "support_bot.json").to_string();
+    rusqlite::Connection::open(&path)
+        .unwrap()
+        .execute(
+            "UPDATE designs SET request = ?1 WHERE id = ?2",
+            rusqlite::params![old, id],
+        )
+        .unwrap();
+
+    let store = SqliteStore::open(&path).unwrap();
+    let design = store.get(DEFAULT_WORKSPACE, &id).await.unwrap().unwrap();
+    assert_eq!(design.request, current);
+}
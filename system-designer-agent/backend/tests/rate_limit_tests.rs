@@ -0,0 +1,249 @@
+use std::net::{IpAddr, SocketAddr};
+
+use axum::{
+    body::Body,
+    extract::ConnectInfo,
+    http::{HeaderMap, Request, StatusCode},
+    Router,
+};
+use backend::app_with_config;
+use backend::config::Config;
+use backend::middleware::auth::hash_api_key;
+use backend::middleware::rate_limit::client_address;
+use http_body_util::BodyExt;
+use serde_json::Value;
+use tower::ServiceExt;
+
+fn ip(s: &str) -> IpAddr {
+    s.parse().unwrap()
+}
+
+fn limited_config(per_second: u32, burst: u32) -> Config {
+    Config {
+        rate_limit_per_second: per_second,
+        rate_limit_burst: burst,
+        health_rate_limit_per_second: 100,
+        health_rate_limit_burst: 100,
+        trusted_proxies: vec![ip("10.0.0.1")],
+        ..Config::default_test()
+    }
+}
+
+fn limited_app(per_second: u32, burst: u32) -> Router {
+    app_with_config(&limited_config(per_second, burst)).unwrap()
+}
+
+/// `limited_app` accepting the API keys `alice-key` and `bob-key`
+fn secured_app(dir: &tempfile::TempDir, per_second: u32, burst: u32) -> Router {
+    let path = dir.path().join("auth.toml");
+    let keys: String = ["alice", "bob"]
+        .iter()
+        .map(|name| {
+            format!(
+                "[[api_keys]]\nname = \"{}\"\nhash = \"{}\"\nscopes = [\"designs:read\"]\n",
+                name,
+                hash_api_key(&format!("{}-key", name))
+            )
+        })
+        .collect();
+    std::fs::write(&path, keys).unwrap();
+    let config = Config {
+        auth_config: Some(path),
+        ..limited_config(per_second, burst)
+    };
+    app_with_config(&config).unwrap()
+}
+
+/// A request arriving from the proxy at 10.0.0.1
+fn request(uri: &str) -> axum::http::request::Builder {
+    Request::builder()
+        .uri(uri)
+        .extension(ConnectInfo(SocketAddr::from(([10, 0, 0, 1], 4000))))
+}
+
+/// `GET uri`, optionally forwarded for `client` by the trusted proxy at
+/// 10.0.0.1
+async fn get(app: &Router, uri: &str, client: Option<&str>) -> axum::response::Response {
+    let mut request = request(uri);
+    if let Some(client) = client {
+        request = request.header("x-forwarded-for", client);
+    }
+    app.clone()
+        .oneshot(request.body(Body::empty()).unwrap())
+        .await
+        .unwrap()
+}
+
+#[test]
+fn test_client_address_ignores_api_key() {
+    let mut headers = HeaderMap::new();
+    headers.insert("x-api-key", "secret".parse().unwrap());
+
+    assert_eq!(
+        client_address(&headers, Some(ip("192.0.2.1")), &[]),
+        ip("192.0.2.1")
+    );
+}
+
+#[test]
+fn test_forwarded_for_only_from_trusted_proxies() {
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        "x-forwarded-for",
+        "198.51.100.2, 203.0.113.7, 10.0.0.2".parse().unwrap(),
+    );
+    let trusted = [ip("10.0.0.1"), ip("10.0.0.2")];
+
+    // Untrusted peers cannot pick their own address
+    assert_eq!(
+        client_address(&headers, Some(ip("192.0.2.1")), &trusted),
+        ip("192.0.2.1")
+    );
+    // The nearest untrusted hop is the client
+    assert_eq!(
+        client_address(&headers, Some(ip("10.0.0.1")), &trusted),
+        ip("203.0.113.7")
+    );
+    // Without a header the proxy itself is the client
+    assert_eq!(
+        client_address(&HeaderMap::new(), Some(ip("10.0.0.1")), &trusted),
+        ip("10.0.0.1")
+    );
+}
+
+#[test]
+fn test_forwarded_for_stops_at_malformed_hop() {
+    let trusted = [ip("10.0.0.1"), ip("10.0.0.2")];
+
+    // The spoofed address left of the junk entry is never used
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        "x-forwarded-for",
+        "1.2.3.4, junk, 203.0.113.7".parse().unwrap(),
+    );
+    assert_eq!(
+        client_address(&headers, Some(ip("10.0.0.1")), &trusted),
+        ip("203.0.113.7")
+    );
+
+    headers.insert("x-forwarded-for", "1.2.3.4, junk".parse().unwrap());
+    assert_eq!(
+        client_address(&headers, Some(ip("10.0.0.1")), &trusted),
+        ip("10.0.0.1")
+    );
+
+    headers.insert(
+        "x-forwarded-for",
+        "1.2.3.4, junk, 10.0.0.2".parse().unwrap(),
+    );
+    assert_eq!(
+        client_address(&headers, Some(ip("10.0.0.1")), &trusted),
+        ip("10.0.0.2")
+    );
+}
+
+#[tokio::test]
+async fn test_exceeding_quota_returns_429() {
+    let app = limited_app(1, 2);
+
+    assert_eq!(get(&app, "/templates", None).await.status(), StatusCode::OK);
+    assert_eq!(get(&app, "/templates", None).await.status(), StatusCode::OK);
+
+    let response = get(&app, "/templates", None).await;
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    let retry_after: u64 = response.headers()["retry-after"]
+        .to_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!(retry_after >= 1);
+
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body_json: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body_json["code"], "RATE_LIMITED");
+}
+
+#[tokio::test]
+async fn test_quotas_are_per_client_and_per_group() {
+    let app = limited_app(1, 1);
+
+    assert_eq!(get(&app, "/templates", None).await.status(), StatusCode::OK);
+    assert_eq!(
+        get(&app, "/templates", None).await.status(),
+        StatusCode::TOO_MANY_REQUESTS
+    );
+
+    // Another client has its own bucket
+    assert_eq!(
+        get(&app, "/templates", Some("203.0.113.7")).await.status(),
+        StatusCode::OK
+    );
+    // Health checks draw from a separate quota
+    assert_eq!(get(&app, "/health", None).await.status(), StatusCode::OK);
+    assert_eq!(get(&app, "/ready", None).await.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_rotating_api_keys_share_a_bucket() {
+    let app = limited_app(1, 1);
+
+    for (i, expected) in [
+        StatusCode::OK,
+        StatusCode::TOO_MANY_REQUESTS,
+        StatusCode::TOO_MANY_REQUESTS,
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let request = request("/templates")
+            .header("x-api-key", format!("bogus-{}", i))
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), expected, "request {}", i);
+    }
+}
+
+#[tokio::test]
+async fn test_valid_credentials_are_limited_per_principal() {
+    let dir = tempfile::tempdir().unwrap();
+    let app = secured_app(&dir, 1, 1);
+    let send = |key: &str, client: &str| {
+        let request = request("/templates")
+            .header("x-api-key", key)
+            .header("x-forwarded-for", client)
+            .body(Body::empty())
+            .unwrap();
+        let app = app.clone();
+        async move { app.oneshot(request).await.unwrap().status() }
+    };
+
+    // One key spread over several addresses still has a single bucket
+    assert_eq!(send("alice-key", "203.0.113.7").await, StatusCode::OK);
+    assert_eq!(
+        send("alice-key", "198.51.100.2").await,
+        StatusCode::TOO_MANY_REQUESTS
+    );
+
+    // Another key behind the same address has its own
+    assert_eq!(send("bob-key", "203.0.113.7").await, StatusCode::OK);
+
+    // Invalid keys are charged to the address
+    assert_eq!(
+        send("guess-1", "203.0.113.7").await,
+        StatusCode::UNAUTHORIZED
+    );
+    assert_eq!(
+        send("guess-2", "203.0.113.7").await,
+        StatusCode::TOO_MANY_REQUESTS
+    );
+}
+
+#[tokio::test]
+async fn test_zero_rate_disables_limiting() {
+    let app = limited_app(0, 0);
+
+    for _ in 0..20 {
+        assert_eq!(get(&app, "/templates", None).await.status(), StatusCode::OK);
+    }
+}
@@ -0,0 +1,239 @@
+use std::fs;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+    Router,
+};
+use backend::app_with_reloader;
+use backend::config::{Config, ConfigSources};
+use backend::logging::LogFilter;
+use backend::metrics::Metrics;
+use backend::reload;
+use http_body_util::BodyExt;
+use serde_json::Value;
+use tower::ServiceExt;
+use tracing_subscriber::{reload as filter_reload, EnvFilter};
+
+async fn get(app: &Router, uri: &str, origin: &str) -> (StatusCode, Option<String>, Value) {
+    let response = app
+        .clone()
+        .oneshot(
+            Request::get(uri)
+                .header("origin", origin)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    let status = response.status();
+    let allowed = response
+        .headers()
+        .get("access-control-allow-origin")
+        .map(|v| v.to_str().unwrap().to_string());
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    (
+        status,
+        allowed,
+        serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+    )
+}
+
+fn sources(file: &std::path::Path) -> ConfigSources {
+    ConfigSources {
+        file: Some(file.to_path_buf()),
+        ..ConfigSources::default()
+    }
+}
+
+#[tokio::test]
+async fn test_cors_origins_and_quotas_are_swapped() {
+    let config = Config {
+        cors_origins: vec!["https://old.example.com".to_string()],
+        rate_limit_per_second: 1,
+        rate_limit_burst: 1,
+        ..Config::default_test()
+    };
+    let (app, reloader) = app_with_reloader(&config, Arc::new(Metrics::new())).unwrap();
+
+    let (_, allowed, _) = get(&app, "/templates", "https://old.example.com").await;
+    assert_eq!(allowed.as_deref(), Some("https://old.example.com"));
+    let (status, _, _) = get(&app, "/templates", "https://old.example.com").await;
+    assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+
+    reloader
+        .apply(Config {
+            cors_origins: vec!["https://new.example.com".to_string()],
+            rate_limit_per_second: 100,
+            rate_limit_burst: 100,
+            ..config
+        })
+        .unwrap();
+
+    let (status, allowed, _) = get(&app, "/templates", "https://old.example.com").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(allowed, None);
+    let (_, allowed, _) = get(&app, "/templates", "https://new.example.com").await;
+    assert_eq!(allowed.as_deref(), Some("https://new.example.com"));
+}
+
+#[tokio::test]
+async fn test_templates_are_swapped() {
+    let first = tempfile::tempdir().unwrap();
+    fs::write(first.path().join("compact.j2"), "{{ purpose.use_case }}").unwrap();
+    let second = tempfile::tempdir().unwrap();
+    fs::write(second.path().join("brief.md"), "# {{ purpose.use_case }}").unwrap();
+
+    let config = Config {
+        template_dir: Some(first.path().to_path_buf()),
+        ..Config::default_test()
+    };
+    let (app, reloader) = app_with_reloader(&config, Arc::new(Metrics::new())).unwrap();
+    let (_, _, body) = get(&app, "/templates", "http://localhost:5173").await;
+    assert_eq!(body["templates"], serde_json::json!(["compact", "default"]));
+
+    reloader
+        .apply(Config {
+            template_dir: Some(second.path().to_path_buf()),
+            ..config
+        })
+        .unwrap();
+    let (_, _, body) = get(&app, "/templates", "http://localhost:5173").await;
+    assert_eq!(body["templates"], serde_json::json!(["brief", "default"]));
+}
+
+#[tokio::test]
+async fn test_invalid_reload_keeps_running_config() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("config.toml");
+    let templates = dir.path().join("templates");
+    fs::create_dir(&templates).unwrap();
+    fs::write(templates.join("compact.j2"), "{{ purpose.use_case }}").unwrap();
+
+    fs::write(&file, "cors_origins = [\"https://a.example.com\"]\n").unwrap();
+    let config = Config::load(&sources(&file)).unwrap();
+    let metrics = Arc::new(Metrics::new());
+    let (app, reloader) = app_with_reloader(&config, metrics.clone()).unwrap();
+
+    // Bad origin and unknown key: rejected as a whole
+    fs::write(
+        &file,
+        "cors_origins = [\"a.example.com\"]\nrate_limit_burts = 5\n",
+    )
+    .unwrap();
+    let err = reloader.reload(&sources(&file)).unwrap_err();
+    assert_eq!(err.exit_code(), 78);
+    assert!(err.to_string().contains("rate_limit_burts"), "{}", err);
+
+    // A broken template fails after validation, before anything is swapped
+    fs::write(templates.join("broken.md"), "{{ purpose.use_case ").unwrap();
+    fs::write(
+        &file,
+        format!(
+            "cors_origins = [\"https://b.example.com\"]\ntemplate_dir = {:?}\n",
+            templates.to_str().unwrap()
+        ),
+    )
+    .unwrap();
+    assert!(reloader.reload(&sources(&file)).is_err());
+
+    assert_eq!(
+        reloader.current().cors_origins,
+        vec!["https://a.example.com"]
+    );
+    let (_, allowed, body) = get(&app, "/templates", "https://a.example.com").await;
+    assert_eq!(allowed.as_deref(), Some("https://a.example.com"));
+    assert_eq!(body["templates"], serde_json::json!(["default"]));
+
+    fs::remove_file(templates.join("broken.md")).unwrap();
+    reloader.reload(&sources(&file)).unwrap();
+    let (_, allowed, body) = get(&app, "/templates", "https://b.example.com").await;
+    assert_eq!(allowed.as_deref(), Some("https://b.example.com"));
+    assert_eq!(body["templates"], serde_json::json!(["compact", "default"]));
+
+    let text = metrics.render().unwrap();
+    assert!(
+        text.contains(r#"config_reloads_total{result="failure"} 2"#),
+        "{}",
+        text
+    );
+    assert!(
+        text.contains(r#"config_reloads_total{result="success"} 1"#),
+        "{}",
+        text
+    );
+}
+
+#[tokio::test]
+async fn test_failed_swap_changes_nothing() {
+    let templates = tempfile::tempdir().unwrap();
+    fs::write(
+        templates.path().join("brief.md"),
+        "# {{ purpose.use_case }}",
+    )
+    .unwrap();
+
+    let config = Config {
+        cors_origins: vec!["https://old.example.com".to_string()],
+        rate_limit_per_second: 1,
+        rate_limit_burst: 1,
+        ..Config::default_test()
+    };
+    // A filter whose subscriber is gone cannot be replaced
+    let (layer, handle) = filter_reload::Layer::new(EnvFilter::new("info"));
+    drop(layer);
+    let (app, reloader) = app_with_reloader(&config, Arc::new(Metrics::new())).unwrap();
+    let reloader = reloader.with_log_filter(LogFilter::from(handle));
+
+    let (_, _, body) = get(&app, "/templates", "https://old.example.com").await;
+    assert_eq!(body["templates"], serde_json::json!(["default"]));
+
+    let result = reloader.apply(Config {
+        cors_origins: vec!["https://new.example.com".to_string()],
+        log_level: "warn".to_string(),
+        rate_limit_per_second: 100,
+        rate_limit_burst: 100,
+        template_dir: Some(templates.path().to_path_buf()),
+        ..config.clone()
+    });
+    assert!(result.is_err());
+
+    // Templates, origins, quota and the recorded configuration are unchanged
+    let (status, allowed, _) = get(&app, "/templates", "https://old.example.com").await;
+    assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(allowed.as_deref(), Some("https://old.example.com"));
+    let current = reloader.current();
+    assert_eq!(current.log_level, config.log_level);
+    assert_eq!(current.template_dir, None);
+    assert_eq!(current.rate_limit_per_second, 1);
+}
+
+#[tokio::test]
+async fn test_file_change_triggers_reload() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("config.toml");
+    fs::write(&file, "rate_limit_burst = 20\n").unwrap();
+    let config = Config::load(&sources(&file)).unwrap();
+    let (_, reloader) = app_with_reloader(&config, Arc::new(Metrics::new())).unwrap();
+
+    let watcher = tokio::spawn(reload::watch(reloader.clone(), sources(&file)));
+    // Let the watcher record the initial modification time
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    // Coarse file systems only track whole seconds
+    tokio::time::sleep(Duration::from_secs(1)).await;
+    fs::write(&file, "rate_limit_burst = 35\n").unwrap();
+
+    let mut burst = 0;
+    for _ in 0..40 {
+        burst = reloader.current().rate_limit_burst;
+        if burst == 35 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(250)).await;
+    }
+    watcher.abort();
+    assert_eq!(burst, 35);
+}
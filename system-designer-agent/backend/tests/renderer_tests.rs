@@ -0,0 +1,144 @@
+mod common;
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+    Router,
+};
+use backend::config::Config;
+use backend::lint::Linter;
+use backend::renderer::{Renderer, DEFAULT_TEMPLATE};
+use backend::{app_with_config, core_generate_design, DesignRequest};
+use http_body_util::BodyExt;
+use serde_json::{json, Value};
+use tower::ServiceExt;
+
+/// The shared sample with several APIs and episodic memory backed by a store
+fn sample_payload() -> Value {
+    let mut payload = common::sample_payload();
+    payload["tools"]["apis"] = json!(["Zendesk", "Slack"]);
+    payload["memory"]["episodic"] = json!(true);
+    payload["memory"]["vector_db"] = json!("Qdrant");
+    payload
+}
+
+fn app_with_templates(dir: &std::path::Path) -> Router {
+    let config = Config {
+        template_dir: Some(dir.to_path_buf()),
+        ..Config::default_test()
+    };
+    app_with_config(&config).unwrap()
+}
+
+async fn post_generate(app: Router, uri: &str) -> (StatusCode, Value) {
+    let response = app
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(uri)
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_vec(&sample_payload()).unwrap()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    let status = response.status();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    (status, serde_json::from_slice(&body).unwrap())
+}
+
+#[test]
+fn test_builtin_template_matches_core_generate_design() {
+    let request: DesignRequest = serde_json::from_value(sample_payload()).unwrap();
+
+    let findings = Linter::new().lint(&request);
+    let rendered = Renderer::new()
+        .render(DEFAULT_TEMPLATE, &request, &findings)
+        .unwrap();
+
+    assert_eq!(rendered, core_generate_design(&request));
+    assert!(rendered.contains("- **External APIs:** [\"Zendesk\", \"Slack\"]"));
+    assert!(rendered.contains("- **Episodic Memory:** true"));
+    assert!(rendered.ends_with("*Generated by System Designer Agent*"));
+}
+
+#[test]
+fn test_flag_filter_keeps_rust_booleans() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("flags.md"),
+        "{{ memory.episodic }} {{ memory.episodic | flag }}",
+    )
+    .unwrap();
+    let mut renderer = Renderer::new();
+    renderer.load_dir(dir.path()).unwrap();
+
+    let request: DesignRequest = serde_json::from_value(sample_payload()).unwrap();
+    let rendered = renderer.render("flags", &request, &[]).unwrap();
+    assert_eq!(rendered, "True true");
+}
+
+#[tokio::test]
+async fn test_generate_with_user_template() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("acme.md"),
+        "# ACME Design: {{ purpose.use_case }}\n\n## Compliance\n- **Guardrails:** {{ prompt.guardrails }}\n",
+    )
+    .unwrap();
+
+    let (status, body) =
+        post_generate(app_with_templates(dir.path()), "/generate?template=acme").await;
+
+    assert_eq!(status, StatusCode::OK);
+    let markdown = body["markdown"].as_str().unwrap();
+    assert!(markdown.starts_with("# ACME Design: Support Bot"));
+    assert!(markdown.contains("- **Guardrails:** No PII"));
+}
+
+#[tokio::test]
+async fn test_generate_unknown_template() {
+    let (status, body) = post_generate(backend::app(), "/generate?template=missing").await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["code"], "BAD_REQUEST");
+}
+
+#[tokio::test]
+async fn test_list_templates() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("compact.j2"), "{{ purpose.use_case }}").unwrap();
+    std::fs::write(dir.path().join("notes.txt"), "ignored").unwrap();
+
+    let response = app_with_templates(dir.path())
+        .oneshot(
+            Request::builder()
+                .uri("/templates")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body_json: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body_json["templates"], json!(["compact", "default"]));
+}
+
+#[test]
+fn test_invalid_template_is_config_error() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("broken.md"), "{{ purpose.use_case ").unwrap();
+
+    let config = Config {
+        template_dir: Some(dir.path().to_path_buf()),
+        ..Config::default_test()
+    };
+
+    assert!(matches!(
+        Renderer::from_config(&config),
+        Err(backend::error::AppError::Config(_))
+    ));
+}
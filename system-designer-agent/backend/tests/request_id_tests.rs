@@ -0,0 +1,107 @@
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+    Router,
+};
+use backend::app;
+use backend::config::Config;
+use backend::logging::{tracer_provider, SERVICE_NAME};
+use http_body_util::BodyExt;
+use opentelemetry::trace::TracerProvider;
+use serde_json::Value;
+use tower::ServiceExt;
+use tracing_subscriber::layer::SubscriberExt;
+
+async fn get(app: &Router, uri: &str, request_id: Option<&str>) -> (StatusCode, String, Value) {
+    let mut request = Request::get(uri);
+    if let Some(id) = request_id {
+        request = request.header("x-request-id", id);
+    }
+    let response = app
+        .clone()
+        .oneshot(request.body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+
+    let status = response.status();
+    let header = response.headers()["x-request-id"]
+        .to_str()
+        .unwrap()
+        .to_string();
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    (
+        status,
+        header,
+        serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+    )
+}
+
+#[tokio::test]
+async fn test_request_id_is_generated_and_included_in_errors() {
+    let app = app();
+
+    let (status, id, body) = get(&app, "/designs/missing", None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert!(uuid::Uuid::parse_str(&id).is_ok(), "{}", id);
+    assert_eq!(body["request_id"], id.as_str());
+    assert_eq!(body["code"], "NOT_FOUND");
+
+    // Every request gets a fresh ID, successful ones included
+    let (status, other, _) = get(&app, "/health", None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_ne!(other, id);
+}
+
+#[tokio::test]
+async fn test_client_request_id_is_propagated() {
+    let app = app();
+
+    let (_, id, body) = get(&app, "/designs/missing", Some("client-7f3a")).await;
+    assert_eq!(id, "client-7f3a");
+    assert_eq!(body["request_id"], "client-7f3a");
+
+    // Malformed or oversized IDs are replaced rather than echoed
+    for bad in ["has space", &"x".repeat(129)] {
+        let (_, id, _) = get(&app, "/health", Some(bad)).await;
+        assert_ne!(id, bad);
+        assert!(uuid::Uuid::parse_str(&id).is_ok());
+    }
+}
+
+#[tokio::test]
+async fn test_spans_are_exported_with_request_id() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("spans.jsonl");
+    let config = Config {
+        trace_file: Some(path.clone()),
+        ..Config::default_test()
+    };
+
+    let provider = tracer_provider(&config).unwrap().unwrap();
+    let subscriber = tracing_subscriber::registry()
+        .with(tracing_opentelemetry::layer().with_tracer(provider.tracer(SERVICE_NAME)));
+    let guard = tracing::subscriber::set_default(subscriber);
+
+    let app = backend::app_with_config(&config).unwrap();
+    let (status, _, _) = get(&app, "/designs/missing", Some("trace-me-42")).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    drop(guard);
+    provider.shutdown().unwrap();
+
+    let spans: Vec<Value> = std::fs::read_to_string(&path)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    let request = spans
+        .iter()
+        .find(|span| span["attributes"]["request_id"] == "trace-me-42")
+        .expect("request span exported");
+    assert_eq!(request["name"], "request");
+    assert_eq!(request["attributes"]["method"], "GET");
+    assert_eq!(request["attributes"]["uri"], "/designs/missing");
+    assert_eq!(request["trace_id"].as_str().unwrap().len(), 32);
+
+    assert!(tracer_provider(&Config::default_test()).unwrap().is_none());
+}
@@ -0,0 +1,173 @@
+mod common;
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+    Router,
+};
+use backend::app;
+use backend::diff::{diff_requests, ChangeKind};
+use backend::store::{DesignStore, SqliteStore, DEFAULT_WORKSPACE};
+use backend::DesignRequest;
+use common::sample_payload;
+use http_body_util::BodyExt;
+use serde_json::{json, Value};
+use tower::ServiceExt;
+
+fn revised_payload() -> Value {
+    let mut payload = sample_payload();
+    payload["model"]["base_model"] = json!("Claude");
+    payload["tools"]["apis"] = json!(["Zendesk", "Jira"]);
+    payload["memory"]["episodic"] = json!(true);
+    payload
+}
+
+async fn send(app: &Router, method: &str, uri: &str, body: Option<Value>) -> (StatusCode, Value) {
+    let mut builder = Request::builder().method(method).uri(uri);
+    let body = match body {
+        Some(body) => {
+            builder = builder.header("content-type", "application/json");
+            Body::from(serde_json::to_vec(&body).unwrap())
+        }
+        None => Body::empty(),
+    };
+
+    let response = app
+        .clone()
+        .oneshot(builder.body(body).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    (status, serde_json::from_slice(&bytes).unwrap())
+}
+
+#[test]
+fn test_structural_diff_by_section_and_field() {
+    let before: DesignRequest = serde_json::from_value(sample_payload()).unwrap();
+    let after: DesignRequest = serde_json::from_value(revised_payload()).unwrap();
+
+    let sections = diff_requests(&before, &after);
+
+    let names: Vec<&str> = sections.iter().map(|s| s.section.as_str()).collect();
+    assert_eq!(names, vec!["model", "tools", "memory"]);
+
+    let model = &sections[0].changes[0];
+    assert_eq!(model.field, "base_model");
+    assert_eq!(model.kind, ChangeKind::Modified);
+    assert_eq!(model.before, Some(json!("GPT-4")));
+    assert_eq!(model.after, Some(json!("Claude")));
+
+    assert_eq!(
+        sections[1].changes[0].after,
+        Some(json!(["Zendesk", "Jira"]))
+    );
+    assert!(diff_requests(&before, &before).is_empty());
+}
+
+#[tokio::test]
+async fn test_revisions_and_diff_api() {
+    let app = app();
+
+    let (_, created) = send(&app, "POST", "/designs", Some(sample_payload())).await;
+    let id = created["id"].as_str().unwrap().to_string();
+    assert_eq!(created["revision"], 1);
+
+    let (status, updated) = send(
+        &app,
+        "PUT",
+        &format!("/designs/{}", id),
+        Some(revised_payload()),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(updated["revision"], 2);
+
+    let (status, revisions) = send(&app, "GET", &format!("/designs/{}/revisions", id), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(revisions.as_array().unwrap().len(), 2);
+    assert_eq!(revisions[0]["revision"], 1);
+
+    let (status, diff) = send(
+        &app,
+        "GET",
+        &format!("/designs/{}/diff?from=1&to=2", id),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(diff["from"], 1);
+    assert_eq!(diff["to"], 2);
+    assert_eq!(diff["sections"][0]["section"], "model");
+    let unified = diff["unified"].as_str().unwrap();
+    assert!(unified.starts_with("--- revision 1\n+++ revision 2\n"));
+    assert!(unified.contains("-- **Base Model:** GPT-4"));
+    assert!(unified.contains("+- **Base Model:** Claude"));
+
+    // Defaults compare the latest revision with the one before it
+    let (status, default_diff) = send(&app, "GET", &format!("/designs/{}/diff", id), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(default_diff, diff);
+}
+
+#[tokio::test]
+async fn test_diff_errors() {
+    let app = app();
+    let (_, created) = send(&app, "POST", "/designs", Some(sample_payload())).await;
+    let id = created["id"].as_str().unwrap();
+
+    let (status, _) = send(&app, "GET", &format!("/designs/{}/diff", id), None).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, body) = send(
+        &app,
+        "GET",
+        &format!("/designs/{}/diff?from=1&to=5", id),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["code"], "NOT_FOUND");
+
+    let (status, _) = send(&app, "GET", "/designs/missing/revisions", None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_sqlite_backfills_revisions_for_existing_designs() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("designs.db");
+    let request = serde_json::to_string(&sample_payload()).unwrap();
+
+    let conn = rusqlite::Connection::open(&path).unwrap();
+    conn.execute_batch(
+        "CREATE TABLE designs (id TEXT PRIMARY KEY, title TEXT NOT NULL, request TEXT NOT NULL,
+         rendered TEXT NOT NULL, created_at TEXT NOT NULL, updated_at TEXT NOT NULL);",
+    )
+    .unwrap();
+    conn.execute(
+        "INSERT INTO designs VALUES ('legacy', 'Support Bot', ?1, '# Legacy',
+         '2024-12-24 10:00:00+00:00', '2024-12-24 10:00:00+00:00')",
+        [request],
+    )
+    .unwrap();
+    drop(conn);
+
+    let store = SqliteStore::open(&path).unwrap();
+
+    assert_eq!(
+        store
+            .get(DEFAULT_WORKSPACE, "legacy")
+            .await
+            .unwrap()
+            .unwrap()
+            .revision,
+        1
+    );
+    let revision = store
+        .revision(DEFAULT_WORKSPACE, "legacy", 1)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(revision.rendered, "# Legacy");
+}
@@ -0,0 +1,107 @@
+use std::fs;
+use std::path::PathBuf;
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use backend::app;
+use backend::openapi::{design_request_schema, openapi};
+use http_body_util::BodyExt;
+use serde_json::{json, Value};
+use tower::ServiceExt;
+
+/// Compare `generated` with the committed copy in `backend/schema`, or
+/// rewrite the copy when `UPDATE_SCHEMAS` is set
+fn assert_committed(name: &str, generated: &Value) {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("schema")
+        .join(name);
+    let generated = format!("{}\n", serde_json::to_string_pretty(generated).unwrap());
+
+    if std::env::var_os("UPDATE_SCHEMAS").is_some() {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, generated).unwrap();
+        return;
+    }
+
+    let committed = fs::read_to_string(&path).unwrap_or_default();
+    assert!(
+        committed == generated,
+        "{} is out of date; run `make schema` and commit the result",
+        path.display()
+    );
+}
+
+async fn get_json(uri: &str) -> (StatusCode, Value) {
+    let response = app()
+        .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    (status, serde_json::from_slice(&body).unwrap())
+}
+
+#[test]
+fn test_committed_documents_are_current() {
+    assert_committed("openapi.json", &serde_json::to_value(openapi()).unwrap());
+    assert_committed("design-request.json", &design_request_schema());
+}
+
+#[tokio::test]
+async fn test_documents_are_served() {
+    let (status, document) = get_json("/openapi.json").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(document, serde_json::to_value(openapi()).unwrap());
+
+    let (status, schema) = get_json("/schema/design-request.json").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(schema, design_request_schema());
+}
+
+#[test]
+fn test_schema_carries_validation_limits() {
+    let schema = design_request_schema();
+    let defs = &schema["$defs"];
+
+    assert_eq!(schema["additionalProperties"], json!(false));
+    assert_eq!(
+        defs["Purpose"]["properties"]["use_case"]["maxLength"],
+        json!(500)
+    );
+    assert_eq!(defs["Tools"]["properties"]["apis"]["maxItems"], json!(50));
+    assert_eq!(
+        defs["Testing"]["properties"]["latency_testing"]["maxLength"],
+        json!(1000)
+    );
+}
+
+#[test]
+fn test_openapi_describes_routes_and_errors() {
+    let document = serde_json::to_value(openapi()).unwrap();
+
+    assert_eq!(document["openapi"], json!("3.1.0"));
+    let generate = &document["paths"]["/generate"]["post"];
+    assert_eq!(
+        generate["requestBody"]["content"]["application/json"]["schema"]["$ref"],
+        json!("#/components/schemas/DesignRequest")
+    );
+    for status in ["400", "401", "403", "422", "429", "500"] {
+        assert_eq!(
+            generate["responses"][status]["content"]["application/json"]["schema"]["$ref"],
+            json!("#/components/schemas/ErrorResponse"),
+            "status {}",
+            status
+        );
+    }
+    // The OpenAPI components and the JSON Schema share the same limits
+    assert_eq!(
+        document["components"]["schemas"]["Purpose"],
+        design_request_schema()["$defs"]["Purpose"]
+    );
+
+    let health = &document["paths"]["/health"]["get"];
+    assert!(health.get("security").is_none());
+    assert!(health["responses"].get("401").is_none());
+}
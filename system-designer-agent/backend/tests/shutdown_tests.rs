@@ -0,0 +1,166 @@
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use axum::body::Body;
+use axum::middleware::from_fn_with_state;
+use axum::routing::get;
+use axum::Router;
+use backend::config::{Config, ConfigSources};
+use backend::health::READY;
+use backend::listener::{self, Listener};
+use backend::middleware::{track_in_flight, InFlight};
+use hyper::{Request, StatusCode};
+use hyper_util::rt::TokioIo;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{oneshot, Notify};
+use tower::ServiceExt;
+
+/// A router whose only route signals `started` and then takes `duration`
+fn slow_app(duration: Duration, started: Arc<Notify>) -> Router {
+    Router::new().route(
+        "/slow",
+        get(move || async move {
+            started.notify_one();
+            tokio::time::sleep(duration).await;
+            "done"
+        }),
+    )
+}
+
+/// Serve `app` on an ephemeral port; the returned sender starts the shutdown
+async fn start(
+    app: Router,
+    drain_timeout: Duration,
+) -> (
+    std::net::SocketAddr,
+    oneshot::Sender<()>,
+    tokio::task::JoinHandle<usize>,
+) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (stop, stopped) = oneshot::channel::<()>();
+    let server = tokio::spawn(listener::serve(
+        Listener::Tcp(listener),
+        app,
+        None,
+        async {
+            let _ = stopped.await;
+        },
+        drain_timeout,
+    ));
+    (addr, stop, server)
+}
+
+/// Send `GET /slow` and return the status, or `None` if the connection died
+fn get_slow(addr: std::net::SocketAddr) -> tokio::task::JoinHandle<Option<StatusCode>> {
+    tokio::spawn(async move {
+        let stream = TcpStream::connect(addr).await.unwrap();
+        let (mut sender, conn) = hyper::client::conn::http1::handshake(TokioIo::new(stream))
+            .await
+            .unwrap();
+        tokio::spawn(conn);
+        let request = Request::get("/slow")
+            .header("host", "localhost")
+            .body(Body::empty())
+            .unwrap();
+        sender.send_request(request).await.ok().map(|r| r.status())
+    })
+}
+
+#[tokio::test]
+async fn test_shutdown_waits_for_in_flight_requests() {
+    let started = Arc::new(Notify::new());
+    let app = slow_app(Duration::from_millis(300), started.clone());
+    let (addr, stop, server) = start(app, Duration::from_secs(5)).await;
+
+    let response = get_slow(addr);
+    started.notified().await;
+    stop.send(()).unwrap();
+
+    assert_eq!(response.await.unwrap(), Some(StatusCode::OK));
+    assert_eq!(server.await.unwrap(), 0, "nothing should be aborted");
+}
+
+#[tokio::test]
+async fn test_drain_timeout_aborts_long_requests() {
+    let started = Arc::new(Notify::new());
+    let app = slow_app(Duration::from_secs(30), started.clone());
+    let (addr, stop, server) = start(app, Duration::from_millis(200)).await;
+
+    let _response = get_slow(addr);
+    started.notified().await;
+    let begin = Instant::now();
+    stop.send(()).unwrap();
+
+    assert_eq!(server.await.unwrap(), 1, "the slow request is aborted");
+    assert!(begin.elapsed() < Duration::from_secs(5));
+}
+
+#[tokio::test]
+async fn test_ready_reports_503_while_shutting_down() {
+    READY.store(false, Ordering::SeqCst);
+    let response = backend::app()
+        .oneshot(Request::get("/ready").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    READY.store(true, Ordering::SeqCst);
+
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    let body = http_body_util::BodyExt::collect(response.into_body())
+        .await
+        .unwrap()
+        .to_bytes();
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body["ready"], false);
+    assert_eq!(body["checks"]["accepting_requests"], false);
+}
+
+#[tokio::test]
+async fn test_in_flight_count_follows_requests() {
+    let in_flight = InFlight::default();
+    let seen = in_flight.clone();
+    let app = Router::new()
+        .route(
+            "/count",
+            get(move || async move { seen.count().to_string() }),
+        )
+        .layer(from_fn_with_state(in_flight.clone(), track_in_flight));
+
+    let response = app
+        .oneshot(Request::get("/count").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let body = http_body_util::BodyExt::collect(response.into_body())
+        .await
+        .unwrap()
+        .to_bytes();
+
+    assert_eq!(&body[..], b"1");
+    assert_eq!(in_flight.count(), 0);
+}
+
+#[test]
+fn test_drain_settings() {
+    let config = Config::default();
+    assert_eq!(config.pre_stop_delay_secs, 0);
+    assert_eq!(config.drain_timeout_secs, 30);
+
+    let sources = ConfigSources {
+        flags: vec![
+            ("pre_stop_delay_secs".to_string(), "5".to_string()),
+            ("drain_timeout_secs".to_string(), "60".to_string()),
+        ],
+        ..ConfigSources::default()
+    };
+    let config = Config::load(&sources).unwrap();
+    assert_eq!(config.pre_stop_delay_secs, 5);
+    assert_eq!(config.drain_timeout_secs, 60);
+
+    let sources = ConfigSources {
+        flags: vec![("drain_timeout_secs".to_string(), "soon".to_string())],
+        ..ConfigSources::default()
+    };
+    let error = Config::load(&sources).unwrap_err().to_string();
+    assert!(error.contains("drain_timeout_secs"), "{}", error);
+}
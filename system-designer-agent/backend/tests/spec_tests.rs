@@ -0,0 +1,162 @@
+mod common;
+
+use axum::{
+    body::Body,
+    http::{HeaderMap, Request, StatusCode},
+};
+use backend::app;
+use backend::spec::{DesignSpec, OutputFormat, SPEC_VERSION};
+use backend::DesignRequest;
+use chrono::{TimeZone, Utc};
+use http_body_util::BodyExt;
+use serde_json::{json, Value};
+use tower::ServiceExt;
+
+/// The shared sample with stray whitespace, blanks and duplicates to normalize
+fn sample_payload() -> Value {
+    let mut payload = common::sample_payload();
+    payload["purpose"]["use_case"] = json!("  Support Bot ");
+    payload["tools"]["apis"] = json!(["Zendesk", " ", "Zendesk ", "Slack"]);
+    payload
+}
+
+async fn generate(uri: &str, accept: Option<&str>) -> (StatusCode, Option<String>, String) {
+    let mut builder = Request::builder()
+        .method("POST")
+        .uri(uri)
+        .header("content-type", "application/json");
+    if let Some(accept) = accept {
+        builder = builder.header("accept", accept);
+    }
+
+    let response = app()
+        .oneshot(
+            builder
+                .body(Body::from(serde_json::to_vec(&sample_payload()).unwrap()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    let status = response.status();
+    let content_type = response
+        .headers()
+        .get("content-type")
+        .map(|v| v.to_str().unwrap().to_string());
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    (
+        status,
+        content_type,
+        String::from_utf8(body.to_vec()).unwrap(),
+    )
+}
+
+#[test]
+fn test_spec_normalizes_request() {
+    let request: DesignRequest = serde_json::from_value(sample_payload()).unwrap();
+    let at = Utc.with_ymd_and_hms(2025, 1, 2, 3, 4, 5).unwrap();
+
+    let spec = DesignSpec::from_request_at(&request, at);
+
+    assert_eq!(spec.spec_version, SPEC_VERSION);
+    assert_eq!(spec.metadata.title, "Support Bot");
+    assert_eq!(spec.metadata.generated_at, at);
+    assert_eq!(spec.design.purpose.use_case, "Support Bot");
+    assert_eq!(spec.design.tools.apis, vec!["Zendesk", "Slack"]);
+}
+
+#[test]
+fn test_spec_keeps_tool_parameters_verbatim() {
+    let parameters = json!({
+        "type": "object",
+        "properties": {
+            "range": {
+                "type": "array",
+                "prefixItems": [{ "type": "integer" }, { "type": "integer" }],
+                "items": false,
+                "examples": [[1, 1], [1, 1]]
+            },
+            "note": { "type": "string", "enum": ["", " urgent ", "", "later"] }
+        }
+    });
+    let mut payload = sample_payload();
+    payload["tools"]["definitions"] = json!([{
+        "name": "schedule",
+        "description": " Book a slot ",
+        "parameters": parameters,
+        "side_effect": "write"
+    }]);
+    let request: DesignRequest = serde_json::from_value(payload).unwrap();
+
+    let spec = DesignSpec::from_request(&request);
+
+    let tool = &spec.design.tools.definitions[0];
+    assert_eq!(tool.description, "Book a slot");
+    assert_eq!(tool.parameters, parameters);
+}
+
+#[test]
+fn test_spec_yaml_round_trip() {
+    let request: DesignRequest = serde_json::from_value(sample_payload()).unwrap();
+    let spec = DesignSpec::from_request(&request);
+
+    let parsed: DesignSpec = serde_yaml::from_str(&spec.to_yaml().unwrap()).unwrap();
+
+    assert_eq!(parsed, spec);
+}
+
+#[tokio::test]
+async fn test_generate_json_via_query() {
+    let (status, content_type, body) = generate("/generate?format=json", None).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(content_type.as_deref(), Some("application/json"));
+    let spec: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(spec["spec_version"], SPEC_VERSION);
+    assert_eq!(spec["metadata"]["title"], "Support Bot");
+    assert_eq!(spec["tools"]["apis"], json!(["Zendesk", "Slack"]));
+    assert!(spec.get("markdown").is_none());
+}
+
+#[tokio::test]
+async fn test_generate_yaml_via_accept_header() {
+    let (status, content_type, body) = generate("/generate", Some("application/yaml")).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(content_type.as_deref(), Some("application/yaml"));
+    assert!(body.contains("spec_version:"));
+    assert!(body.contains("use_case: Support Bot"));
+}
+
+#[tokio::test]
+async fn test_generate_plain_json_accept_keeps_markdown() {
+    let (status, _, body) = generate("/generate", Some("application/json")).await;
+
+    assert_eq!(status, StatusCode::OK);
+    let body_json: Value = serde_json::from_str(&body).unwrap();
+    assert!(body_json["markdown"].as_str().is_some());
+}
+
+#[tokio::test]
+async fn test_generate_unsupported_format() {
+    let (status, _, body) = generate("/generate?format=xml", None).await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let body_json: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(body_json["code"], "BAD_REQUEST");
+}
+
+#[test]
+fn test_format_query_overrides_accept() {
+    let mut headers = HeaderMap::new();
+    headers.insert("accept", "application/yaml".parse().unwrap());
+
+    assert_eq!(
+        OutputFormat::negotiate(Some("json"), &headers).unwrap(),
+        OutputFormat::Json
+    );
+    assert_eq!(
+        OutputFormat::negotiate(None, &headers).unwrap(),
+        OutputFormat::Yaml
+    );
+}
@@ -0,0 +1,233 @@
+mod common;
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+    Router,
+};
+use backend::app;
+use backend::config::Config;
+use backend::store::{DesignStore, MemoryStore, SqliteStore, DEFAULT_WORKSPACE};
+use common::{payload_for, request_for};
+use http_body_util::BodyExt;
+use serde_json::Value;
+use tower::ServiceExt;
+
+async fn exercise_store(store: &dyn DesignStore) {
+    let created = store
+        .create(
+            DEFAULT_WORKSPACE,
+            request_for("First"),
+            "# First".to_string(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(created.title, "First");
+    assert_eq!(created.created_at, created.updated_at);
+
+    let fetched = store
+        .get(DEFAULT_WORKSPACE, &created.id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(fetched, created);
+
+    let updated = store
+        .update(
+            DEFAULT_WORKSPACE,
+            &created.id,
+            request_for("Renamed"),
+            "# Renamed".to_string(),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(updated.title, "Renamed");
+    assert_eq!(updated.revision, 2);
+    assert_eq!(updated.rendered, "# Renamed");
+    assert_eq!(updated.created_at, created.created_at);
+    assert!(updated.updated_at >= created.updated_at);
+
+    let second = store
+        .create(
+            DEFAULT_WORKSPACE,
+            request_for("Second"),
+            "# Second".to_string(),
+        )
+        .await
+        .unwrap();
+    let listed: Vec<String> = store
+        .list(DEFAULT_WORKSPACE)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|s| s.title)
+        .collect();
+    assert_eq!(listed, vec!["Second", "Renamed"]);
+
+    let revisions = store
+        .revisions(DEFAULT_WORKSPACE, &created.id)
+        .await
+        .unwrap()
+        .unwrap();
+    let numbered: Vec<(u32, String)> = revisions
+        .into_iter()
+        .map(|r| (r.revision, r.title))
+        .collect();
+    assert_eq!(
+        numbered,
+        vec![(1, "First".to_string()), (2, "Renamed".to_string())]
+    );
+    let first = store
+        .revision(DEFAULT_WORKSPACE, &created.id, 1)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(first.request, request_for("First"));
+    assert_eq!(first.rendered, "# First");
+    assert!(store
+        .revision(DEFAULT_WORKSPACE, &created.id, 3)
+        .await
+        .unwrap()
+        .is_none());
+
+    assert!(store.delete(DEFAULT_WORKSPACE, &second.id).await.unwrap());
+    assert!(!store.delete(DEFAULT_WORKSPACE, &second.id).await.unwrap());
+    assert!(store
+        .get(DEFAULT_WORKSPACE, &second.id)
+        .await
+        .unwrap()
+        .is_none());
+    assert!(store
+        .revisions(DEFAULT_WORKSPACE, &second.id)
+        .await
+        .unwrap()
+        .is_none());
+    assert!(store
+        .update(
+            DEFAULT_WORKSPACE,
+            "missing",
+            request_for("X"),
+            String::new()
+        )
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn test_memory_store() {
+    exercise_store(&MemoryStore::new()).await;
+}
+
+#[tokio::test]
+async fn test_sqlite_store() {
+    exercise_store(&SqliteStore::open_in_memory().unwrap()).await;
+}
+
+#[tokio::test]
+async fn test_sqlite_store_persists_across_reopen() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("designs.db");
+
+    let id = SqliteStore::open(&path)
+        .unwrap()
+        .create(
+            DEFAULT_WORKSPACE,
+            request_for("Durable"),
+            "# Durable".to_string(),
+        )
+        .await
+        .unwrap()
+        .id;
+
+    let reopened = SqliteStore::open(&path).unwrap();
+    let design = reopened.get(DEFAULT_WORKSPACE, &id).await.unwrap().unwrap();
+    assert_eq!(design.title, "Durable");
+    assert_eq!(design.request, request_for("Durable"));
+}
+
+async fn send(app: &Router, method: &str, uri: &str, body: Option<Value>) -> (StatusCode, Value) {
+    let mut builder = Request::builder().method(method).uri(uri);
+    let body = match body {
+        Some(body) => {
+            builder = builder.header("content-type", "application/json");
+            Body::from(serde_json::to_vec(&body).unwrap())
+        }
+        None => Body::empty(),
+    };
+
+    let response = app
+        .clone()
+        .oneshot(builder.body(body).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    let json = if bytes.is_empty() {
+        Value::Null
+    } else {
+        serde_json::from_slice(&bytes).unwrap()
+    };
+    (status, json)
+}
+
+#[tokio::test]
+async fn test_designs_crud_api() {
+    let app = app();
+
+    let (status, created) = send(&app, "POST", "/designs", Some(payload_for("Support Bot"))).await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(created["title"], "Support Bot");
+    assert!(created["rendered"]
+        .as_str()
+        .unwrap()
+        .starts_with("# System Design Specification: Support Bot"));
+    let id = created["id"].as_str().unwrap().to_string();
+
+    let (status, listed) = send(&app, "GET", "/designs", None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(listed[0]["id"], id.as_str());
+    assert!(listed[0].get("request").is_none());
+
+    let (status, updated) = send(
+        &app,
+        "PUT",
+        &format!("/designs/{}", id),
+        Some(payload_for("Sales Bot")),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(updated["title"], "Sales Bot");
+    assert_eq!(updated["request"]["purpose"]["use_case"], "Sales Bot");
+
+    let (status, _) = send(&app, "DELETE", &format!("/designs/{}", id), None).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+
+    let (status, body) = send(&app, "GET", &format!("/designs/{}", id), None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["code"], "NOT_FOUND");
+}
+
+#[tokio::test]
+async fn test_designs_api_with_sqlite_config() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = Config {
+        database_path: Some(dir.path().join("designs.db")),
+        ..Config::default_test()
+    };
+    let app = backend::app_with_config(&config).unwrap();
+
+    let (status, created) = send(&app, "POST", "/designs", Some(payload_for("Persisted"))).await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    let (status, fetched) = send(
+        &app,
+        "GET",
+        &format!("/designs/{}", created["id"].as_str().unwrap()),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(fetched["title"], "Persisted");
+}
@@ -0,0 +1,233 @@
+use std::fs;
+use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::body::Body;
+use backend::config::{Config, ConfigSources};
+use backend::listener::{self, Listener};
+use backend::tls::TlsConfig;
+use hyper::{Request, StatusCode};
+use hyper_util::rt::TokioIo;
+use rcgen::{BasicConstraints, CertificateParams, CertifiedIssuer, IsCa, KeyPair};
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, ServerName};
+use rustls::{ClientConfig, RootCertStore};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::oneshot;
+use tokio_rustls::TlsConnector;
+
+/// A throwaway certificate authority
+struct Ca {
+    issuer: CertifiedIssuer<'static, KeyPair>,
+}
+
+impl Ca {
+    fn new(name: &str) -> Self {
+        let mut params = CertificateParams::new(Vec::<String>::new()).unwrap();
+        params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        params
+            .distinguished_name
+            .push(rcgen::DnType::CommonName, name);
+        let issuer = CertifiedIssuer::self_signed(params, KeyPair::generate().unwrap()).unwrap();
+        Ca { issuer }
+    }
+
+    /// Certificate and key PEMs for `name`, signed by this CA
+    fn issue(&self, name: &str) -> (String, String) {
+        let key = KeyPair::generate().unwrap();
+        let cert = CertificateParams::new(vec![name.to_string()])
+            .unwrap()
+            .signed_by(&key, &self.issuer)
+            .unwrap();
+        (cert.pem(), key.serialize_pem())
+    }
+
+    fn pem(&self) -> String {
+        self.issuer.pem()
+    }
+
+    fn roots(&self) -> RootCertStore {
+        let mut roots = RootCertStore::empty();
+        roots.add(self.issuer.der().clone()).unwrap();
+        roots
+    }
+}
+
+fn write_server_cert(dir: &Path, ca: &Ca) {
+    let (cert, key) = ca.issue("localhost");
+    fs::write(dir.join("server.pem"), cert).unwrap();
+    fs::write(dir.join("server.key"), key).unwrap();
+}
+
+fn tls_config(dir: &Path, client_ca: Option<&Ca>) -> Config {
+    if let Some(ca) = client_ca {
+        fs::write(dir.join("clients.pem"), ca.pem()).unwrap();
+    }
+    Config {
+        tls_cert: Some(dir.join("server.pem")),
+        tls_key: Some(dir.join("server.key")),
+        tls_client_ca: client_ca.map(|_| dir.join("clients.pem")),
+        ..Config::default_test()
+    }
+}
+
+/// Serve the app over HTTPS on an ephemeral port until the sender drops
+async fn start(config: &Config) -> (SocketAddr, TlsConfig, oneshot::Sender<()>) {
+    let tls = TlsConfig::from_config(config).unwrap().unwrap();
+    let app = backend::app_with_config(config).unwrap();
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (stop, stopped) = oneshot::channel::<()>();
+    tokio::spawn(listener::serve(
+        Listener::Tcp(listener),
+        app,
+        Some(tls.clone()),
+        async {
+            let _ = stopped.await;
+        },
+        Duration::from_secs(5),
+    ));
+    (addr, tls, stop)
+}
+
+/// `GET /health` over TLS, trusting `roots` and optionally presenting a
+/// client certificate
+async fn get_health(
+    addr: SocketAddr,
+    roots: RootCertStore,
+    client: Option<(String, String)>,
+) -> Result<StatusCode, String> {
+    let builder = ClientConfig::builder().with_root_certificates(roots);
+    let config = match client {
+        Some((cert, key)) => builder
+            .with_client_auth_cert(
+                vec![CertificateDer::from_pem_slice(cert.as_bytes()).unwrap()],
+                PrivateKeyDer::from_pem_slice(key.as_bytes()).unwrap(),
+            )
+            .unwrap(),
+        None => builder.with_no_client_auth(),
+    };
+
+    let tcp = TcpStream::connect(addr).await.map_err(|e| e.to_string())?;
+    let stream = TlsConnector::from(Arc::new(config))
+        .connect(ServerName::try_from("localhost").unwrap(), tcp)
+        .await
+        .map_err(|e| e.to_string())?;
+    let (mut sender, conn) = hyper::client::conn::http1::handshake(TokioIo::new(stream))
+        .await
+        .map_err(|e| e.to_string())?;
+    tokio::spawn(conn);
+
+    let request = Request::get("/health")
+        .header("host", "localhost")
+        .body(Body::empty())
+        .unwrap();
+    let response = sender
+        .send_request(request)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(response.status())
+}
+
+#[tokio::test]
+async fn test_https_serves_requests() {
+    let dir = tempfile::tempdir().unwrap();
+    let ca = Ca::new("Test CA");
+    write_server_cert(dir.path(), &ca);
+
+    let (addr, _tls, _stop) = start(&tls_config(dir.path(), None)).await;
+    assert_eq!(get_health(addr, ca.roots(), None).await, Ok(StatusCode::OK));
+
+    // Untrusted server certificates are rejected by the client
+    let other = Ca::new("Other CA");
+    assert!(get_health(addr, other.roots(), None).await.is_err());
+}
+
+#[tokio::test]
+async fn test_client_certificate_required_with_client_ca() {
+    let dir = tempfile::tempdir().unwrap();
+    let ca = Ca::new("Server CA");
+    let clients = Ca::new("Client CA");
+    write_server_cert(dir.path(), &ca);
+
+    let (addr, tls, _stop) = start(&tls_config(dir.path(), Some(&clients))).await;
+    assert!(tls.requires_client_cert());
+
+    let client = clients.issue("reporting-job");
+    assert_eq!(
+        get_health(addr, ca.roots(), Some(client)).await,
+        Ok(StatusCode::OK)
+    );
+    assert!(get_health(addr, ca.roots(), None).await.is_err());
+
+    let stranger = Ca::new("Stranger CA").issue("intruder");
+    assert!(get_health(addr, ca.roots(), Some(stranger)).await.is_err());
+}
+
+#[tokio::test]
+async fn test_certificate_reload() {
+    let dir = tempfile::tempdir().unwrap();
+    let old_ca = Ca::new("Old CA");
+    write_server_cert(dir.path(), &old_ca);
+    let (addr, tls, _stop) = start(&tls_config(dir.path(), None)).await;
+
+    // A broken key is rejected and the running certificate kept
+    fs::write(dir.path().join("server.key"), "not a key").unwrap();
+    assert!(tls.reload().is_err());
+    assert_eq!(
+        get_health(addr, old_ca.roots(), None).await,
+        Ok(StatusCode::OK)
+    );
+
+    let new_ca = Ca::new("New CA");
+    write_server_cert(dir.path(), &new_ca);
+    tls.reload().unwrap();
+    assert_eq!(
+        get_health(addr, new_ca.roots(), None).await,
+        Ok(StatusCode::OK)
+    );
+    assert!(get_health(addr, old_ca.roots(), None).await.is_err());
+}
+
+#[test]
+fn test_tls_settings_are_validated() {
+    let dir = tempfile::tempdir().unwrap();
+    let cert = dir.path().join("server.pem");
+    fs::write(&cert, "").unwrap();
+
+    let errors = Config::load(&ConfigSources {
+        flags: vec![
+            ("tls_cert".to_string(), cert.to_str().unwrap().to_string()),
+            ("tls_client_ca".to_string(), "/no/such/ca.pem".to_string()),
+        ],
+        ..ConfigSources::default()
+    })
+    .unwrap_err()
+    .to_string();
+    assert!(
+        errors.contains("tls_cert and tls_key must be set together"),
+        "{}",
+        errors
+    );
+    assert!(
+        errors.contains("tls_client_ca: /no/such/ca.pem is not a file"),
+        "{}",
+        errors
+    );
+
+    // An empty certificate file passes validation but cannot be loaded
+    let config = Config {
+        tls_cert: Some(cert.clone()),
+        tls_key: Some(cert),
+        ..Config::default_test()
+    };
+    assert_eq!(
+        TlsConfig::from_config(&config).err().unwrap().exit_code(),
+        78
+    );
+    assert!(TlsConfig::from_config(&Config::default_test())
+        .unwrap()
+        .is_none());
+}
@@ -0,0 +1,210 @@
+mod common;
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use backend::diagram::mermaid_flowchart;
+use backend::import::parse_design_markdown;
+use backend::lint::Linter;
+use backend::tools::{function_calling, AuthMethod, SideEffect};
+use backend::{app, core_generate_design, validate_design_request, DesignRequest};
+use http_body_util::BodyExt;
+use serde_json::{json, Value};
+use tower::ServiceExt;
+
+/// The shared sample with a read and a destructive tool, and no guardrails
+fn sample_payload() -> Value {
+    let mut payload = common::sample_payload();
+    payload["prompt"]["guardrails"] = json!("");
+    payload["tools"]["definitions"] = json!([
+        {
+            "name": "lookup_order",
+            "description": "Find an order by id | email",
+            "parameters": {
+                "type": "object",
+                "properties": { "order_id": { "type": "string" } },
+                "required": ["order_id"]
+            },
+            "auth": "api_key",
+            "side_effect": "read",
+            "rate_limit": "100 requests/minute"
+        },
+        {
+            "name": "refund_order",
+            "parameters": { "type": "object", "properties": { "amount": { "type": "number" } } },
+            "auth": "oauth2",
+            "side_effect": "destructive"
+        }
+    ]);
+    payload
+}
+
+fn sample_request() -> DesignRequest {
+    serde_json::from_value(sample_payload()).unwrap()
+}
+
+#[test]
+fn test_defaults_for_optional_tool_fields() {
+    let request = sample_request();
+    let refund = &request.tools.definitions[1];
+
+    assert_eq!(refund.description, "");
+    assert_eq!(refund.rate_limit, "");
+    assert_eq!(refund.side_effect, SideEffect::Destructive);
+    assert_eq!(refund.auth, AuthMethod::OAuth2);
+    validate_design_request(&request).unwrap();
+
+    let mut payload = sample_payload();
+    payload["tools"]["definitions"] = json!([{ "name": "ping", "side_effect": "read" }]);
+    let request: DesignRequest = serde_json::from_value(payload).unwrap();
+    let ping = &request.tools.definitions[0];
+    assert_eq!(ping.auth, AuthMethod::None);
+    assert_eq!(
+        ping.parameters,
+        json!({ "type": "object", "properties": {} })
+    );
+}
+
+#[test]
+fn test_invalid_definitions_are_rejected() {
+    let mut request = sample_request();
+    request.tools.definitions[0].name = "look up".to_string();
+    request.tools.definitions[1].parameters = json!({ "type": "string" });
+    assert!(validate_design_request(&request).is_err());
+    let fields = common::invalid_fields(&request);
+    assert!(fields.contains(&"tools.definitions[0].name".to_string()));
+    assert!(fields.contains(&"tools.definitions[1].parameters".to_string()));
+
+    let mut request = sample_request();
+    request.tools.definitions[1].name = "lookup_order".to_string();
+    assert!(validate_design_request(&request).is_err());
+    assert_eq!(common::invalid_fields(&request), ["tools.definitions"]);
+}
+
+#[tokio::test]
+async fn test_side_effect_is_required() {
+    let mut payload = sample_payload();
+    payload["tools"]["definitions"] = json!([{ "name": "ping" }]);
+
+    let response = app()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/generate")
+                .header("content-type", "application/json")
+                .body(Body::from(payload.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body: Value = serde_json::from_slice(&body).unwrap();
+    assert!(body["error"]
+        .as_str()
+        .unwrap()
+        .contains("missing field `side_effect`"));
+}
+
+#[test]
+fn test_function_calling_format() {
+    let request = sample_request();
+
+    assert_eq!(
+        function_calling(&request.tools.definitions),
+        json!([
+            {
+                "type": "function",
+                "function": {
+                    "name": "lookup_order",
+                    "description": "Find an order by id | email",
+                    "parameters": {
+                        "type": "object",
+                        "properties": { "order_id": { "type": "string" } },
+                        "required": ["order_id"]
+                    }
+                }
+            },
+            {
+                "type": "function",
+                "function": {
+                    "name": "refund_order",
+                    "parameters": { "type": "object", "properties": { "amount": { "type": "number" } } }
+                }
+            }
+        ])
+    );
+}
+
+#[test]
+fn test_catalog_rendered_and_imported() {
+    let request = sample_request();
+    let markdown = core_generate_design(&request);
+
+    assert!(markdown.contains(
+        "### Tool Catalog\n\
+         | Tool | Description | Side Effect | Auth | Rate Limit |\n\
+         |------|-------------|-------------|------|------------|\n\
+         | `lookup_order` | Find an order by id \\| email | read | api_key | 100 requests/minute |\n\
+         | `refund_order` |  | destructive | oauth2 |  |\n"
+    ));
+    let block =
+        serde_json::to_string_pretty(&function_calling(&request.tools.definitions)).unwrap();
+    assert!(markdown.contains(&format!("#### Function Calling\n```json\n{}\n```\n", block)));
+
+    let report = parse_design_markdown(&markdown).unwrap();
+    assert!(report.issues.is_empty(), "{:?}", report.issues);
+    assert_eq!(report.request, request);
+}
+
+#[test]
+fn test_no_catalog_without_definitions() {
+    let mut request = sample_request();
+    request.tools.definitions.clear();
+
+    let markdown = core_generate_design(&request);
+    assert!(!markdown.contains("Tool Catalog"));
+    assert!(markdown.contains("- **Custom Functions:** \n\n## 5. Memory Systems"));
+}
+
+#[test]
+fn test_mismatched_catalog_is_reported() {
+    let markdown = core_generate_design(&sample_request());
+    let markdown = markdown.replace("| `refund_order` |  | destructive | oauth2 |  |\n", "");
+
+    let report = parse_design_markdown(&markdown).unwrap();
+    assert_eq!(report.issues.len(), 1);
+    assert_eq!(report.issues[0].field.as_deref(), Some("Tool Catalog"));
+    assert!(report.request.tools.definitions.is_empty());
+}
+
+#[test]
+fn test_destructive_tools_need_guardrails() {
+    let mut request = sample_request();
+
+    let findings = Linter::new().lint(&request);
+    let finding = findings
+        .iter()
+        .find(|f| f.rule_id == "destructive-tool-without-guardrails")
+        .unwrap();
+    assert_eq!(finding.path, "prompt.guardrails");
+    assert!(finding.message.contains("refund_order"));
+    assert!(!finding.message.contains("lookup_order"));
+
+    request.prompt.guardrails = "Refunds above $50 need human approval".to_string();
+    assert!(Linter::new()
+        .lint(&request)
+        .iter()
+        .all(|f| f.rule_id != "destructive-tool-without-guardrails"));
+}
+
+#[test]
+fn test_definitions_drawn_as_tools() {
+    let chart = mermaid_flowchart(&sample_request());
+
+    assert!(chart.contains("tool_0[\"Tool: lookup_order\"]"));
+    assert!(chart.contains("orchestrator -->|\"action\"| tool_1"));
+    assert!(chart.contains("tool_1 -.->|\"observation\"| orchestrator"));
+}
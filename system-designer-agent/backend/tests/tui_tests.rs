@@ -0,0 +1,154 @@
+use backend::tui::wizard::{Screen, STEPS};
+use backend::tui::{ui, Wizard};
+use backend::DesignRequest;
+use ratatui::backend::TestBackend;
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::Terminal;
+
+fn press(wizard: &mut Wizard, code: KeyCode) {
+    wizard.handle_key(KeyEvent::new(code, KeyModifiers::NONE));
+}
+
+fn type_text(wizard: &mut Wizard, text: &str) {
+    for c in text.chars() {
+        press(wizard, KeyCode::Char(c));
+    }
+}
+
+fn wizard() -> Wizard {
+    Wizard::new(DesignRequest::default(), "DESIGN_SPEC.md".into())
+}
+
+#[test]
+fn test_steps_mirror_web_wizard() {
+    let names: Vec<&str> = STEPS.iter().map(|step| step.name).collect();
+    assert_eq!(
+        names,
+        [
+            "Purpose & Scope",
+            "System Prompt Design",
+            "Choose LLM",
+            "Tools & Integrations",
+            "Memory Systems",
+            "Orchestration",
+            "User Interface",
+            "Testing & Evals"
+        ]
+    );
+}
+
+#[test]
+fn test_typing_fills_request_fields() {
+    let mut wizard = wizard();
+    type_text(&mut wizard, "Support Bot");
+    press(&mut wizard, KeyCode::Backspace);
+    press(&mut wizard, KeyCode::Char('t'));
+    assert_eq!(wizard.request.purpose.use_case, "Support Bot");
+
+    // Jump to Tools & Integrations and fill the comma separated API list
+    for _ in 0..3 {
+        press(&mut wizard, KeyCode::PageDown);
+    }
+    assert_eq!(wizard.current_step().name, "Tools & Integrations");
+    type_text(&mut wizard, "Stripe, ");
+    assert_eq!(wizard.buffer, "Stripe, ");
+    type_text(&mut wizard, "Slack");
+    assert_eq!(wizard.request.tools.apis, vec!["Stripe", "Slack"]);
+
+    // Memory flags toggle with space
+    press(&mut wizard, KeyCode::PageDown);
+    press(&mut wizard, KeyCode::Tab);
+    press(&mut wizard, KeyCode::Char(' '));
+    assert!(wizard.request.memory.working_memory);
+    assert!(!wizard.request.memory.episodic);
+}
+
+#[test]
+fn test_validation_errors_block_next_step() {
+    let mut wizard = wizard();
+    type_text(&mut wizard, &"x".repeat(501));
+
+    let step = wizard.current_step();
+    let errors = wizard.errors_for(step, &step.fields[0]);
+    assert_eq!(errors, vec!["length must be at most 500"]);
+
+    press(&mut wizard, KeyCode::PageDown);
+    assert_eq!(wizard.step, 0);
+    assert!(wizard
+        .status
+        .as_deref()
+        .unwrap()
+        .contains("Purpose & Scope"));
+
+    press(&mut wizard, KeyCode::Backspace);
+    press(&mut wizard, KeyCode::PageDown);
+    assert_eq!(wizard.step, 1);
+}
+
+#[test]
+fn test_preview_and_save() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = dir.path().join("DESIGN_SPEC.md");
+    let mut wizard = Wizard::new(DesignRequest::default(), output.clone());
+    type_text(&mut wizard, "Support Bot");
+
+    for _ in 0..STEPS.len() {
+        press(&mut wizard, KeyCode::PageDown);
+    }
+    assert_eq!(wizard.screen, Screen::Preview);
+    assert!(wizard
+        .preview
+        .starts_with("# System Design Specification: Support Bot"));
+
+    press(&mut wizard, KeyCode::Char('s'));
+    assert_eq!(std::fs::read_to_string(&output).unwrap(), wizard.preview);
+
+    press(&mut wizard, KeyCode::Char('b'));
+    assert_eq!(wizard.screen, Screen::Form);
+    press(&mut wizard, KeyCode::Esc);
+    assert!(wizard.quit);
+}
+
+#[test]
+fn test_quit_asks_before_discarding_answers() {
+    let mut untouched = wizard();
+    press(&mut untouched, KeyCode::Esc);
+    assert!(untouched.quit);
+
+    let mut wizard = wizard();
+    type_text(&mut wizard, "Support Bot");
+    press(&mut wizard, KeyCode::Esc);
+    assert!(!wizard.quit);
+    assert!(wizard
+        .status
+        .as_deref()
+        .unwrap()
+        .contains("Unsaved answers"));
+
+    // Another key keeps editing and the next Esc asks again
+    press(&mut wizard, KeyCode::Tab);
+    press(&mut wizard, KeyCode::Esc);
+    assert!(!wizard.quit);
+    press(&mut wizard, KeyCode::Esc);
+    assert!(wizard.quit);
+}
+
+#[test]
+fn test_draws_current_step() {
+    let mut wizard = wizard();
+    type_text(&mut wizard, &"x".repeat(501));
+
+    let mut terminal = Terminal::new(TestBackend::new(120, 40)).unwrap();
+    terminal.draw(|frame| ui::draw(frame, &wizard)).unwrap();
+
+    let screen: String = terminal
+        .backend()
+        .buffer()
+        .content()
+        .iter()
+        .map(|cell| cell.symbol())
+        .collect();
+    assert!(screen.contains("Step 1 of 8: Purpose & Scope"));
+    assert!(screen.contains("8. Testing & Evals"));
+    assert!(screen.contains("length must be at most 500"));
+}
@@ -0,0 +1,429 @@
+mod common;
+
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+    Router,
+};
+use backend::app_with_config;
+use backend::config::Config;
+use backend::middleware::auth::hash_api_key;
+use backend::store::DEFAULT_WORKSPACE;
+use common::payload_for;
+use http_body_util::BodyExt;
+use jsonwebtoken::{encode, EncodingKey, Header};
+use serde_json::{json, Value};
+use tower::ServiceExt;
+
+async fn send(
+    app: &Router,
+    method: &str,
+    uri: &str,
+    headers: &[(&str, &str)],
+    body: Option<Value>,
+) -> (StatusCode, Value) {
+    let mut request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("content-type", "application/json");
+    for (name, value) in headers {
+        request = request.header(*name, *value);
+    }
+    let body = body.map_or_else(Body::empty, |b| Body::from(b.to_string()));
+
+    let response = app
+        .clone()
+        .oneshot(request.body(body).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    (
+        status,
+        serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+    )
+}
+
+async fn create_workspace(app: &Router, headers: &[(&str, &str)], id: &str) {
+    let (status, body) = send(
+        app,
+        "POST",
+        "/admin/workspaces",
+        headers,
+        Some(json!({ "id": id, "name": id })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED, "{}", body);
+}
+
+fn open_app() -> Router {
+    app_with_config(&Config::default_test()).unwrap()
+}
+
+fn secured_app(dir: &tempfile::TempDir) -> Router {
+    let jwks = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/auth/jwks.json");
+    let path = dir.path().join("auth.toml");
+    std::fs::write(
+        &path,
+        format!(
+            r#"
+[[api_keys]]
+name = "team-a-ci"
+hash = "{}"
+scopes = ["designs:read", "designs:write"]
+workspace = "team-a"
+
+[[api_keys]]
+name = "shared"
+hash = "{}"
+scopes = ["designs:read"]
+
+[[api_keys]]
+name = "ops"
+hash = "{}"
+scopes = ["admin"]
+workspace = "team-a"
+
+[jwt]
+jwks = "{}"
+"#,
+            hash_api_key("team-a-key"),
+            hash_api_key("shared-key"),
+            hash_api_key("ops-key"),
+            jwks.display()
+        ),
+    )
+    .unwrap();
+
+    let config = Config {
+        auth_config: Some(path),
+        ..Config::default_test()
+    };
+    app_with_config(&config).unwrap()
+}
+
+#[tokio::test]
+async fn test_designs_are_isolated_per_workspace() {
+    let app = open_app();
+    create_workspace(&app, &[], "team-a").await;
+    let team_a = [("x-workspace-id", "team-a")];
+
+    let (status, created) = send(
+        &app,
+        "POST",
+        "/designs",
+        &team_a,
+        Some(payload_for("Team A Bot")),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let uri = format!("/designs/{}", created["id"].as_str().unwrap());
+
+    let (status, listed) = send(&app, "GET", "/designs", &team_a, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(listed.as_array().unwrap().len(), 1);
+
+    // Without the header the default workspace is used and sees nothing
+    let (_, listed) = send(&app, "GET", "/designs", &[], None).await;
+    assert_eq!(listed, json!([]));
+    for method in ["GET", "DELETE"] {
+        let (status, _) = send(&app, method, &uri, &[], None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND, "{}", method);
+    }
+    let (status, _) = send(&app, "GET", &format!("{}/revisions", uri), &[], None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _) = send(&app, "PUT", &uri, &[], Some(payload_for("Hijack"))).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, design) = send(&app, "GET", &uri, &team_a, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(design["title"], "Team A Bot");
+
+    let (status, body) = send(
+        &app,
+        "GET",
+        "/designs",
+        &[("x-workspace-id", "nobody")],
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["error"], "Workspace nobody not found");
+}
+
+#[tokio::test]
+async fn test_principal_workspace_overrides_header() {
+    let dir = tempfile::tempdir().unwrap();
+    let app = secured_app(&dir);
+    let ops = ("x-api-key", "ops-key");
+    create_workspace(&app, &[ops], "team-a").await;
+    create_workspace(&app, &[ops], "team-b").await;
+
+    let team_a_key = ("x-api-key", "team-a-key");
+    let (status, _) = send(
+        &app,
+        "POST",
+        "/designs",
+        &[team_a_key],
+        Some(payload_for("Bound")),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    // A bound key cannot reach into another workspace
+    let (status, body) = send(
+        &app,
+        "GET",
+        "/designs",
+        &[team_a_key, ("x-workspace-id", "team-b")],
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["error"], "Not allowed to access workspace team-b");
+
+    // Naming its own workspace is fine
+    let (status, listed) = send(
+        &app,
+        "GET",
+        "/designs",
+        &[team_a_key, ("x-workspace-id", "team-a")],
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(listed[0]["title"], "Bound");
+
+    // Admins may switch workspaces
+    let (_, listed) = send(
+        &app,
+        "GET",
+        "/designs",
+        &[ops, ("x-workspace-id", "team-b")],
+        None,
+    )
+    .await;
+    assert_eq!(listed, json!([]));
+    let (_, listed) = send(&app, "GET", "/designs", &[ops], None).await;
+    assert_eq!(listed[0]["title"], "Bound");
+
+    // Unbound keys stay in the default workspace
+    let shared = ("x-api-key", "shared-key");
+    let (status, body) = send(
+        &app,
+        "GET",
+        "/designs",
+        &[shared, ("x-workspace-id", "team-a")],
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["error"], "Not allowed to access workspace team-a");
+    let (status, listed) = send(
+        &app,
+        "GET",
+        "/designs",
+        &[shared, ("x-workspace-id", DEFAULT_WORKSPACE)],
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(listed, json!([]));
+    let (status, listed) = send(&app, "GET", "/designs", &[shared], None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(listed, json!([]));
+
+    // JWTs carry the binding in the `workspace` claim
+    let exp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+        + 300;
+    let mut header = Header::new(jsonwebtoken::Algorithm::HS256);
+    header.kid = Some("hs-1".to_string());
+    let token = encode(
+        &header,
+        &json!({ "sub": "bob", "exp": exp, "scope": "designs:read", "workspace": "team-b" }),
+        &EncodingKey::from_secret(b"test-hmac-secret-for-system-designer"),
+    )
+    .unwrap();
+    let bearer = format!("Bearer {}", token);
+    let (status, listed) = send(&app, "GET", "/designs", &[("authorization", &bearer)], None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(listed, json!([]));
+
+    // A token without the claim cannot pick a workspace either
+    let token = encode(
+        &header,
+        &json!({ "sub": "carol", "exp": exp, "scope": "designs:read" }),
+        &EncodingKey::from_secret(b"test-hmac-secret-for-system-designer"),
+    )
+    .unwrap();
+    let bearer = format!("Bearer {}", token);
+    let (status, _) = send(
+        &app,
+        "GET",
+        "/designs",
+        &[("authorization", &bearer), ("x-workspace-id", "team-a")],
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_archived_workspace_is_read_only() {
+    let app = open_app();
+    create_workspace(&app, &[], "team-a").await;
+    let team_a = [("x-workspace-id", "team-a")];
+
+    let (_, created) = send(
+        &app,
+        "POST",
+        "/designs",
+        &team_a,
+        Some(payload_for("Legacy")),
+    )
+    .await;
+    let uri = format!("/designs/{}", created["id"].as_str().unwrap());
+
+    let (status, archived) =
+        send(&app, "POST", "/admin/workspaces/team-a/archive", &[], None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(archived["archived_at"].is_string());
+
+    let (status, _) = send(&app, "GET", &uri, &team_a, None).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, body) = send(&app, "PUT", &uri, &team_a, Some(payload_for("New"))).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(body["error"], "Workspace team-a is archived");
+    let (status, _) = send(&app, "DELETE", &uri, &team_a, None).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = send(&app, "POST", "/designs", &team_a, Some(payload_for("New"))).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let (status, _) = send(&app, "POST", "/admin/workspaces/default/archive", &[], None).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = send(&app, "POST", "/admin/workspaces/nobody/archive", &[], None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_workspace_admin_api() {
+    let dir = tempfile::tempdir().unwrap();
+    let app = secured_app(&dir);
+    let ops = ("x-api-key", "ops-key");
+
+    create_workspace(&app, &[ops], "team-a").await;
+
+    let (status, body) = send(
+        &app,
+        "POST",
+        "/admin/workspaces",
+        &[ops],
+        Some(json!({ "id": "team-a", "name": "Again" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["code"], "CONFLICT");
+
+    let (status, body) = send(
+        &app,
+        "POST",
+        "/admin/workspaces",
+        &[ops],
+        Some(json!({ "id": "../Team A", "name": "Bad" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert!(body["error"].as_str().unwrap().starts_with("id: "));
+
+    let (status, listed) = send(&app, "GET", "/admin/workspaces", &[ops], None).await;
+    assert_eq!(status, StatusCode::OK);
+    let ids: Vec<&str> = listed
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|w| w["id"].as_str().unwrap())
+        .collect();
+    assert_eq!(ids, vec!["default", "team-a"]);
+
+    let (status, _) = send(
+        &app,
+        "GET",
+        "/admin/workspaces",
+        &[("x-api-key", "team-a-key")],
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_workspace_templates_and_lint_rules() {
+    let dir = tempfile::tempdir().unwrap();
+    let team_a = dir.path().join("team-a");
+    std::fs::create_dir_all(team_a.join("templates")).unwrap();
+    std::fs::write(
+        team_a.join("templates/brief.md"),
+        "Brief: {{ purpose.use_case }}",
+    )
+    .unwrap();
+    std::fs::write(
+        team_a.join("lint.toml"),
+        "[rules.no-evals]\nenabled = false\n",
+    )
+    .unwrap();
+
+    let config = Config {
+        workspace_dir: Some(dir.path().to_path_buf()),
+        ..Config::default_test()
+    };
+    let app = app_with_config(&config).unwrap();
+    create_workspace(&app, &[], "team-a").await;
+    let headers = [("x-workspace-id", "team-a")];
+
+    let (_, templates) = send(&app, "GET", "/templates", &headers, None).await;
+    assert_eq!(templates["templates"], json!(["brief", "default"]));
+    let (_, templates) = send(&app, "GET", "/templates", &[], None).await;
+    assert_eq!(templates["templates"], json!(["default"]));
+
+    let (status, body) = send(
+        &app,
+        "POST",
+        "/generate?template=brief",
+        &headers,
+        Some(payload_for("Scoped")),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["markdown"], "Brief: Scoped");
+    let (status, _) = send(
+        &app,
+        "POST",
+        "/generate?template=brief",
+        &[],
+        Some(payload_for("Scoped")),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let rule_ids = |body: Value| -> Vec<String> {
+        body["findings"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|f| f["rule_id"].as_str().unwrap().to_string())
+            .collect()
+    };
+    let mut no_evals = payload_for("X");
+    no_evals["testing"]["evals"] = json!("");
+    let (_, body) = send(&app, "POST", "/lint", &headers, Some(no_evals.clone())).await;
+    assert!(!rule_ids(body).contains(&"no-evals".to_string()));
+    let (_, body) = send(&app, "POST", "/lint", &[], Some(no_evals)).await;
+    assert!(rule_ids(body).contains(&"no-evals".to_string()));
+}
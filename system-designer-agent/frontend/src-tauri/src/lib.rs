@@ -1,16 +1,158 @@
-use backend::{core_generate_design, validate_design_request, DesignRequest};
+use backend::config::{Config, ConfigSources};
+use backend::diagram::mermaid_flowchart;
+use backend::error::AppError;
+use backend::import::{parse_design_markdown, ImportReport};
+use backend::lint::{Finding, Linter};
+use backend::migrate;
+use backend::renderer::{Renderer, DEFAULT_TEMPLATE};
+use backend::spec::{DesignSpec, OutputFormat};
+use backend::{validate_design_request, DesignRequest};
+use serde_json::Value;
+
+/// Templates and lint rules configured like the server: config file, `.env`
+/// and `APP_*` variables such as `APP_TEMPLATE_DIR` and `APP_LINT_CONFIG`
+fn design_tools(sources: &ConfigSources) -> Result<(Renderer, Linter), AppError> {
+    let config = Config::load(sources)?;
+    Ok((
+        Renderer::from_config(&config)?,
+        Linter::from_config(&config)?,
+    ))
+}
+
+/// Upgrade a payload from the webview to the current schema and deserialize it
+fn design_request(payload: Value) -> Result<DesignRequest, String> {
+    migrate::design_request(payload).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn generate_design_command(
+    renderer: tauri::State<'_, Renderer>,
+    linter: tauri::State<'_, Linter>,
+    payload: Value,
+    template: Option<String>,
+) -> Result<String, String> {
+    let payload = design_request(payload)?;
+    validate_design_request(&payload)?;
+    let findings = linter.lint(&payload);
+    renderer
+        .render(
+            template.as_deref().unwrap_or(DEFAULT_TEMPLATE),
+            &payload,
+            &findings,
+        )
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn lint_design_command(
+    linter: tauri::State<'_, Linter>,
+    payload: Value,
+) -> Result<Vec<Finding>, String> {
+    Ok(linter.lint(&design_request(payload)?))
+}
 
 #[tauri::command]
-fn generate_design_command(payload: DesignRequest) -> Result<String, String> {
+fn generate_spec_command(
+    renderer: tauri::State<'_, Renderer>,
+    linter: tauri::State<'_, Linter>,
+    payload: Value,
+    format: String,
+) -> Result<String, String> {
+    let payload = design_request(payload)?;
     validate_design_request(&payload)?;
-    Ok(core_generate_design(&payload))
+    match format.parse::<OutputFormat>().map_err(|e| e.to_string())? {
+        OutputFormat::Markdown => {
+            renderer.render(DEFAULT_TEMPLATE, &payload, &linter.lint(&payload))
+        }
+        OutputFormat::Json => DesignSpec::from_request(&payload).to_json(),
+        OutputFormat::Yaml => DesignSpec::from_request(&payload).to_yaml(),
+    }
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn import_design_command(markdown: String) -> Result<ImportReport, String> {
+    let report = parse_design_markdown(&markdown).map_err(|e| e.to_string())?;
+    validate_design_request(&report.request)?;
+    Ok(report)
+}
+
+#[tauri::command]
+fn diagram_command(payload: Value) -> Result<String, String> {
+    Ok(mermaid_flowchart(&design_request(payload)?))
+}
+
+#[tauri::command]
+fn list_templates_command(renderer: tauri::State<'_, Renderer>) -> Vec<String> {
+    renderer.template_names()
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    let (renderer, linter) = match design_tools(&ConfigSources::from_process(None, Vec::new())) {
+        Ok(tools) => tools,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(e.exit_code().into());
+        }
+    };
+
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
-        .invoke_handler(tauri::generate_handler![generate_design_command])
+        .manage(renderer)
+        .manage(linter)
+        .invoke_handler(tauri::generate_handler![
+            diagram_command,
+            generate_design_command,
+            generate_spec_command,
+            import_design_command,
+            lint_design_command,
+            list_templates_command
+        ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_design_tools_follow_app_variables() {
+        let dir = std::env::temp_dir().join(format!("system-designer-app-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("brief.md"), "Brief: {{ purpose.use_case }}").unwrap();
+        let lint_config = dir.join("lint.toml");
+        std::fs::write(&lint_config, "[rules.no-evals]\nenabled = false\n").unwrap();
+
+        std::env::set_var("APP_TEMPLATE_DIR", &dir);
+        std::env::set_var("APP_LINT_CONFIG", &lint_config);
+        let tools = design_tools(&ConfigSources::from_process(None, Vec::new()));
+        std::env::remove_var("APP_TEMPLATE_DIR");
+        std::env::remove_var("APP_LINT_CONFIG");
+        let (renderer, linter) = tools.unwrap();
+
+        assert!(renderer.template_names().contains(&"brief".to_string()));
+        let request = design_request(serde_json::json!({
+            "purpose": { "use_case": "Support Bot", "user_needs": "", "success_criteria": "", "constraints": "" },
+            "prompt": { "goals": "", "role": "", "instructions": "", "guardrails": "" },
+            "model": { "base_model": "", "parameters": "", "context_window": "", "cost_latency_tradeoff": "" },
+            "tools": { "apis": [], "mcp_servers": [], "custom_functions": "" },
+            "memory": { "episodic": false, "working_memory": false, "vector_db": "", "sql_db": "" },
+            "orchestration": { "workflow": "", "triggers": "", "error_handling": "" },
+            "interface": { "platform": "", "interaction_mode": "", "api_endpoint": "" },
+            "testing": { "unit_tests": [], "quality_metrics": "", "evals": "" }
+        }))
+        .unwrap();
+        assert!(linter
+            .lint(&request)
+            .iter()
+            .all(|finding| finding.rule_id != "no-evals"));
+        assert_eq!(
+            renderer.render("brief", &request, &[]).unwrap(),
+            "Brief: Support Bot"
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
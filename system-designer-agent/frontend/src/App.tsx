@@ -16,6 +16,7 @@ import {
 
 import { Input, TextArea, CheckboxCard, LoadingSpinner, ErrorAlert } from '@/components/ui';
 import { useWizard, useFormData } from '@/hooks';
+import { knownValues } from '@/config';
 import { generateDesign, downloadMarkdown } from '@/services/api';
 import type { Step, ApiError, LoadingState } from '@/types';
 
@@ -269,6 +270,7 @@ function App() {
                     label="Base Model"
                     value={formData.model.base_model}
                     onChange={v => updateField('model', 'base_model', v)}
+                    suggestions={knownValues.baseModel}
                     placeholder="Claude 3.5, GPT-4o..."
                   />
                   <Input
@@ -343,6 +345,12 @@ function App() {
                     onChange={v => updateField('memory', 'sql_db', v)}
                     placeholder="PostgreSQL, MongoDB..."
                   />
+                  <Input
+                    label="File Storage"
+                    value={formData.memory.file_storage}
+                    onChange={v => updateField('memory', 'file_storage', v)}
+                    placeholder="S3, GCS, local disk..."
+                  />
                 </div>
               )}
 
@@ -352,6 +360,7 @@ function App() {
                     label="Workflow Patterns"
                     value={formData.orchestration.workflow}
                     onChange={v => updateField('orchestration', 'workflow', v)}
+                    suggestions={knownValues.workflow}
                     placeholder="Router, Sequential, Graph-based..."
                   />
                   <Input
@@ -366,6 +375,12 @@ function App() {
                     onChange={v => updateField('orchestration', 'error_handling', v)}
                     placeholder="How do we handle hallucinations or API failures?"
                   />
+                  <Input
+                    label="Message Queues"
+                    value={formData.orchestration.message_queues}
+                    onChange={v => updateField('orchestration', 'message_queues', v)}
+                    placeholder="Kafka, RabbitMQ, SQS..."
+                  />
                 </div>
               )}
 
@@ -375,12 +390,14 @@ function App() {
                     label="Platform"
                     value={formData.interface.platform}
                     onChange={v => updateField('interface', 'platform', v)}
+                    suggestions={knownValues.platform}
                     placeholder="Web App, CLI, Slack Bot..."
                   />
                   <Input
                     label="Interaction Mode"
                     value={formData.interface.interaction_mode}
                     onChange={v => updateField('interface', 'interaction_mode', v)}
+                    suggestions={knownValues.interactionMode}
                     placeholder="Chat, Proactive, Voice..."
                   />
                   <div className="md:col-span-2">
@@ -402,6 +419,12 @@ function App() {
                     onChange={v => updateField('testing', 'unit_tests', v)}
                     placeholder="e.g. Identity check, Tool failure (comma separated)"
                   />
+                  <Input
+                    label="Latency Testing"
+                    value={formData.testing.latency_testing}
+                    onChange={v => updateField('testing', 'latency_testing', v)}
+                    placeholder="p95 under 2s, load test with 100 concurrent users..."
+                  />
                   <Input
                     label="Quality Metrics"
                     value={formData.testing.quality_metrics}
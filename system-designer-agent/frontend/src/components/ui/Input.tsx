@@ -1,9 +1,10 @@
 import { useId } from 'react';
 import type { InputProps } from '@/types';
 
-export function Input({ label, value, onChange, placeholder, error, required }: InputProps) {
+export function Input({ label, value, onChange, placeholder, error, required, suggestions }: InputProps) {
   const id = useId();
   const errorId = `${id}-error`;
+  const listId = `${id}-suggestions`;
 
   return (
     <div className="flex flex-col group">
@@ -20,6 +21,7 @@ export function Input({ label, value, onChange, placeholder, error, required }:
         value={value}
         onChange={(e) => onChange(e.target.value)}
         placeholder={placeholder}
+        list={suggestions ? listId : undefined}
         aria-required={required}
         aria-invalid={!!error}
         aria-describedby={error ? errorId : undefined}
@@ -31,6 +33,11 @@ export function Input({ label, value, onChange, placeholder, error, required }:
           ${error ? 'border-red-400 focus:border-red-500 focus:ring-red-500/10' : 'border-slate-100'}
         `}
       />
+      {suggestions && (
+        <datalist id={listId}>
+          {suggestions.map(option => <option key={option} value={option} />)}
+        </datalist>
+      )}
       {error && (
         <p
           id={errorId}
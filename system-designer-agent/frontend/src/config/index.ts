@@ -1,5 +1,6 @@
 export const config = {
   apiUrl: import.meta.env.VITE_API_URL || 'http://localhost:3000',
+  apiKey: import.meta.env.VITE_API_KEY,
   isDev: import.meta.env.DEV,
   isProd: import.meta.env.PROD,
 } as const;
@@ -7,3 +8,15 @@ export const config = {
 export const endpoints = {
   generate: `${config.apiUrl}/generate`,
 } as const;
+
+// Values the backend recognizes and describes in the generated document
+// (the `examples` in backend/schema/design-request.json); other text is kept as typed
+export const knownValues = {
+  baseModel: [
+    'Claude 3.5 Sonnet', 'Claude 3.5 Haiku', 'Claude 3 Opus', 'GPT-4o', 'GPT-4o mini',
+    'GPT-4', 'Gemini 1.5 Pro', 'Gemini 1.5 Flash', 'Llama 3', 'Mistral Large',
+  ],
+  workflow: ['ReAct', 'Plan-and-Execute', 'Router', 'Supervisor-Worker', 'Sequential'],
+  platform: ['Web', 'Slack', 'Microsoft Teams', 'Discord', 'CLI', 'API', 'Mobile'],
+  interactionMode: ['Chat', 'Voice', 'Proactive', 'Batch', 'Autonomous'],
+} as const;
@@ -1,7 +1,11 @@
-import { endpoints } from '@/config';
+import { config, endpoints } from '@/config';
 import type { FormData, GenerateResponse, ApiError } from '@/types';
 
+// DesignRequest shape this client sends; the backend upgrades older versions
+const SCHEMA_VERSION = 3;
+
 interface GeneratePayload {
+  schema_version: number;
   purpose: FormData['purpose'];
   prompt: FormData['prompt'];
   model: FormData['model'];
@@ -23,6 +27,7 @@ interface GeneratePayload {
 
 function transformFormDataToPayload(formData: FormData): GeneratePayload {
   return {
+    schema_version: SCHEMA_VERSION,
     ...formData,
     tools: {
       ...formData.tools,
@@ -38,17 +43,25 @@ function transformFormDataToPayload(formData: FormData): GeneratePayload {
 
 export async function generateDesign(formData: FormData): Promise<GenerateResponse> {
   const payload = transformFormDataToPayload(formData);
+  const requestId = crypto.randomUUID();
 
   const response = await fetch(endpoints.generate, {
     method: 'POST',
-    headers: { 'Content-Type': 'application/json' },
+    headers: {
+      'Content-Type': 'application/json',
+      'X-Request-Id': requestId,
+      ...(config.apiKey ? { 'X-Api-Key': config.apiKey } : {}),
+    },
     body: JSON.stringify(payload),
   });
 
   if (!response.ok) {
+    const body = await response.json().catch(() => null);
+    const id = body?.request_id ?? response.headers.get('X-Request-Id') ?? requestId;
     const error: ApiError = {
-      message: `Failed to generate design: ${response.statusText}`,
+      message: `Failed to generate design: ${body?.error ?? response.statusText} (request ${id})`,
       status: response.status,
+      requestId: id,
     };
     throw error;
   }
@@ -77,13 +77,25 @@ export interface Step {
 }
 
 // API Types
+export type FindingSeverity = 'error' | 'warning' | 'info';
+
+export interface Finding {
+  rule_id: string;
+  severity: FindingSeverity;
+  path: string;
+  message: string;
+}
+
 export interface GenerateResponse {
   markdown: string;
+  findings: Finding[];
 }
 
 export interface ApiError {
   message: string;
   status?: number;
+  /** `X-Request-Id` of the failed call, for matching backend logs */
+  requestId?: string;
 }
 
 // Component Props
@@ -94,6 +106,7 @@ export interface InputProps {
   placeholder?: string;
   error?: string;
   required?: boolean;
+  suggestions?: readonly string[];
 }
 
 export interface TextAreaProps extends InputProps {